@@ -1,3 +1,8 @@
+use crate::crossrefs::CrossReferences;
+use crate::lexicon::Lexicon;
+use crate::reference::Reference;
+use crate::session::Session;
+use crate::translations::TranslationRegistry;
 use crate::{bible::Bible, prelude::*};
 
 /// App data that is persisted between states.
@@ -8,12 +13,54 @@ use crate::{bible::Bible, prelude::*};
 /// persistent data will be passed between states.
 pub struct PersistentAppData {
     pub bible: Arc<Bible>,
+    pub translations: Arc<TranslationRegistry>,
+    /// Translations shown next to `bible` in the parallel view.
+    pub parallel: Vec<Arc<Bible>>,
+    /// Strong's dictionaries for the words of tagged translations.
+    pub lexicon: Arc<Lexicon>,
+    /// The imported cross reference dataset.
+    pub cross_references: Arc<CrossReferences>,
+    /// Where the last reader state was, so that the next one can continue there.
+    pub position: Option<Reference>,
+    /// Colour the words of Christ.
+    pub red_letter: bool,
+    /// The session to restore in the next reader state, see `position` for where to read.
+    pub restore: Option<Session>,
 }
 
 impl PersistentAppData {
-    pub fn from_translation(translation: &str) -> Result<PersistentAppData> {
+    /// Explains why red letter does nothing, if the translation does not mark who is speaking.
+    pub fn red_letter_notice(&self) -> Option<String> {
+        (self.red_letter && !self.bible.get_has_speakers()).then(|| {
+            format!(
+                "{} does not mark who is speaking, red letter is not available",
+                self.bible.get_translation().name
+            )
+        })
+    }
+
+    pub fn from_translation(
+        translations: Arc<TranslationRegistry>,
+        translation: &str,
+    ) -> Result<PersistentAppData> {
+        let info = translations.get(translation)?;
+        let lexicon = Lexicon::load().unwrap_or_else(|e| {
+            error!("Failed to load the lexicon: {e}");
+            Lexicon::in_memory()
+        });
+        let cross_references = CrossReferences::load().unwrap_or_else(|e| {
+            error!("Failed to load the cross references: {e}");
+            CrossReferences::in_memory()
+        });
         Ok(PersistentAppData {
-            bible: Arc::new(Bible::from_translation(translation)?),
+            bible: Arc::new(Bible::from_translation(info)?),
+            translations,
+            parallel: Vec::new(),
+            lexicon: Arc::new(lexicon),
+            cross_references: Arc::new(cross_references),
+            position: None,
+            red_letter: false,
+            restore: None,
         })
     }
 }
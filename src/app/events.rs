@@ -1,5 +1,8 @@
-use crossterm::event::{KeyCode, KeyModifiers};
+use crate::error::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Deserializer, de};
 use std::collections::HashMap;
+use std::str::FromStr;
 
 pub enum AppEvent {
     /// Special event fired at start of app.
@@ -10,6 +13,10 @@ pub enum AppEvent {
     Focus,
     /// Window defocus, specific to components.
     Defocus,
+    /// Raw key press, only sent while the state captures text input.
+    KeyInput(KeyEvent),
+    /// The text of the last `EditRequest` after the editor was closed.
+    Edited(Result<String>),
 }
 
 /// Actions that can be performed by the user.  They all should have key mappings.
@@ -18,16 +25,200 @@ pub enum UserAction {
     Quit,
     MoveUp,
     MoveDown,
+    PageUp,
+    PageDown,
     IncrementWindow,
     DecrementWindow,
     /// `usize`: The index of the window.
     JumpToWindow(usize),
     OpenReader,
+    /// Open the command prompt, used to jump to references.
+    OpenCommand,
+    OpenSearch,
+    /// Look up the word under the word cursor in the concordance, or prompt for a word.
+    OpenConcordance,
+    /// Open the picker to switch between installed translations.
+    OpenTranslations,
+    /// Toggle between the reader and the parallel translation view.
+    OpenParallel,
+    /// Toggle colouring the words of Christ.
+    ToggleRedLetter,
+    /// Move the verse cursor.
+    NextVerse,
+    PrevVerse,
+    /// Move the word cursor, e.g. to look up words in the Strong's panel.
+    NextWord,
+    PrevWord,
+    /// Start or stop selecting verses from the cursor.
+    ToggleVisual,
+    /// Copy the selected verses or words.
+    Yank,
+    /// Bookmark the selected verses.
+    AddBookmark,
+    /// Write a note about the selected verses, or edit the selected note.
+    EditNote,
+    /// Highlight the selected verses or words in a category.
+    Highlight,
+    /// Open the overview of the highlights.
+    OpenHighlights,
+    /// Cycle the category or the book that the focused list is filtered by.
+    FilterCategory,
+    FilterBook,
+    Rename,
+    Delete,
+    /// Change the order of the focused list, e.g. cross references by votes or in canon order.
+    Sort,
+    /// Confirm the selected item, e.g. a search result.
+    Select,
+    /// Close the focused panel.
+    Close,
 }
 
-/// Mappings of keys -> actions.
-///
-/// TODO: This should go to a config file.
+impl FromStr for UserAction {
+    type Err = String;
+
+    /// Parses the snake case name of the action, e.g. "page_down" or "jump_to_window_1".
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if let Some(window) = s.strip_prefix("jump_to_window_") {
+            return match window.parse::<usize>() {
+                Ok(n) if n >= 1 => Ok(UserAction::JumpToWindow(n - 1)),
+                _ => Err(format!("invalid window {window:?}, windows start at 1")),
+            };
+        }
+
+        Ok(match s {
+            "quit" => UserAction::Quit,
+            "move_up" => UserAction::MoveUp,
+            "move_down" => UserAction::MoveDown,
+            "page_up" => UserAction::PageUp,
+            "page_down" => UserAction::PageDown,
+            "increment_window" => UserAction::IncrementWindow,
+            "decrement_window" => UserAction::DecrementWindow,
+            "open_reader" => UserAction::OpenReader,
+            "open_command" => UserAction::OpenCommand,
+            "open_search" => UserAction::OpenSearch,
+            "open_concordance" => UserAction::OpenConcordance,
+            "open_translations" => UserAction::OpenTranslations,
+            "open_parallel" => UserAction::OpenParallel,
+            "toggle_red_letter" => UserAction::ToggleRedLetter,
+            "next_verse" => UserAction::NextVerse,
+            "prev_verse" => UserAction::PrevVerse,
+            "next_word" => UserAction::NextWord,
+            "prev_word" => UserAction::PrevWord,
+            "toggle_visual" => UserAction::ToggleVisual,
+            "yank" => UserAction::Yank,
+            "add_bookmark" => UserAction::AddBookmark,
+            "edit_note" => UserAction::EditNote,
+            "highlight" => UserAction::Highlight,
+            "open_highlights" => UserAction::OpenHighlights,
+            "filter_category" => UserAction::FilterCategory,
+            "filter_book" => UserAction::FilterBook,
+            "rename" => UserAction::Rename,
+            "delete" => UserAction::Delete,
+            "sort" => UserAction::Sort,
+            "select" => UserAction::Select,
+            "close" => UserAction::Close,
+            _ => return Err(format!("unknown action {s:?}")),
+        })
+    }
+}
+
+/// The action a key is bound to in the config file, "none" removes a default binding.
+pub struct BoundAction(pub Option<UserAction>);
+
+impl<'de> Deserialize<'de> for BoundAction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "none" => Ok(BoundAction(None)),
+            action => action
+                .parse()
+                .map(|a| BoundAction(Some(a)))
+                .map_err(de::Error::custom),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A key with its modifiers, as written in the config file, e.g. "j", "ctrl+d" or "shift+tab".
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding(pub KeyCode, pub KeyModifiers);
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        // "+" on its own is a key, not a separator.
+        let (modifiers, key) = match s.rsplit_once('+') {
+            Some((modifiers, "")) => (modifiers.strip_suffix('+').unwrap_or(modifiers), "+"),
+            Some((modifiers, key)) => (modifiers, key),
+            None => ("", s),
+        };
+
+        let mut mods = KeyModifiers::NONE;
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            mods |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "shift" => KeyModifiers::SHIFT,
+                "alt" => KeyModifiers::ALT,
+                _ => return Err(format!("unknown modifier {modifier:?}")),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_uppercase() => {
+                // Terminals send shift with upper case letters.
+                mods |= KeyModifiers::SHIFT;
+                KeyCode::Char(c)
+            }
+            (Some(c), None) if mods.contains(KeyModifiers::SHIFT) && c.is_ascii_lowercase() => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" if mods.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => {
+                    mods |= KeyModifiers::SHIFT;
+                    KeyCode::BackTab
+                }
+                "backspace" => KeyCode::Backspace,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "insert" => KeyCode::Insert,
+                "delete" => KeyCode::Delete,
+                f => match f.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key {key:?}")),
+                },
+            },
+        };
+        Ok(KeyBinding(code, mods))
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyBinding {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Mappings of keys -> actions, the defaults can be changed in the `[keys]` section of the config
+/// file.
 pub struct KeyMap(HashMap<(KeyCode, KeyModifiers), UserAction>);
 
 impl KeyMap {
@@ -38,6 +229,49 @@ impl KeyMap {
         map.insert((KeyCode::Char('r'), none), UserAction::OpenReader);
         map.insert((KeyCode::Char('j'), none), UserAction::MoveDown);
         map.insert((KeyCode::Char('k'), none), UserAction::MoveUp);
+        map.insert((KeyCode::PageDown, none), UserAction::PageDown);
+        map.insert((KeyCode::PageUp, none), UserAction::PageUp);
+        map.insert(
+            (KeyCode::Char('d'), KeyModifiers::CONTROL),
+            UserAction::PageDown,
+        );
+        map.insert(
+            (KeyCode::Char('u'), KeyModifiers::CONTROL),
+            UserAction::PageUp,
+        );
+        map.insert((KeyCode::Char(':'), none), UserAction::OpenCommand);
+        map.insert((KeyCode::Char('g'), none), UserAction::OpenCommand);
+        map.insert((KeyCode::Char('/'), none), UserAction::OpenSearch);
+        map.insert((KeyCode::Char('*'), none), UserAction::OpenConcordance);
+        map.insert((KeyCode::Char('t'), none), UserAction::OpenTranslations);
+        map.insert((KeyCode::Char('p'), none), UserAction::OpenParallel);
+        map.insert(
+            (KeyCode::Char('R'), KeyModifiers::SHIFT),
+            UserAction::ToggleRedLetter,
+        );
+        map.insert((KeyCode::Char('l'), none), UserAction::NextVerse);
+        map.insert((KeyCode::Char('h'), none), UserAction::PrevVerse);
+        map.insert((KeyCode::Char('w'), none), UserAction::NextWord);
+        map.insert((KeyCode::Char('b'), none), UserAction::PrevWord);
+        map.insert((KeyCode::Char('v'), none), UserAction::ToggleVisual);
+        map.insert((KeyCode::Char('y'), none), UserAction::Yank);
+        map.insert((KeyCode::Char('m'), none), UserAction::AddBookmark);
+        map.insert((KeyCode::Char('n'), none), UserAction::EditNote);
+        map.insert(
+            (KeyCode::Char('H'), KeyModifiers::SHIFT),
+            UserAction::Highlight,
+        );
+        map.insert((KeyCode::Char('o'), none), UserAction::OpenHighlights);
+        map.insert((KeyCode::Char('f'), none), UserAction::FilterCategory);
+        map.insert(
+            (KeyCode::Char('F'), KeyModifiers::SHIFT),
+            UserAction::FilterBook,
+        );
+        map.insert((KeyCode::Char('c'), none), UserAction::Rename);
+        map.insert((KeyCode::Char('d'), none), UserAction::Delete);
+        map.insert((KeyCode::Char('s'), none), UserAction::Sort);
+        map.insert((KeyCode::Enter, none), UserAction::Select);
+        map.insert((KeyCode::Esc, none), UserAction::Close);
         map.insert((KeyCode::Tab, none), UserAction::IncrementWindow);
         map.insert(
             (KeyCode::BackTab, KeyModifiers::SHIFT),
@@ -48,10 +282,91 @@ impl KeyMap {
         map.insert((KeyCode::Char('3'), none), UserAction::JumpToWindow(2));
         map.insert((KeyCode::Char('4'), none), UserAction::JumpToWindow(3));
         map.insert((KeyCode::Char('5'), none), UserAction::JumpToWindow(4));
+        map.insert((KeyCode::Char('6'), none), UserAction::JumpToWindow(5));
+        map.insert((KeyCode::Char('7'), none), UserAction::JumpToWindow(6));
         KeyMap(map)
     }
 
+    /// The default mappings with the bindings of the config file applied on top.
+    pub fn from_bindings(bindings: &HashMap<KeyBinding, BoundAction>) -> Self {
+        let mut map = Self::default();
+        for (KeyBinding(code, modifiers), BoundAction(action)) in bindings {
+            match action {
+                Some(action) => map.0.insert((*code, *modifiers), *action),
+                None => map.0.remove(&(*code, *modifiers)),
+            };
+        }
+        map
+    }
+
     pub fn get(&self, key: &KeyCode, modifiers: KeyModifiers) -> Option<UserAction> {
-        self.0.get(&(*key, modifiers)).copied()
+        self.0
+            .get(&(*key, modifiers))
+            .copied()
+            .or_else(|| match key {
+                // Whether symbols come with shift depends on the terminal and keyboard layout.
+                KeyCode::Char(c) if modifiers == KeyModifiers::SHIFT && !c.is_alphabetic() => {
+                    self.0.get(&(*key, KeyModifiers::NONE)).copied()
+                }
+                _ => None,
+            })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(s: &str) -> (KeyCode, KeyModifiers) {
+        let KeyBinding(code, modifiers) = s.parse().unwrap();
+        (code, modifiers)
+    }
+
+    #[test]
+    fn parses_key_bindings() {
+        let (none, shift) = (KeyModifiers::NONE, KeyModifiers::SHIFT);
+        assert_eq!(binding("j"), (KeyCode::Char('j'), none));
+        assert_eq!(
+            binding("ctrl+d"),
+            (KeyCode::Char('d'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            binding("Ctrl+Alt+x"),
+            (
+                KeyCode::Char('x'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            )
+        );
+        // Terminals send upper case letters with shift, and shift+tab as a key of its own.
+        assert_eq!(binding("F"), (KeyCode::Char('F'), shift));
+        assert_eq!(binding("shift+f"), (KeyCode::Char('F'), shift));
+        assert_eq!(binding("shift+tab"), (KeyCode::BackTab, shift));
+        assert_eq!(binding("backtab"), (KeyCode::BackTab, shift));
+        assert_eq!(binding("f5"), (KeyCode::F(5), none));
+        assert_eq!(binding("space"), (KeyCode::Char(' '), none));
+        // A "+" on its own is the key.
+        assert_eq!(binding("+"), (KeyCode::Char('+'), none));
+        assert_eq!(
+            binding("ctrl++"),
+            (KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+
+        for invalid in ["hyper+x", "f13", "", "pgdn"] {
+            assert!(invalid.parse::<KeyBinding>().is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn symbols_are_found_with_and_without_shift() {
+        let map = KeyMap::default();
+        for modifiers in [KeyModifiers::NONE, KeyModifiers::SHIFT] {
+            assert!(matches!(
+                map.get(&KeyCode::Char(':'), modifiers),
+                Some(UserAction::OpenCommand)
+            ));
+        }
+        assert!(map.get(&KeyCode::Char('q'), KeyModifiers::SHIFT).is_none());
     }
 }
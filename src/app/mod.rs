@@ -2,4 +2,5 @@ pub mod data;
 pub mod events;
 pub mod state;
 pub mod state_default_reader;
+pub mod state_parallel_reader;
 pub mod state_dashboard;
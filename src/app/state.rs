@@ -1,6 +1,8 @@
 use crate::app::data::PersistentAppData;
 use crate::app::state_dashboard::Dashboard;
 use crate::app::state_default_reader::DefaultReader;
+use crate::app::state_parallel_reader::ParallelReader;
+use crate::editor::EditRequest;
 use crate::prelude::*;
 use ratatui::Frame;
 
@@ -11,9 +13,11 @@ pub trait AppStateTrait {
     fn render(&mut self, f: &mut Frame) -> Result<()>;
 }
 
+#[allow(clippy::large_enum_variant)]
 pub enum AppStateEnum {
     Dashboard(Dashboard),
     DefaultReader(DefaultReader),
+    ParallelReader(ParallelReader),
     Exit,
 }
 
@@ -22,6 +26,7 @@ impl AppStateEnum {
         match self {
             AppStateEnum::Dashboard(s) => s.get_app_data(),
             AppStateEnum::DefaultReader(s) => s.get_app_data(),
+            AppStateEnum::ParallelReader(s) => s.get_app_data(),
             AppStateEnum::Exit => {
                 panic!("Exit should not request data")
             }
@@ -32,16 +37,35 @@ impl AppStateEnum {
         match self {
             AppStateEnum::Dashboard(s) => s.update(event),
             AppStateEnum::DefaultReader(s) => s.update(event),
+            AppStateEnum::ParallelReader(s) => s.update(event),
             AppStateEnum::Exit => {
                 panic!("Exit should not update")
             }
         }
     }
 
+    /// Whether raw key presses should be sent instead of mapped actions.
+    pub fn captures_input(&self) -> bool {
+        match self {
+            AppStateEnum::DefaultReader(s) => s.captures_input(),
+            AppStateEnum::ParallelReader(s) => s.captures_input(),
+            _ => false,
+        }
+    }
+
+    /// Text for the user to edit, the terminal interface is suspended while the editor is open.
+    pub fn take_edit_request(&mut self) -> Option<EditRequest> {
+        match self {
+            AppStateEnum::DefaultReader(s) => s.take_edit_request(),
+            _ => None,
+        }
+    }
+
     pub fn render(&mut self, f: &mut Frame) -> Result<()> {
         match self {
             AppStateEnum::Dashboard(s) => s.render(f),
             AppStateEnum::DefaultReader(s) => s.render(f),
+            AppStateEnum::ParallelReader(s) => s.render(f),
             AppStateEnum::Exit => {
                 panic!("Exit should not render")
             }
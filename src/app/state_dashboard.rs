@@ -2,21 +2,72 @@ use crate::app::data::PersistentAppData;
 use crate::app::events::UserAction;
 use crate::app::state::{AppStateEnum, AppStateTrait};
 use crate::app::state_default_reader::DefaultReader;
+use crate::app::state_parallel_reader::ParallelReader;
+use crate::bible::Bible;
 use crate::components::Component;
 use crate::components::splash_screen::SplashScreen;
+use crate::components::translation_picker::TranslationPicker;
 use crate::prelude::*;
+use crate::session::Session;
+use crate::translations::TranslationRegistry;
 use ratatui::Frame;
 
 pub struct Dashboard {
     pub app_data: Option<PersistentAppData>,
+    pub translations: Arc<TranslationRegistry>,
     pub splash: SplashScreen,
+    pub picker: TranslationPicker,
+    /// Where reading stopped the last time.
+    pub session: Option<Session>,
 }
 
 impl Dashboard {
     pub fn new() -> Self {
+        let translations = Arc::new(TranslationRegistry::discover());
         Dashboard {
             app_data: None,
-            splash: SplashScreen,
+            picker: TranslationPicker::new(translations.clone()),
+            translations,
+            splash: SplashScreen {
+                error: settings().error.clone(),
+                ..SplashScreen::new()
+            },
+            session: None,
+        }
+    }
+
+    fn load_translation(&mut self, name: &str) -> Result<()> {
+        let app_data = PersistentAppData::from_translation(self.translations.clone(), name)?;
+        self.splash.translation = Some(app_data.bible.get_translation().name.clone());
+        self.app_data = Some(app_data);
+        Ok(())
+    }
+
+    /// Opens the reader that was open the last time, where it was, in the loaded translation.
+    fn continue_reading(mut self) -> Result<AppStateEnum> {
+        let (Some(session), Some(app_data)) = (self.session.take(), self.app_data.as_mut()) else {
+            return Ok(AppStateEnum::Dashboard(self));
+        };
+        info!("Continuing at {}", session.reference());
+
+        for name in &session.parallel {
+            let bible = self
+                .translations
+                .get(name)
+                .and_then(Bible::from_translation);
+            match bible {
+                Ok(bible) => app_data.parallel.push(Arc::new(bible)),
+                Err(e) => warn!("Failed to load {name} for the parallel view: {e}"),
+            }
+        }
+        app_data.position = Some(session.reference());
+        app_data.red_letter = session.red_letter;
+        let parallel_open = session.parallel_open;
+        app_data.restore = Some(session);
+
+        match parallel_open {
+            true => ParallelReader::from_state(AppStateEnum::Dashboard(self)),
+            false => DefaultReader::from_state(AppStateEnum::Dashboard(self)),
         }
     }
 }
@@ -29,20 +80,56 @@ impl AppStateTrait for Dashboard {
     fn update(mut self, event: AppEvent) -> Result<AppStateEnum> {
         self.splash.update(&event)?;
 
+        // The picker is modal.
+        if self.picker.is_open() {
+            self.picker.update(&event)?;
+            if let Some(info) = self.picker.take_picked()
+                && self.splash.translation.as_ref() != Some(&info.name)
+            {
+                self.splash.error = None;
+                if let Err(e) = self.load_translation(&info.name) {
+                    warn!("Failed to load {}: {e}", info.name);
+                    self.splash.error = Some(e.to_string());
+                }
+            }
+            return Ok(AppStateEnum::Dashboard(self));
+        }
+
         match event {
             AppEvent::AppStart => {
-                self.app_data = Some(PersistentAppData::from_translation("KVJ")?);
+                // Start in the translation of the last session, if it is still installed.
+                self.session = Session::load();
+                let restored = match self.session.as_ref().map(|s| s.translation.clone()) {
+                    Some(name) => self
+                        .load_translation(&name)
+                        .inspect_err(|e| warn!("Failed to load {name} of the last session: {e}"))
+                        .is_ok(),
+                    None => false,
+                };
+                if !restored {
+                    let default = self.translations.get_default()?.name.clone();
+                    self.load_translation(&default)?;
+                }
+                self.splash.continue_reading = self
+                    .session
+                    .as_ref()
+                    .map(|s| format!("{} ({})", s.reference(), s.translation));
             }
             AppEvent::UserAction(action) => match action {
                 UserAction::Quit => return Ok(AppStateEnum::Exit),
                 UserAction::OpenReader => {
                     return DefaultReader::from_state(AppStateEnum::Dashboard(self));
                 }
+                UserAction::Select if self.session.is_some() => return self.continue_reading(),
+                UserAction::OpenTranslations => {
+                    let current = self.splash.translation.as_deref();
+                    self.picker.open(&current.into_iter().collect::<Vec<_>>());
+                }
                 _ => {}
             },
             _ => {}
         }
-        return Ok(AppStateEnum::Dashboard(self));
+        Ok(AppStateEnum::Dashboard(self))
     }
 
     fn render(&mut self, f: &mut Frame) -> Result<()> {
@@ -50,6 +137,7 @@ impl AppStateTrait for Dashboard {
         let buf = f.buffer_mut();
 
         self.splash.render(area, buf)?;
+        self.picker.render(area, buf)?;
         Ok(())
     }
 
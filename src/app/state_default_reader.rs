@@ -1,24 +1,47 @@
 use crate::app::data::PersistentAppData;
 use crate::app::events::{AppEvent, UserAction};
 use crate::app::state::{AppStateEnum, AppStateTrait};
+use crate::app::state_parallel_reader::ParallelReader;
+use crate::bible::Bible;
+use crate::bookmarks::Bookmarks;
+use crate::clipboard::ClipboardSink;
 use crate::components::Component;
-use crate::components::book_reader::BookReader;
+use crate::components::book_reader::{BookReader, HighlightTarget};
+use crate::components::bookmarks::BookmarksPanel;
 use crate::components::books_view::BooksView;
+use crate::components::command_prompt::{CommandPrompt, PromptKind};
+use crate::components::concordance::ConcordancePanel;
 use crate::components::footer::LogosFooter;
+use crate::components::highlight_picker::HighlightPicker;
+use crate::components::highlights::HighlightsPanel;
+use crate::components::notes::NotesPanel;
 use crate::components::references::References;
+use crate::components::search_panel::SearchPanel;
 use crate::components::strongs::Strongs;
+use crate::components::translation_picker::TranslationPicker;
+use crate::editor::EditRequest;
+use crate::highlights::{Highlight, Highlights, WordRange};
+use crate::notes::{Notes, osis_reference};
 use crate::prelude::*;
+use crate::reference::{Reference, format_range};
+use crate::session::{Panel, Session};
+use crate::store::{Store, Stored};
+use crate::translations::TranslationInfo;
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Layout};
+use serde::{Deserialize, Serialize};
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum FocusedWindow {
     Books,
     Reader,
     References,
     Strongs,
+    Search,
+    Bookmarks,
+    Notes,
 }
 
 impl FocusedWindow {
@@ -27,16 +50,22 @@ impl FocusedWindow {
             Self::Books => Self::Reader,
             Self::Reader => Self::References,
             Self::References => Self::Strongs,
-            Self::Strongs => Self::Books,
+            Self::Strongs => Self::Search,
+            Self::Search => Self::Bookmarks,
+            Self::Bookmarks => Self::Notes,
+            Self::Notes => Self::Books,
         }
     }
 
     fn prev(self) -> Self {
         match self {
-            Self::Books => Self::Strongs,
+            Self::Books => Self::Notes,
             Self::Reader => Self::Books,
             Self::References => Self::Reader,
             Self::Strongs => Self::References,
+            Self::Search => Self::Strongs,
+            Self::Bookmarks => Self::Search,
+            Self::Notes => Self::Bookmarks,
         }
     }
 }
@@ -48,8 +77,19 @@ pub struct DefaultReader {
     pub books_view: BooksView,
     pub book_reader: BookReader,
     pub footer: LogosFooter,
+    pub prompt: CommandPrompt,
     pub references: References,
     pub strongs: Strongs,
+    pub search: SearchPanel,
+    /// Shown in place of the search results.
+    pub concordance: ConcordancePanel,
+    /// Shown in place of the search results.
+    pub highlights: HighlightsPanel,
+    pub bookmarks: BookmarksPanel,
+    pub notes: NotesPanel,
+    pub picker: TranslationPicker,
+    pub highlight_picker: HighlightPicker,
+    pub clipboard: ClipboardSink,
     pub focused: FocusedWindow,
 }
 
@@ -59,10 +99,37 @@ impl DefaultReader {
         self.book_reader.update(&AppEvent::Defocus)?;
         self.references.update(&AppEvent::Defocus)?;
         self.strongs.update(&AppEvent::Defocus)?;
+        self.search.update(&AppEvent::Defocus)?;
+        self.concordance.update(&AppEvent::Defocus)?;
+        self.highlights.update(&AppEvent::Defocus)?;
+        self.bookmarks.update(&AppEvent::Defocus)?;
+        self.notes.update(&AppEvent::Defocus)?;
         Ok(())
     }
 
+    /// Whether the window is shown and can be focused.
+    fn is_available(&self, window: FocusedWindow) -> bool {
+        match window {
+            FocusedWindow::Search => {
+                self.search.is_open() || self.concordance.is_open() || self.highlights.is_open()
+            }
+            _ => true,
+        }
+    }
+
+    /// Focuses the next available window in the direction of `step`.
+    fn cycle_focus(&mut self, step: fn(FocusedWindow) -> FocusedWindow) -> Result<()> {
+        let mut window = step(self.focused);
+        while !self.is_available(window) {
+            window = step(window);
+        }
+        self.focus(window)
+    }
+
     fn focus(&mut self, window: FocusedWindow) -> Result<()> {
+        if !self.is_available(window) {
+            return Ok(());
+        }
         self.defocus_all()?;
         self.focused = window;
         match window {
@@ -70,40 +137,358 @@ impl DefaultReader {
             FocusedWindow::Reader => self.book_reader.update(&AppEvent::Focus),
             FocusedWindow::References => self.references.update(&AppEvent::Focus),
             FocusedWindow::Strongs => self.strongs.update(&AppEvent::Focus),
+            FocusedWindow::Search if self.highlights.is_open() => {
+                self.highlights.update(&AppEvent::Focus)
+            }
+            FocusedWindow::Search if self.concordance.is_open() => {
+                self.concordance.update(&AppEvent::Focus)
+            }
+            FocusedWindow::Search => self.search.update(&AppEvent::Focus),
+            FocusedWindow::Bookmarks => self.bookmarks.update(&AppEvent::Focus),
+            FocusedWindow::Notes => self.notes.update(&AppEvent::Focus),
+        }
+    }
+
+    pub fn captures_input(&self) -> bool {
+        self.prompt.is_active() || self.bookmarks.is_editing()
+    }
+
+    /// The note to open in the editor, if one was asked for.
+    pub fn take_edit_request(&mut self) -> Option<EditRequest> {
+        self.notes.take_edit_request()
+    }
+
+    /// Saves the note that was being edited.
+    fn finish_edit(&mut self, edited: Result<String>) {
+        match edited.and_then(|text| self.notes.finish_edit(&text)) {
+            Ok(message) => self.prompt.set_message(message),
+            Err(e) => {
+                warn!("Failed to edit the note: {e}");
+                self.prompt.set_error(e.to_string());
+            }
+        }
+        self.book_reader.set_notes(self.notes.notes());
+    }
+
+    /// Highlights the verses or words in the category, or removes their highlights.
+    fn apply_highlight(
+        &mut self,
+        target: HighlightTarget,
+        category: Option<String>,
+    ) -> Result<String> {
+        let location = format_range(&target.book, target.start, target.end);
+        let versification = self.app_data.bible.get_versification();
+        let book = target.book.as_str();
+        let start = versification.map_to_kjv(book, target.start.0, target.start.1);
+        let end = versification.map_to_kjv(book, target.end.0, target.end.1);
+        let Some(category) = category else {
+            return Ok(match self.highlights.clear(book, start, end)? {
+                0 => format!("No highlights of {location}"),
+                1 => format!("Removed the highlight of {location}"),
+                removed => format!("Removed {removed} highlights of {location}"),
+            });
+        };
+
+        let words = target.words.map(|words| WordRange {
+            translation: self.app_data.bible.get_translation().name.clone(),
+            start: words.start,
+            end: words.end,
+        });
+        let message = match &words {
+            Some(_) => format!("Highlighted words of {location} as {category}"),
+            None => format!("Highlighted {location} as {category}"),
+        };
+        self.highlights.add(Highlight {
+            reference: osis_reference(book, start, end),
+            category,
+            words,
+        })?;
+        Ok(message)
+    }
+
+    fn show_reference(&mut self, reference: Reference) -> Result<()> {
+        info!("Jumping to {reference}");
+        self.books_view.select_book(&reference.book);
+        self.book_reader.jump_to(reference);
+        self.focus(FocusedWindow::Reader)
+    }
+
+    /// Loads another translation, keeping the book, chapter and verse that is being read.
+    fn switch_translation(&mut self, info: &TranslationInfo) -> Result<()> {
+        let bible = Arc::new(Bible::from_translation(info)?);
+        let (book, chapter, verse) = self.book_reader.position();
+        let book = match bible.get_book_index(book) {
+            Ok(_) => book.to_string(),
+            Err(_) => {
+                warn!("{book} is not in {}, opening the first book", info.name);
+                bible.get_books()[0].clone()
+            }
+        };
+        info!("Switching to {} at {book} {chapter}:{verse}", info.name);
+
+        self.books_view = BooksView::new(&bible);
+        self.books_view.select_book(&book);
+        self.book_reader = BookReader::new(bible.clone(), book.clone());
+        self.book_reader.scroll_to(&book, chapter, verse);
+        self.book_reader.red_letter = self.app_data.red_letter;
+        self.search = SearchPanel::new(bible.clone());
+        self.concordance = ConcordancePanel::new(bible.clone());
+        self.references.set_bible(bible.clone());
+        self.notes.set_bible(bible.clone());
+        self.highlights.set_bible(bible.clone());
+        self.app_data.bible = bible;
+        if let Some(notice) = self.app_data.red_letter_notice() {
+            self.prompt.set_message(notice);
+        }
+
+        // The new components need to know about focus, search and the concordance are closed now
+        // but the highlights are kept open.
+        let focused = match self.focused {
+            FocusedWindow::Search if !self.highlights.is_open() => FocusedWindow::Reader,
+            window => window,
+        };
+        self.focus(focused)
+    }
+
+    /// Where the user is reading, to continue there the next time.
+    pub fn session(&self) -> Session {
+        let (book, chapter, verse) = self.book_reader.position();
+        Session {
+            translation: self.app_data.bible.get_translation().name.clone(),
+            parallel: self
+                .app_data
+                .parallel
+                .iter()
+                .map(|b| b.get_translation().name.clone())
+                .collect(),
+            parallel_open: false,
+            book: book.to_string(),
+            chapter,
+            verse,
+            scrolled_offset: self.book_reader.scrolled_offset(),
+            focused: self.focused,
+            panel: self.open_panel(),
+            red_letter: self.app_data.red_letter,
+        }
+    }
+
+    /// The panel that is shown below the reader, to open it again the next time.
+    fn open_panel(&self) -> Option<Panel> {
+        if self.highlights.is_open() {
+            Some(Panel::Highlights)
+        } else if let Some(term) = self.concordance.query() {
+            Some(Panel::Concordance(term.to_string()))
+        } else {
+            self.search
+                .query()
+                .map(|query| Panel::Search(query.to_string()))
+        }
+    }
+
+    /// Opens the panel of the last session again.
+    fn restore_panel(&mut self, panel: &Panel) -> Result<()> {
+        match panel {
+            Panel::Search(query) => self.search.search(query),
+            Panel::Concordance(term) => self.concordance.lookup(term),
+            Panel::Highlights => {
+                self.highlights.open();
+                Ok(())
+            }
+        }
+    }
+
+    fn submit_prompt(&mut self, kind: PromptKind, input: &str) -> Result<()> {
+        match kind {
+            PromptKind::Jump => {
+                let reference = Reference::parse(input, &self.app_data.bible)?;
+                self.show_reference(reference)
+            }
+            PromptKind::Search => {
+                self.search.search(input)?;
+                self.concordance.close();
+                self.highlights.close();
+                self.focus(FocusedWindow::Search)
+            }
+            PromptKind::Concordance => {
+                self.concordance.lookup(input)?;
+                self.search.close();
+                self.highlights.close();
+                self.focus(FocusedWindow::Search)
+            }
         }
     }
+
+    /// Looks up the word under the word cursor in the concordance, by its Strong's number if it
+    /// is tagged.  Without a word cursor it is prompted for.
+    fn open_concordance(&mut self) -> Result<()> {
+        let Some(word) = self.book_reader.cursor_word() else {
+            self.prompt.open(PromptKind::Concordance);
+            return Ok(());
+        };
+        let term = match word.tag.as_ref().and_then(|tag| tag.strongs().next()) {
+            Some(number) => number,
+            None => word.parts.iter().map(|(text, _)| text.as_str()).collect(),
+        };
+        self.submit_prompt(PromptKind::Concordance, &term)
+    }
 }
 
 impl AppStateTrait for DefaultReader {
     fn from_state(state: AppStateEnum) -> Result<AppStateEnum> {
-        let app_data = state.get_app_data();
-        let books = app_data.bible.get_books().clone();
-        // TODO: Save/load from cache.
-        let initial_book = &books[0];
+        let mut app_data = state.get_app_data();
+        let restore = app_data.restore.take();
+        let initial_book = app_data.bible.get_books()[0].clone();
 
-        let book_reader = BookReader::new(app_data.bible.clone(), initial_book.to_string());
-        let mut books_view = BooksView::new(books);
+        let mut book_reader = BookReader::new(app_data.bible.clone(), initial_book);
+        book_reader.red_letter = app_data.red_letter;
+        let picker = TranslationPicker::new(app_data.translations.clone());
+        let mut books_view = BooksView::new(&app_data.bible);
         books_view.update(&AppEvent::Focus)?;
+        let mut prompt = CommandPrompt::new();
+        let bookmarks: Bookmarks = load_store(&mut prompt);
+        let notes: Notes = load_store(&mut prompt);
+        let highlights: Highlights = load_store(&mut prompt);
+
+        // Continue where the previous state was.
+        if let Some(position) = &app_data.position
+            && app_data.bible.get_book_index(&position.book).is_ok()
+        {
+            books_view.select_book(&position.book);
+            book_reader.scroll_to(&position.book, position.chapter, position.first_verse());
+        }
+
+        let search = SearchPanel::new(app_data.bible.clone());
+        let concordance = ConcordancePanel::new(app_data.bible.clone());
+        let mut focused = FocusedWindow::Books;
+        if let Some(session) = &restore {
+            book_reader.restore_offset(session.scrolled_offset);
+            focused = session.focused;
+        }
 
-        Ok(AppStateEnum::DefaultReader(DefaultReader {
+        let references = References::new(app_data.cross_references.clone(), app_data.bible.clone());
+        let notes = NotesPanel::new(notes, app_data.bible.clone());
+        let highlights = HighlightsPanel::new(highlights, app_data.bible.clone());
+        let reader_lexicon = app_data.lexicon.clone();
+        let mut reader = DefaultReader {
             app_data,
             books_view,
             book_reader,
             footer: LogosFooter::new(),
-            references: References::new(),
-            strongs: Strongs::new(),
+            prompt,
+            references,
+            strongs: Strongs::new(reader_lexicon),
+            search,
+            concordance,
+            highlights,
+            bookmarks: BookmarksPanel::new(bookmarks),
+            notes,
+            picker,
+            highlight_picker: HighlightPicker::new(),
+            clipboard: ClipboardSink::from_env(),
             focused: FocusedWindow::Books,
-        }))
+        };
+        if let Some(panel) = restore.and_then(|session| session.panel)
+            && let Err(e) = reader.restore_panel(&panel)
+        {
+            warn!("Failed to restore the {panel:?} panel: {e}");
+        }
+        reader.focus(focused)?;
+        Ok(AppStateEnum::DefaultReader(reader))
     }
 
     fn update(mut self, event: AppEvent) -> Result<AppStateEnum> {
+        // The picker is modal.
+        if self.picker.is_open() {
+            self.picker.update(&event)?;
+            if let Some(info) = self.picker.take_picked()
+                && info.name != self.app_data.bible.get_translation().name
+                && let Err(e) = self.switch_translation(&info)
+            {
+                warn!("Failed to switch to {}: {e}", info.name);
+                self.prompt.set_error(e.to_string());
+            }
+            return Ok(AppStateEnum::DefaultReader(self));
+        }
+        if self.highlight_picker.is_open() {
+            self.highlight_picker.update(&event)?;
+            if let Some((target, category)) = self.highlight_picker.take_picked() {
+                match self.apply_highlight(target, category) {
+                    Ok(message) => self.prompt.set_message(message),
+                    Err(e) => {
+                        warn!("Failed to save highlights: {e}");
+                        self.prompt.set_error(e.to_string());
+                    }
+                }
+                self.book_reader
+                    .set_highlights(self.highlights.highlights());
+            }
+            return Ok(AppStateEnum::DefaultReader(self));
+        }
+
+        if let AppEvent::Edited(edited) = event {
+            self.finish_edit(edited);
+            return Ok(AppStateEnum::DefaultReader(self));
+        }
+        if let AppEvent::UserAction(_) = event {
+            self.prompt.update(&event)?;
+        }
+
         match &event {
-            AppEvent::UserAction(UserAction::Quit) => return Ok(AppStateEnum::Exit),
+            AppEvent::KeyInput(_) if self.bookmarks.is_editing() => {
+                if let Err(e) = self.bookmarks.update(&event) {
+                    warn!("Failed to save bookmarks: {e}");
+                    self.prompt.set_error(e.to_string());
+                }
+            }
+            AppEvent::KeyInput(_) => {
+                self.prompt.update(&event)?;
+                if let Some((kind, input)) = self.prompt.take_submitted() {
+                    // Unknown references and invalid searches are shown to the user.
+                    if let Err(e) = self.submit_prompt(kind, &input) {
+                        warn!("Failed to submit {input:?}: {e}");
+                        self.prompt.set_error(e.to_string());
+                    }
+                }
+            }
+            AppEvent::UserAction(UserAction::Quit) => {
+                if let Err(e) = self.session().save() {
+                    warn!("Failed to save the session: {e}");
+                }
+                return Ok(AppStateEnum::Exit);
+            }
+            AppEvent::UserAction(UserAction::OpenCommand) => self.prompt.open(PromptKind::Jump),
+            AppEvent::UserAction(UserAction::OpenSearch) => self.prompt.open(PromptKind::Search),
+            AppEvent::UserAction(UserAction::OpenConcordance) => {
+                if let Err(e) = self.open_concordance() {
+                    warn!("Failed to open the concordance: {e}");
+                    self.prompt.set_error(e.to_string());
+                }
+            }
+            AppEvent::UserAction(UserAction::OpenHighlights) => {
+                self.highlights.open();
+                self.search.close();
+                self.concordance.close();
+                self.focus(FocusedWindow::Search)?;
+            }
+            AppEvent::UserAction(UserAction::OpenParallel) => {
+                return ParallelReader::from_state(AppStateEnum::DefaultReader(self));
+            }
+            AppEvent::UserAction(UserAction::ToggleRedLetter) => {
+                self.app_data.red_letter = !self.app_data.red_letter;
+                self.book_reader.red_letter = self.app_data.red_letter;
+                if let Some(notice) = self.app_data.red_letter_notice() {
+                    self.prompt.set_message(notice);
+                }
+            }
+            AppEvent::UserAction(UserAction::OpenTranslations) => {
+                self.picker
+                    .open(&[self.app_data.bible.get_translation().name.as_str()]);
+            }
             AppEvent::UserAction(UserAction::IncrementWindow) => {
-                self.focus(self.focused.next())?;
+                self.cycle_focus(FocusedWindow::next)?;
             }
             AppEvent::UserAction(UserAction::DecrementWindow) => {
-                self.focus(self.focused.prev())?;
+                self.cycle_focus(FocusedWindow::prev)?;
             }
             AppEvent::UserAction(UserAction::JumpToWindow(i)) => {
                 self.focus(match i {
@@ -111,6 +496,9 @@ impl AppStateTrait for DefaultReader {
                     1 => FocusedWindow::Reader,
                     2 => FocusedWindow::References,
                     3 => FocusedWindow::Strongs,
+                    4 => FocusedWindow::Search,
+                    5 => FocusedWindow::Bookmarks,
+                    6 => FocusedWindow::Notes,
                     _ => self.focused,
                 })?;
             }
@@ -120,10 +508,79 @@ impl AppStateTrait for DefaultReader {
                 self.references.update(&event)?;
                 self.strongs.update(&event)?;
                 self.footer.update(&event)?;
+                self.search.update(&event)?;
+                self.concordance.update(&event)?;
+                if let Err(e) = self.highlights.update(&event) {
+                    warn!("Failed to save highlights: {e}");
+                    self.prompt.set_error(e.to_string());
+                }
+                if let Err(e) = self.bookmarks.update(&event) {
+                    warn!("Failed to save bookmarks: {e}");
+                    self.prompt.set_error(e.to_string());
+                }
+                if let Err(e) = self.notes.update(&event) {
+                    warn!("Failed to save notes: {e}");
+                    self.prompt.set_error(e.to_string());
+                }
             }
         }
 
+        if let Some(reference) = self.search.take_selected() {
+            self.show_reference(reference)?;
+        }
+        if let Some(reference) = self.concordance.take_selected() {
+            self.show_reference(reference)?;
+        }
+        if let Some(reference) = self.highlights.take_selected() {
+            self.show_reference(reference)?;
+        }
+        if let Some(reference) = self.bookmarks.take_selected() {
+            self.show_reference(reference)?;
+        }
+        if let Some(reference) = self.references.take_selected() {
+            self.show_reference(reference)?;
+        }
+        if let Some(bookmark) = self.book_reader.take_bookmark() {
+            let location = bookmark.location();
+            match self.bookmarks.add(bookmark) {
+                Ok(()) => self.prompt.set_message(format!("Bookmarked {location}")),
+                Err(e) => {
+                    warn!("Failed to save bookmarks: {e}");
+                    self.prompt.set_error(e.to_string());
+                }
+            }
+        }
+        if let Some((book, start, end)) = self.book_reader.take_note() {
+            let versification = self.app_data.bible.get_versification();
+            let start = versification.map_to_kjv(book, start.0, start.1);
+            let end = versification.map_to_kjv(book, end.0, end.1);
+            let reference = osis_reference(book, start, end);
+            self.notes.edit(&reference);
+        }
+        if let Some(target) = self.book_reader.take_highlight() {
+            self.highlight_picker.open(target);
+        }
+        if let Some((text, reference)) = self.book_reader.take_yanked() {
+            match self.clipboard.copy(&text) {
+                Ok(()) => self.prompt.set_message(format!("Yanked {reference}")),
+                Err(e) => {
+                    warn!("Failed to yank {reference}: {e}");
+                    self.prompt.set_error(e.to_string());
+                }
+            }
+        }
+        if self.focused == FocusedWindow::Search && !self.is_available(FocusedWindow::Search) {
+            self.focus(FocusedWindow::Reader)?;
+        }
+
         self.book_reader.set_book(self.books_view.selected_book());
+        self.strongs.set_word(self.book_reader.cursor_word());
+        let (book, chapter, verse) = self.book_reader.position();
+        self.references.set_verse(book, chapter, verse);
+        self.notes.set_verse(book, chapter, verse);
+        self.book_reader.set_notes(self.notes.notes());
+        self.book_reader
+            .set_highlights(self.highlights.highlights());
         Ok(AppStateEnum::DefaultReader(self))
     }
 
@@ -131,29 +588,80 @@ impl AppStateTrait for DefaultReader {
         let [main, footer] =
             Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(f.area());
 
+        let layout = &settings().layout;
         let [books, content, sidebar] = Layout::horizontal([
-            Constraint::Percentage(15),
+            Constraint::Percentage(layout.books),
             Constraint::Fill(1),
-            Constraint::Percentage(20),
+            Constraint::Percentage(layout.sidebar),
         ])
         .areas(main);
 
-        let [references, strongs] =
-            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)])
-                .areas(sidebar);
+        let [references, strongs, bookmarks, notes] = Layout::vertical([
+            Constraint::Ratio(1, 4),
+            Constraint::Ratio(1, 4),
+            Constraint::Ratio(1, 4),
+            Constraint::Ratio(1, 4),
+        ])
+        .areas(sidebar);
 
         let buf = f.buffer_mut();
         self.books_view.render(books, buf)?;
-        self.book_reader.render(content, buf)?;
+        if self.is_available(FocusedWindow::Search) {
+            let [reader, search] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Percentage(layout.search)])
+                    .areas(content);
+            self.book_reader.render(reader, buf)?;
+            if self.highlights.is_open() {
+                self.highlights.render(search, buf)?;
+            } else if self.concordance.is_open() {
+                self.concordance.render(search, buf)?;
+            } else {
+                self.search.render(search, buf)?;
+            }
+        } else {
+            self.book_reader.render(content, buf)?;
+        }
         self.references.render(references, buf)?;
         self.strongs.render(strongs, buf)?;
-        self.footer.render(footer, buf)?;
+        self.bookmarks.render(bookmarks, buf)?;
+        self.notes.render(notes, buf)?;
+        if self.prompt.is_visible() {
+            self.prompt.render(footer, buf)?;
+        } else {
+            self.footer.render(footer, buf)?;
+        }
+        self.picker.render(main, buf)?;
+        self.highlight_picker.render(main, buf)?;
         Ok(())
     }
 
     fn get_app_data(self) -> PersistentAppData {
-        self.app_data
+        let (book, chapter, verse) = self.book_reader.position();
+        let position = Reference {
+            book: book.to_string(),
+            chapter,
+            verses: Some(verse..=verse),
+        };
+        PersistentAppData {
+            position: Some(position),
+            ..self.app_data
+        }
     }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Loads the items of the user.  When their file can not be read they are only kept in memory,
+/// saving would overwrite the ones that could not be read.
+fn load_store<T: Stored>(prompt: &mut CommandPrompt) -> Store<T> {
+    Store::load().unwrap_or_else(|e| {
+        error!(
+            "Failed to load the {}, they will not be saved: {e}",
+            T::NAME
+        );
+        prompt.set_error(format!("The {} will not be saved: {e}", T::NAME));
+        Store::in_memory()
+    })
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,241 @@
+use crate::app::data::PersistentAppData;
+use crate::app::events::{AppEvent, UserAction};
+use crate::app::state::{AppStateEnum, AppStateTrait};
+use crate::app::state_default_reader::{DefaultReader, FocusedWindow};
+use crate::bible::Bible;
+use crate::components::Component;
+use crate::components::command_prompt::{CommandPrompt, PromptKind};
+use crate::components::footer::LogosFooter;
+use crate::components::parallel_view::ParallelView;
+use crate::components::translation_picker::TranslationPicker;
+use crate::prelude::*;
+use crate::reference::Reference;
+use crate::session::Session;
+use crate::translations::TranslationInfo;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout};
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Reads the open translation side by side with others.
+pub struct ParallelReader {
+    pub app_data: PersistentAppData,
+    pub view: ParallelView,
+    pub footer: LogosFooter,
+    pub prompt: CommandPrompt,
+    pub picker: TranslationPicker,
+}
+
+impl ParallelReader {
+    pub fn captures_input(&self) -> bool {
+        self.prompt.is_active()
+    }
+
+    /// The translation that is being read first, followed by the others.
+    fn bibles(app_data: &PersistentAppData) -> Vec<Arc<Bible>> {
+        let current = &app_data.bible.get_translation().name;
+        let others = app_data
+            .parallel
+            .iter()
+            .filter(|b| &b.get_translation().name != current);
+        [&app_data.bible]
+            .into_iter()
+            .chain(others)
+            .cloned()
+            .collect()
+    }
+
+    /// Adds the translation to the view, or removes it if it is already shown.  The translation
+    /// that is being read can not be removed.
+    fn toggle_translation(&mut self, info: &TranslationInfo) -> Result<()> {
+        if info.name == self.app_data.bible.get_translation().name {
+            return Ok(());
+        }
+
+        let shown = self
+            .app_data
+            .parallel
+            .iter()
+            .position(|b| b.get_translation().name == info.name);
+        match shown {
+            Some(i) => {
+                info!("Removing {} from the parallel view", info.name);
+                self.app_data.parallel.remove(i);
+            }
+            None => {
+                info!("Adding {} to the parallel view", info.name);
+                let bible = Arc::new(Bible::from_translation(info)?);
+                self.app_data.parallel.push(bible);
+            }
+        }
+        self.view.set_bibles(Self::bibles(&self.app_data));
+        Ok(())
+    }
+
+    /// Where the user is reading, to continue there the next time.
+    pub fn session(&self) -> Session {
+        let (book, chapter, verse) = self.view.position();
+        Session {
+            translation: self.app_data.bible.get_translation().name.clone(),
+            parallel: self
+                .app_data
+                .parallel
+                .iter()
+                .map(|b| b.get_translation().name.clone())
+                .collect(),
+            parallel_open: true,
+            book: book.to_string(),
+            chapter,
+            verse,
+            // The view is scrolled to the verse, which does not depend on the terminal size.
+            scrolled_offset: 0,
+            focused: FocusedWindow::Reader,
+            panel: None,
+            red_letter: self.app_data.red_letter,
+        }
+    }
+
+    fn submit_prompt(&mut self, kind: PromptKind, input: &str) -> Result<()> {
+        match kind {
+            PromptKind::Jump => {
+                let reference = Reference::parse(input, &self.app_data.bible)?;
+                info!("Jumping to {reference}");
+                self.view.jump_to(reference);
+                Ok(())
+            }
+            PromptKind::Search | PromptKind::Concordance => Err(Error::InvalidQuery(
+                "search is not available in the parallel view".to_string(),
+            )),
+        }
+    }
+}
+
+impl AppStateTrait for ParallelReader {
+    fn from_state(state: AppStateEnum) -> Result<AppStateEnum> {
+        let mut app_data = state.get_app_data();
+        // The position is all there is to restore here.
+        app_data.restore = None;
+
+        // Start off with the next installed translation, if there is one.
+        if app_data.parallel.is_empty() {
+            let current = &app_data.bible.get_translation().name;
+            let other = app_data.translations.iter().find(|t| &t.name != current);
+            if let Some(info) = other {
+                match Bible::from_translation(info) {
+                    Ok(bible) => app_data.parallel.push(Arc::new(bible)),
+                    Err(e) => warn!("Failed to load {}: {e}", info.name),
+                }
+            }
+        }
+
+        let position = app_data.position.clone();
+        let book = match &position {
+            Some(reference) => reference.book.clone(),
+            None => app_data.bible.get_books()[0].clone(),
+        };
+        let mut view = ParallelView::new(Self::bibles(&app_data), book.clone());
+        view.red_letter = app_data.red_letter;
+        if let Some(reference) = position {
+            view.scroll_to(&book, reference.chapter, reference.first_verse());
+        }
+
+        Ok(AppStateEnum::ParallelReader(ParallelReader {
+            picker: TranslationPicker::new(app_data.translations.clone()),
+            app_data,
+            view,
+            footer: LogosFooter::new(),
+            prompt: CommandPrompt::new(),
+        }))
+    }
+
+    fn update(mut self, event: AppEvent) -> Result<AppStateEnum> {
+        // The picker is modal.
+        if self.picker.is_open() {
+            self.picker.update(&event)?;
+            if let Some(info) = self.picker.take_picked()
+                && let Err(e) = self.toggle_translation(&info)
+            {
+                warn!("Failed to load {}: {e}", info.name);
+                self.prompt.set_error(e.to_string());
+            }
+            return Ok(AppStateEnum::ParallelReader(self));
+        }
+
+        if let AppEvent::UserAction(_) = event {
+            self.prompt.update(&event)?;
+        }
+
+        match &event {
+            AppEvent::KeyInput(_) => {
+                self.prompt.update(&event)?;
+                if let Some((kind, input)) = self.prompt.take_submitted()
+                    && let Err(e) = self.submit_prompt(kind, &input)
+                {
+                    warn!("Failed to submit {input:?}: {e}");
+                    self.prompt.set_error(e.to_string());
+                }
+            }
+            AppEvent::UserAction(UserAction::Quit) => {
+                if let Err(e) = self.session().save() {
+                    warn!("Failed to save the session: {e}");
+                }
+                return Ok(AppStateEnum::Exit);
+            }
+            AppEvent::UserAction(UserAction::OpenCommand) => self.prompt.open(PromptKind::Jump),
+            AppEvent::UserAction(UserAction::ToggleRedLetter) => {
+                self.app_data.red_letter = !self.app_data.red_letter;
+                self.view.red_letter = self.app_data.red_letter;
+                if let Some(notice) = self.app_data.red_letter_notice() {
+                    self.prompt.set_message(notice);
+                }
+            }
+            AppEvent::UserAction(UserAction::OpenTranslations) => {
+                let loaded: Vec<&str> = self
+                    .view
+                    .get_bibles()
+                    .iter()
+                    .map(|b| b.get_translation().name.as_str())
+                    .collect();
+                self.picker.open(&loaded);
+            }
+            AppEvent::UserAction(UserAction::OpenParallel | UserAction::Close) => {
+                return DefaultReader::from_state(AppStateEnum::ParallelReader(self));
+            }
+            _ => {
+                self.view.update(&event)?;
+                self.footer.update(&event)?;
+            }
+        }
+        Ok(AppStateEnum::ParallelReader(self))
+    }
+
+    fn render(&mut self, f: &mut Frame) -> Result<()> {
+        let [main, footer] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(f.area());
+
+        let buf = f.buffer_mut();
+        self.view.render(main, buf)?;
+        if self.prompt.is_visible() {
+            self.prompt.render(footer, buf)?;
+        } else {
+            self.footer.render(footer, buf)?;
+        }
+        self.picker.render(main, buf)?;
+        Ok(())
+    }
+
+    fn get_app_data(self) -> PersistentAppData {
+        let (book, chapter, verse) = self.view.position();
+        let position = Reference {
+            book: book.to_string(),
+            chapter,
+            verses: Some(verse..=verse),
+        };
+        PersistentAppData {
+            position: Some(position),
+            ..self.app_data
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -1,55 +1,93 @@
 use crate::{
-    config::get_translations,
-    filesystem::{decompress_xz, is_xml_file, is_xz_compressed_xml},
-    prelude::*,
+    canon, filesystem::open_text, formats::Format, prelude::*, translations::TranslationInfo,
+    versification::Versification,
 };
 use indexmap::IndexMap;
-use quick_xml::Reader;
-use quick_xml::events::Event;
+use std::borrow::Cow;
 use std::time::Instant;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 #[derive(Debug)]
 pub struct Bible {
-    translation: String,
-    disk_file: PathBuf,
+    /// Includes the file the bible was loaded from.
+    translation: TranslationInfo,
 
+    /// Books keyed by OSIS ID.
     index: IndexMap<String, Book>,
+    /// OSIS IDs of the books in order.
     books: Vec<String>,
+    /// Normalized names and abbreviations -> OSIS ID.
+    aliases: HashMap<String, String>,
+    /// Whether any quote says who is speaking, needed for red letter.
+    has_speakers: bool,
+    /// How the chapters and verses are numbered.
+    versification: &'static Versification,
     raw: String,
 }
 
-impl Bible {
-    pub fn get_book(&self, book_name: &str) -> Book {
-        // TODO: Don't like the clone and unwrap.
-        self.index.get(book_name).unwrap().clone()
-    }
-}
-
-////////////////////////////////////////////////////////////////////////////////////////////////////
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Book {
+    /// Stable identity of the book, e.g. "Gen" or "1Cor".
+    pub osis_id: String,
+    /// Display name, from the title of the book.
+    pub name: String,
+    pub testament: Option<Testament>,
+    pub abbreviations: Vec<String>,
     pub chapters: Vec<Chapter>,
 }
 
 impl Book {
-    pub fn new() -> Self {
+    /// Creates an empty book, filling in what is known about the ID.
+    pub fn new(osis_id: &str, testament: Option<Testament>) -> Self {
+        let info = canon::book_info(osis_id);
         Self {
+            osis_id: osis_id.to_string(),
+            name: canon::book_name(osis_id).to_string(),
+            testament: testament.or(info.map(|i| i.testament)),
+            abbreviations: info
+                .map(|i| i.abbreviations.iter().map(|a| a.to_string()).collect())
+                .unwrap_or_default(),
             chapters: Vec::new(),
         }
     }
 
+    /// All of the names the book can be looked up by.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        let english = canon::book_info(&self.osis_id).map(|i| i.name);
+        [self.osis_id.as_str(), self.name.as_str()]
+            .into_iter()
+            .chain(english)
+            .chain(self.abbreviations.iter().map(String::as_str))
+    }
+
     pub fn get_chapters(&self) -> impl Iterator<Item = &Chapter> {
         self.chapters.iter()
     }
 }
 
-impl Default for Book {
-    fn default() -> Self {
-        Self {
-            chapters: Vec::new(),
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The canon group of a book, taken from the `bookGroup` divs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Testament {
+    Old,
+    New,
+    Apocrypha,
+}
+
+impl Testament {
+    /// Classifies the title of a book group, e.g. "Apocrypha/Deuterocanon".
+    pub fn from_title(title: &str) -> Option<Testament> {
+        let title = title.to_lowercase();
+        if title.contains("old testament") {
+            Some(Testament::Old)
+        } else if title.contains("new testament") {
+            Some(Testament::New)
+        } else if title.contains("apocrypha") || title.contains("deuterocanon") {
+            Some(Testament::Apocrypha)
+        } else {
+            None
         }
     }
 }
@@ -81,18 +119,89 @@ impl Chapter {
 #[derive(Debug, Clone)]
 pub struct VerseView {
     pub number: usize,
-    /// (start, end) byte ranges of text regions in the raw data.
-    pub indices: Vec<(usize, usize)>,
+    /// Text and inline markup, in the order it appears.
+    pub inlines: Vec<Inline>,
+}
+
+/// Inline content of a verse.  Text refers to (start, end) byte ranges in the raw data, see
+/// `text_at`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Inline {
+    Text(usize, usize),
+    /// Words supplied by the translators, `<transChange type="added">`.
+    Added(usize, usize),
+    /// Start of a quotation, with the speaker if the source has one.
+    QuoteStart {
+        who: Option<String>,
+    },
+    QuoteEnd,
+    /// Start of a poetry line, `<l>`.
+    LineStart {
+        level: usize,
+    },
+    LineEnd,
+    /// `<lb/>`
+    LineBreak,
+    /// Start of a word that is tagged with its lemma, `<w lemma="strong:H7225">`.
+    WordStart(WordTag),
+    WordEnd,
+}
+
+/// The lemma and morphology of a tagged word, as written in the source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordTag {
+    /// e.g. "strong:H7225", multiple lemmas are separated by spaces.
+    pub lemma: String,
+    /// e.g. "strongMorph:TH8799" or "robinson:V-PAI-3S".
+    pub morph: Option<String>,
+}
+
+impl WordTag {
+    /// The Strong's numbers of the lemma, e.g. "H7225".
+    pub fn strongs(&self) -> impl Iterator<Item = String> {
+        self.lemma
+            .split_whitespace()
+            .filter_map(|lemma| strongs_number(lemma.strip_prefix("strong:")?))
+    }
+}
+
+/// Normalizes a Strong's number, e.g. "h07225" -> "H7225".  Letters after the number, used by
+/// some sources to tell apart meanings, are left out.
+pub fn strongs_number(number: &str) -> Option<String> {
+    let mut chars = number.trim().chars();
+    let prefix = chars.next()?.to_ascii_uppercase();
+    let digits: String = chars.take_while(|c| c.is_ascii_digit()).collect();
+    let digits = digits.trim_start_matches('0');
+    match prefix {
+        'H' | 'G' if !digits.is_empty() => Some(format!("{prefix}{digits}")),
+        _ => None,
+    }
+}
+
+impl Inline {
+    /// Markup that closes something, which belongs to the preceding verse when it is found
+    /// between verses.
+    pub fn is_end(&self) -> bool {
+        matches!(self, Inline::QuoteEnd | Inline::LineEnd | Inline::WordEnd)
+    }
 }
 
 impl VerseView {
     pub fn new(number: usize) -> Self {
         Self {
             number,
-            indices: Vec::new(),
+            inlines: Vec::new(),
         }
     }
 
+    /// (start, end) byte ranges of the text in the raw data, ignoring the markup.
+    pub fn text_ranges(&self) -> impl Iterator<Item = (usize, usize)> {
+        self.inlines.iter().filter_map(|inline| match inline {
+            Inline::Text(s, e) | Inline::Added(s, e) => Some((*s, *e)),
+            _ => None,
+        })
+    }
+
     // TODO: Don't really like that you have to pass in the raw.
     // Collect all of these types (Bible, chapter, verse) into a single
     // struct with references.
@@ -100,42 +209,70 @@ impl VerseView {
         // There seems to be some groups of whitespaces in the data.  I guess the file is just
         // dirty.
         // TODO: Investigate and move this logic out.
-        let s: String = self.indices.iter().map(move |&(s, e)| &raw[s..e]).collect();
+        let s: String = self
+            .text_ranges()
+            .map(move |(s, e)| text_at(raw, s, e))
+            .collect();
         s.split_whitespace().collect::<Vec<_>>().join(" ")
     }
 }
 
+/// The text at a byte range of the raw data.  XML sources have entity references like `&amp;`
+/// in ranges of their own, which are resolved.
+pub fn text_at(raw: &str, start: usize, end: usize) -> Cow<'_, str> {
+    let text = &raw[start..end];
+    if text.starts_with('&')
+        && text.ends_with(';')
+        && let Ok(resolved) = quick_xml::escape::unescape(text)
+    {
+        return resolved;
+    }
+    Cow::Borrowed(text)
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 impl Bible {
-    pub fn from_translation(translation: &str) -> Result<Bible> {
-        let file = get_translations()
-            // TODO: Select translation from UI.
-            .get("KJV")
-            .ok_or(Error::UnsupprtedTranslation(translation.to_string()))?;
-        Bible::from_file(file)
+    pub fn from_translation(translation: &TranslationInfo) -> Result<Bible> {
+        Bible::from_file(&translation.path)
     }
 
     pub fn from_file(path: &std::path::Path) -> Result<Bible> {
         info!("Loading {:?} into memory", path);
         let start = Instant::now();
-        let raw = if is_xz_compressed_xml(path) {
-            decompress_xz(path)?
-        } else if is_xml_file(path) {
-            std::fs::read_to_string(path)?
-        } else {
-            return Err(Error::InvalidBibleFile);
-        };
+        let (format, _) = Format::sniff_file(path)?;
+        let parser = format.parser();
+        let raw = parser.load(path)?;
         info!("Loaded {:?} in {:?}", path, start.elapsed());
 
-        let index = Self::build_index_from_osis(&raw)?;
-        return Ok(Bible {
-            disk_file: path.to_path_buf(),
+        let translation = parser.info(&mut open_text(path)?, path)?;
+        info!("Building the index of the {} source", format.name());
+        let index = parser.parse(&raw)?;
+        let versification = match translation.versification.as_deref() {
+            Some(name) => Versification::from_name(name).unwrap_or_else(|| {
+                warn!("Unknown versification {name:?}, guessing from the verses");
+                Self::detect_versification(&index)
+            }),
+            None => Self::detect_versification(&index),
+        };
+        info!("{} uses the {} versification", translation.name, versification.name);
+        Ok(Bible {
+            translation,
             books: index.keys().cloned().collect(),
-            index: index,
-            translation: "KJV".to_string(), // TODO: Get translation.
-            raw: raw,
-        });
+            aliases: Self::build_aliases(&index),
+            has_speakers: Self::has_speakers(&index),
+            versification,
+            index,
+            raw,
+        })
+    }
+
+    pub fn get_translation(&self) -> &TranslationInfo {
+        &self.translation
+    }
+
+    pub fn get_versification(&self) -> &'static Versification {
+        self.versification
     }
 
     pub fn get_books(&self) -> &Vec<String> {
@@ -146,111 +283,275 @@ impl Bible {
         &self.raw
     }
 
+    /// Looks up a book by OSIS ID, name or abbreviation.
     pub fn get_book_index(&self, name: &str) -> Result<&Book> {
         self.index
             .get(name)
+            .or_else(|| {
+                let id = self.aliases.get(&canon::normalize_name(name))?;
+                self.index.get(id)
+            })
             .ok_or(Error::BookNotFound(name.to_string()))
     }
 
-    fn build_index_from_osis(raw: &str) -> Result<IndexMap<String, Book>> {
-        info!("Building bible index");
+    /// Books in canonical order.
+    pub fn iter_books(&self) -> impl Iterator<Item = (&str, &Book)> {
+        self.index.iter().map(|(name, book)| (name.as_str(), book))
+    }
 
-        let start = Instant::now();
-        let mut index: IndexMap<String, Book> = IndexMap::new();
-        let mut reader = Reader::from_str(raw);
-
-        let mut buf = Vec::new();
-        let mut book = String::new();
-        let mut awaiting_title = false;
-        let mut in_verse = false;
-        let mut current_chapter = 1;
-        let mut current_verse = 1;
-
-        loop {
-            match reader.read_event_into(&mut buf) {
-                Ok(Event::Start(ref e))
-                    if e.name().as_ref() == b"div" && Self::has_attr_val(e, b"type", b"book") =>
-                {
-                    awaiting_title = true;
-                }
-                Ok(Event::Start(ref e)) if e.name().as_ref() == b"title" && awaiting_title => {
-                    if let Some(name) = Self::attr(e, b"short") {
-                        book = name;
-                        index.entry(book.clone()).or_default();
-                    }
-                    awaiting_title = false;
-                    current_chapter = 1;
-                }
-                Ok(Event::Empty(ref e))
-                    if e.name().as_ref() == b"chapter" && Self::has_attr(e, b"sID") =>
-                {
-                    index
-                        .entry(book.clone())
-                        .or_default()
-                        .chapters
-                        .push(Chapter::new(current_chapter));
-                    current_chapter += 1;
-                    current_verse = 1;
-                }
-                Ok(Event::Empty(ref e))
-                    if e.name().as_ref() == b"verse" && Self::has_attr(e, b"sID") =>
-                {
-                    in_verse = true;
-                    if let Some(ch) = index.get_mut(&book).and_then(|b| b.chapters.last_mut()) {
-                        ch.verses.push(VerseView::new(current_verse));
-                        current_verse += 1;
-                    }
-                }
-                Ok(Event::Empty(ref e))
-                    if e.name().as_ref() == b"verse" && Self::has_attr(e, b"eID") =>
-                {
-                    in_verse = false;
-                }
-                Ok(Event::Text(ref e)) if in_verse => {
-                    let len = e.len();
-                    if len > 0 {
-                        let end = reader.buffer_position() as usize;
-                        let start = end - len;
-                        if let Some(verse) = index
-                            .get_mut(&book)
-                            .and_then(|b| b.chapters.last_mut())
-                            .and_then(|c| c.verses.last_mut())
-                        {
-                            verse.indices.push((start, end));
-                        }
-                    }
-                }
-                Ok(Event::Eof) => break,
-                Err(e) => return Err(Error::BibleIndex(e.to_string())),
-                _ => {}
-            }
-            buf.clear();
+    /// The book at `position` in canonical order.
+    pub fn get_book_at(&self, position: usize) -> Option<(&str, &Book)> {
+        self.index
+            .get_index(position)
+            .map(|(name, book)| (name.as_str(), book))
+    }
+
+    pub fn get_chapter(&self, book: &str, chapter: usize) -> Result<&Chapter> {
+        let book = self.get_book_index(book)?;
+        book.chapters
+            .iter()
+            .find(|c| c.number == chapter)
+            .ok_or(Error::ChapterNotFound(book.name.clone(), chapter))
+    }
+
+    /// Finds the OSIS ID of the book that is meant by a (possibly abbreviated) name.
+    ///
+    /// Tries, in order:
+    /// - An OSIS ID, name or known abbreviation.
+    /// - A prefix of the name, ignoring case, whitespace and dots ("1 cori" -> "1 Corinthians").
+    /// - The letters in order, starting at the first letter ("Jdgs" -> "Judges").  When multiple
+    ///   books match the shortest name wins.
+    pub fn find_book(&self, name: &str) -> Result<&str> {
+        if let Ok(book) = self.get_book_index(name) {
+            return Ok(&book.osis_id);
         }
 
-        info!("Built index in {:?}", start.elapsed());
-        Ok(index)
+        let needle = canon::normalize_name(name);
+        if needle.is_empty() {
+            return Err(Error::BookNotFound(name.to_string()));
+        }
+
+        let books = self
+            .index
+            .values()
+            .map(|b| (b.osis_id.as_str(), canon::normalize_name(&b.name)));
+        if let Some((book, _)) = books.clone().find(|(_, b)| b.starts_with(&needle)) {
+            return Ok(book);
+        }
+
+        books
+            .filter(|(_, b)| b.chars().next() == needle.chars().next())
+            .filter(|(_, b)| {
+                let mut haystack = b.chars();
+                needle.chars().all(|c| haystack.any(|h| h == c))
+            })
+            .min_by_key(|(_, b)| b.len())
+            .map(|(book, _)| book)
+            .ok_or(Error::BookNotFound(name.to_string()))
     }
 
-    // Parsing utils.
+    pub fn get_has_speakers(&self) -> bool {
+        self.has_speakers
+    }
 
-    fn attr(e: &quick_xml::events::BytesStart, key: &[u8]) -> Option<String> {
-        e.attributes()
-            .filter_map(|a| a.ok())
-            .find(|a| a.key.as_ref() == key)
-            .map(|a| String::from_utf8_lossy(&a.value).to_string())
+    fn has_speakers(index: &IndexMap<String, Book>) -> bool {
+        index
+            .values()
+            .flat_map(|b| b.get_chapters())
+            .flat_map(|c| c.get_verses())
+            .flat_map(|v| v.inlines.iter())
+            .any(|i| matches!(i, Inline::QuoteStart { who: Some(_) }))
     }
 
-    fn has_attr(e: &quick_xml::events::BytesStart, key: &[u8]) -> bool {
-        e.attributes()
-            .filter_map(|a| a.ok())
-            .any(|a| a.key.as_ref() == key)
+    /// Guesses the versification from verses that only some systems have.
+    fn detect_versification(index: &IndexMap<String, Book>) -> &'static Versification {
+        let has = |book: &str, chapter: usize, verse: usize| {
+            index
+                .get(book)
+                .and_then(|b| b.chapters.iter().find(|c| c.number == chapter))
+                .is_some_and(|c| c.verses.iter().any(|v| v.number == verse))
+        };
+        // Psalms 9 and 10 are one psalm in the Septuagint.
+        let name = if has("Ps", 9, 39) {
+            if has("Rom", 14, 26) {
+                "Synodal"
+            } else if index.contains_key("Rom") {
+                "Vulg"
+            } else {
+                "LXX"
+            }
+        } else if has("Mal", 3, 24) || has("Joel", 4, 1) {
+            "Leningrad"
+        } else if has("Ps", 3, 9) {
+            "German"
+        } else {
+            "KJV"
+        };
+        Versification::from_name(name).unwrap_or_else(Versification::kjv)
     }
 
-    fn has_attr_val(e: &quick_xml::events::BytesStart, key: &[u8], val: &[u8]) -> bool {
-        e.attributes()
-            .filter_map(|a| a.ok())
-            .any(|a| a.key.as_ref() == key && a.value.as_ref() == val)
+    fn build_aliases(index: &IndexMap<String, Book>) -> HashMap<String, String> {
+        let mut aliases = HashMap::new();
+        for book in index.values() {
+            for name in book.names() {
+                // Earlier books win when abbreviations clash.
+                aliases
+                    .entry(canon::normalize_name(name))
+                    .or_insert_with(|| book.osis_id.clone());
+            }
+        }
+        aliases
     }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// Loads a bible from its source, through a file like any translation.  The extension of
+    /// `name` is kept, some formats are told apart by it.
+    pub fn bible_from(name: &str, source: &str) -> Bible {
+        static COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let count = COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let path =
+            std::env::temp_dir().join(format!("logos-{}-{count}-{name}", std::process::id()));
+        std::fs::write(&path, source).unwrap();
+        let bible = Bible::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        bible.unwrap()
+    }
+
+    #[test]
+    fn finds_books_by_id_name_and_abbreviation() {
+        let bible = bible_from(
+            "books.xml",
+            r#"<osis><osisText><div type="bookGroup"><title>New Testament</title>
+<div type="book" osisID="1Cor"><title short="1 Corinthians">Paul to the Corinthians</title>
+<chapter sID="1Cor.1"/><verse sID="1Cor.1.1"/>Paul.<verse eID="1Cor.1.1"/><chapter eID="1Cor.1"/></div>
+<div type="book" osisID="Judg"><title short="Judges">Judges</title>
+<chapter sID="Judg.1"/><verse sID="Judg.1.1"/>Now.<verse eID="Judg.1.1"/><chapter eID="Judg.1"/></div>
+</div></osisText></osis>"#,
+        );
+        assert_eq!(bible.get_books(), &["1Cor", "Judg"]);
+        let book = bible.get_book_index("I Cor.").unwrap();
+        assert_eq!(
+            (book.osis_id.as_str(), book.name.as_str()),
+            ("1Cor", "1 Corinthians")
+        );
+        assert_eq!(book.testament, Some(Testament::New));
+
+        for name in ["1Cor", "1 Corinthians", "1 co", "1 cori"] {
+            assert_eq!(bible.find_book(name).unwrap(), "1Cor", "{name}");
+        }
+        assert_eq!(bible.find_book("Jdgs").unwrap(), "Judg");
+        assert!(bible.find_book("Gen").is_err());
+    }
+
+    #[test]
+    fn indexes_inline_markup() {
+        let bible = bible_from(
+            "inlines.xml",
+            r#"<osis><osisText><div type="book" osisID="John"><chapter sID="John.1" osisID="John.1"/>
+<verse sID="John.1.1" osisID="John.1.1"/>He said, <q who="Jesus">I <transChange type="added">am</transChange> here.</q><verse eID="John.1.1"/>
+<l sID="l1" level="2"/><verse sID="John.1.2" osisID="John.1.2"/>Line one<lb/>more<verse eID="John.1.2"/><l eID="l1"/>
+<chapter eID="John.1"/></div></osisText></osis>"#,
+        );
+        let raw = bible.get_raw_data();
+        let inlines = |verse: &VerseView| -> Vec<String> {
+            verse
+                .inlines
+                .iter()
+                .map(|inline| match inline {
+                    Inline::Text(s, e) => raw[*s..*e].to_string(),
+                    Inline::Added(s, e) => format!("[{}]", &raw[*s..*e]),
+                    inline => format!("{inline:?}"),
+                })
+                .collect()
+        };
+        let mut verses = bible.get_chapter("John", 1).unwrap().get_verses();
+        assert_eq!(
+            inlines(verses.next().unwrap()),
+            [
+                "He said, ",
+                r#"QuoteStart { who: Some("Jesus") }"#,
+                "I ",
+                "[am]",
+                " here.",
+                "QuoteEnd"
+            ]
+        );
+        // Markup between verses that starts something goes to the next verse, and markup that
+        // ends something to the previous one.
+        assert_eq!(
+            inlines(verses.next().unwrap()),
+            [
+                "LineStart { level: 2 }",
+                "Line one",
+                "LineBreak",
+                "more",
+                "LineEnd"
+            ]
+        );
+    }
+
+    #[test]
+    fn reopens_quotes_that_span_verses() {
+        let bible = bible_from(
+            "quotes.xml",
+            r#"<osis><osisText><div type="book" osisID="John"><chapter sID="John.1" osisID="John.1"/>
+<verse sID="John.1.1" osisID="John.1.1"/>He said, <q sID="q1" who="Jesus"/>Come<verse eID="John.1.1"/>
+<verse sID="John.1.2" osisID="John.1.2"/>and see.<q eID="q1"/> So they went.<verse eID="John.1.2"/>
+<chapter eID="John.1"/></div></osisText></osis>"#,
+        );
+        assert!(bible.get_has_speakers());
+        let quotes = |verse: &VerseView| -> Vec<bool> {
+            verse
+                .inlines
+                .iter()
+                .filter_map(|inline| match inline {
+                    Inline::QuoteStart { who } => Some(who.as_deref() == Some("Jesus")),
+                    Inline::QuoteEnd => Some(false),
+                    _ => None,
+                })
+                .collect()
+        };
+        // Every verse opens and closes the quotes it has a part of.
+        let mut verses = bible.get_chapter("John", 1).unwrap().get_verses();
+        assert_eq!(quotes(verses.next().unwrap()), [true, false]);
+        assert_eq!(quotes(verses.next().unwrap()), [true, false]);
+    }
+    #[test]
+    fn numbers_verses_by_their_osis_id() {
+        // The last chapter of Malachi is part of chapter 3 in the Hebrew bible.
+        let malachi = |last: &str| {
+            bible_from(
+                "malachi.xml",
+                &format!(
+                    r#"<osis><osisText><div type="book" osisID="Mal"><chapter sID="Mal.3" osisID="Mal.3"/>
+<verse sID="Mal.3.18" osisID="Mal.3.18"/>Then.<verse eID="Mal.3.18"/>
+<verse sID="{last}" osisID="{last}"/>For, behold.<verse eID="{last}"/>
+<chapter eID="Mal.3"/></div></osisText></osis>"#
+                ),
+            )
+        };
+        let numbers = |bible: &Bible| -> Vec<(usize, Vec<usize>)> {
+            bible
+                .get_book_index("Mal")
+                .unwrap()
+                .get_chapters()
+                .map(|c| (c.number, c.get_verses().map(|v| v.number).collect()))
+                .collect()
+        };
+
+        let kjv = malachi("Mal.4.1");
+        assert_eq!(numbers(&kjv), [(3, vec![18]), (4, vec![1])]);
+        assert_eq!(kjv.get_versification().name, "KJV");
+        let hebrew = malachi("Mal.3.19");
+        assert_eq!(numbers(&hebrew), [(3, vec![18, 19])]);
+        // Only the verses past the end of the KJV's chapter tell the systems apart.
+        let hebrew = malachi("Mal.3.24");
+        assert_eq!(hebrew.get_versification().name, "Leningrad");
+    }
+}
@@ -0,0 +1,136 @@
+use crate::prelude::*;
+use crate::reference::{Reference, format_range};
+use crate::store::{Store, Stored};
+use serde::{Deserialize, Serialize};
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A named verse or range of verses.  Bookmarks refer to books by OSIS ID, so they work in every
+/// translation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    /// OSIS ID of the book.
+    pub book: String,
+    /// (chapter, verse) of the first verse.
+    pub start: (usize, usize),
+    /// (chapter, verse) of the last verse.
+    pub end: (usize, usize),
+}
+
+impl Bookmark {
+    /// A bookmark that is named after the verses, e.g. "John 3:16-17".
+    pub fn new(book: &str, start: (usize, usize), end: (usize, usize)) -> Bookmark {
+        Bookmark {
+            name: format_range(book, start, end),
+            book: book.to_string(),
+            start,
+            end,
+        }
+    }
+
+    /// The verses, e.g. "John 3:16-17".
+    pub fn location(&self) -> String {
+        format_range(&self.book, self.start, self.end)
+    }
+
+    /// The reference to jump to.  Only the first verse is referred to when the bookmark spans
+    /// multiple chapters.
+    pub fn reference(&self) -> Reference {
+        let verses = match self.start.0 == self.end.0 {
+            true => self.start.1..=self.end.1,
+            false => self.start.1..=self.start.1,
+        };
+        Reference {
+            book: self.book.clone(),
+            chapter: self.start.0,
+            verses: Some(verses),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl Stored for Bookmark {
+    const NAME: &'static str = "bookmarks";
+    const VERSION: u32 = 1;
+
+    fn file() -> Option<PathBuf> {
+        bookmarks_file()
+    }
+}
+
+/// The bookmarks of the user, saved after every change.
+pub type Bookmarks = Store<Bookmark>;
+
+impl Store<Bookmark> {
+    pub fn add(&mut self, bookmark: Bookmark) -> Result<()> {
+        self.change(|bookmarks| bookmarks.push(bookmark))
+    }
+
+    pub fn rename(&mut self, i: usize, name: String) -> Result<()> {
+        self.change(|bookmarks| {
+            if let Some(bookmark) = bookmarks.get_mut(i) {
+                bookmark.name = name;
+            }
+        })
+    }
+
+    pub fn remove(&mut self, i: usize) -> Result<()> {
+        self.change(|bookmarks| {
+            if i < bookmarks.len() {
+                bookmarks.remove(i);
+            }
+        })
+    }
+
+    pub fn get(&self, i: usize) -> Option<&Bookmark> {
+        self.iter().nth(i)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("logos-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn saves_every_change() {
+        let path = temp_file("bookmarks-changes.json");
+        let mut bookmarks = Bookmarks::from_file(path.clone()).unwrap();
+        assert!(bookmarks.is_empty());
+        bookmarks
+            .add(Bookmark::new("John", (3, 16), (3, 17)))
+            .unwrap();
+        bookmarks
+            .add(Bookmark::new("Jude", (1, 3), (1, 3)))
+            .unwrap();
+        bookmarks.rename(0, "Love".to_string()).unwrap();
+        bookmarks.remove(1).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let file: serde_json::Value = serde_json::from_str(&contents).unwrap();
+        assert_eq!(file["version"], 1);
+
+        let bookmarks = Bookmarks::from_file(path.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let names: Vec<(&str, String)> = bookmarks
+            .iter()
+            .map(|b| (b.name.as_str(), b.location()))
+            .collect();
+        assert_eq!(names, [("Love", "John 3:16-17".to_string())]);
+    }
+
+    #[test]
+    fn jumps_to_the_first_chapter_of_a_bookmark() {
+        let reference = Bookmark::new("John", (3, 16), (3, 17)).reference();
+        assert_eq!((reference.chapter, reference.verses), (3, Some(16..=17)));
+        let reference = Bookmark::new("John", (3, 36), (4, 2)).reference();
+        assert_eq!((reference.chapter, reference.verses), (3, Some(36..=36)));
+    }
+}
@@ -0,0 +1,237 @@
+use crate::bible::Testament;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Well known information about a book, independent of the translation.
+#[derive(Debug)]
+pub struct BookInfo {
+    pub osis_id: &'static str,
+    /// English name.
+    pub name: &'static str,
+    pub testament: Testament,
+    /// Common abbreviations.  Matching ignores case, whitespace and dots.
+    pub abbreviations: &'static [&'static str],
+}
+
+const fn book(
+    osis_id: &'static str,
+    name: &'static str,
+    testament: Testament,
+    abbreviations: &'static [&'static str],
+) -> BookInfo {
+    BookInfo {
+        osis_id,
+        name,
+        testament,
+        abbreviations,
+    }
+}
+
+use Testament::{Apocrypha as AP, New as NT, Old as OT};
+
+/// Books with their OSIS IDs, in the order of the KJV (with apocrypha).
+#[rustfmt::skip]
+pub static BOOKS: &[BookInfo] = &[
+    book("Gen", "Genesis", OT, &["Ge", "Gn"]),
+    book("Exod", "Exodus", OT, &["Ex", "Exo"]),
+    book("Lev", "Leviticus", OT, &["Le", "Lv"]),
+    book("Num", "Numbers", OT, &["Nu", "Nm", "Nb"]),
+    book("Deut", "Deuteronomy", OT, &["Dt", "De"]),
+    book("Josh", "Joshua", OT, &["Jos", "Jsh"]),
+    book("Judg", "Judges", OT, &["Jdg", "Jg", "Jdgs"]),
+    book("Ruth", "Ruth", OT, &["Rth", "Ru"]),
+    book("1Sam", "1 Samuel", OT, &["1Sa", "1Sm", "1S"]),
+    book("2Sam", "2 Samuel", OT, &["2Sa", "2Sm", "2S"]),
+    book("1Kgs", "1 Kings", OT, &["1Ki", "1Kin", "1K"]),
+    book("2Kgs", "2 Kings", OT, &["2Ki", "2Kin", "2K"]),
+    book("1Chr", "1 Chronicles", OT, &["1Ch", "1Chron"]),
+    book("2Chr", "2 Chronicles", OT, &["2Ch", "2Chron"]),
+    book("Ezra", "Ezra", OT, &["Ezr"]),
+    book("Neh", "Nehemiah", OT, &["Ne"]),
+    book("Esth", "Esther", OT, &["Est", "Es"]),
+    book("Job", "Job", OT, &["Jb"]),
+    book("Ps", "Psalms", OT, &["Psalm", "Psa", "Pss", "Psm"]),
+    book("Prov", "Proverbs", OT, &["Pr", "Prv", "Pro"]),
+    book("Eccl", "Ecclesiastes", OT, &["Ecc", "Ec", "Qoh"]),
+    book("Song", "Song of Solomon", OT, &["SOS", "So", "Song of Songs", "Canticles", "Cant"]),
+    book("Isa", "Isaiah", OT, &["Is"]),
+    book("Jer", "Jeremiah", OT, &["Je", "Jr"]),
+    book("Lam", "Lamentations", OT, &["La"]),
+    book("Ezek", "Ezekiel", OT, &["Eze", "Ezk"]),
+    book("Dan", "Daniel", OT, &["Da", "Dn"]),
+    book("Hos", "Hosea", OT, &["Ho"]),
+    book("Joel", "Joel", OT, &["Jl", "Joe"]),
+    book("Amos", "Amos", OT, &["Am"]),
+    book("Obad", "Obadiah", OT, &["Ob"]),
+    book("Jonah", "Jonah", OT, &["Jon", "Jnh"]),
+    book("Mic", "Micah", OT, &["Mc"]),
+    book("Nah", "Nahum", OT, &["Na"]),
+    book("Hab", "Habakkuk", OT, &["Hb"]),
+    book("Zeph", "Zephaniah", OT, &["Zep", "Zp"]),
+    book("Hag", "Haggai", OT, &["Hg"]),
+    book("Zech", "Zechariah", OT, &["Zec", "Zc"]),
+    book("Mal", "Malachi", OT, &["Ml"]),
+    book("Tob", "Tobit", AP, &["Tb"]),
+    book("Jdt", "Judith", AP, &["Jdth"]),
+    book("EsthGr", "Esther (Greek)", AP, &["Greek Esther", "AddEsth", "GkEsth"]),
+    book("Wis", "Wisdom", AP, &["Wisdom of Solomon", "Ws"]),
+    book("Sir", "Sirach", AP, &["Ecclesiasticus", "Ecclus"]),
+    book("Bar", "Baruch", AP, &[]),
+    book("EpJer", "Letter of Jeremiah", AP, &["Epistle of Jeremiah", "LJe"]),
+    book("PrAzar", "Azariah", AP, &["Prayer of Azariah", "Song of Three", "Aza"]),
+    book("Sus", "Susanna", AP, &[]),
+    book("Bel", "Bel", AP, &["Bel and the Dragon"]),
+    // The three above as one book, the way German bibles and Zefania files have them.
+    book("AddDan", "Additions to Daniel", AP, &[]),
+    book("1Macc", "1 Maccabees", AP, &["1Mac", "1Ma", "1M"]),
+    book("2Macc", "2 Maccabees", AP, &["2Mac", "2Ma", "2M"]),
+    book("1Esd", "1 Esdras", AP, &["1Es"]),
+    book("PrMan", "Manasseh", AP, &["Prayer of Manasseh", "PMa"]),
+    book("2Esd", "2 Esdras", AP, &["2Es"]),
+    book("Matt", "Matthew", NT, &["Mt", "Mat"]),
+    book("Mark", "Mark", NT, &["Mk", "Mrk", "Mr"]),
+    book("Luke", "Luke", NT, &["Lk", "Luk", "Lu"]),
+    book("John", "John", NT, &["Jn", "Jhn", "Joh"]),
+    book("Acts", "Acts", NT, &["Ac", "Act"]),
+    book("Rom", "Romans", NT, &["Ro", "Rm"]),
+    book("1Cor", "1 Corinthians", NT, &["1Co"]),
+    book("2Cor", "2 Corinthians", NT, &["2Co"]),
+    book("Gal", "Galatians", NT, &["Ga"]),
+    book("Eph", "Ephesians", NT, &["Ephes"]),
+    book("Phil", "Philippians", NT, &["Php", "Pp"]),
+    book("Col", "Colossians", NT, &["Co"]),
+    book("1Thess", "1 Thessalonians", NT, &["1Th", "1Thes"]),
+    book("2Thess", "2 Thessalonians", NT, &["2Th", "2Thes"]),
+    book("1Tim", "1 Timothy", NT, &["1Ti", "1Tm"]),
+    book("2Tim", "2 Timothy", NT, &["2Ti", "2Tm"]),
+    book("Titus", "Titus", NT, &["Tit", "Ti"]),
+    book("Phlm", "Philemon", NT, &["Phm", "Philem", "Pm"]),
+    book("Heb", "Hebrews", NT, &["He"]),
+    book("Jas", "James", NT, &["Jm", "Jam"]),
+    book("1Pet", "1 Peter", NT, &["1Pe", "1Pt", "1P"]),
+    book("2Pet", "2 Peter", NT, &["2Pe", "2Pt", "2P"]),
+    book("1John", "1 John", NT, &["1Jn", "1Jhn", "1Jo"]),
+    book("2John", "2 John", NT, &["2Jn", "2Jhn", "2Jo"]),
+    book("3John", "3 John", NT, &["3Jn", "3Jhn", "3Jo"]),
+    book("Jude", "Jude", NT, &["Jud", "Jd"]),
+    book("Rev", "Revelation", NT, &["Re", "Rv", "Apocalypse", "Revelations"]),
+];
+
+/// USFM book codes -> OSIS IDs, for the books in `BOOKS`.
+#[rustfmt::skip]
+static USFM_CODES: &[(&str, &str)] = &[
+    ("GEN", "Gen"), ("EXO", "Exod"), ("LEV", "Lev"), ("NUM", "Num"), ("DEU", "Deut"),
+    ("JOS", "Josh"), ("JDG", "Judg"), ("RUT", "Ruth"), ("1SA", "1Sam"), ("2SA", "2Sam"),
+    ("1KI", "1Kgs"), ("2KI", "2Kgs"), ("1CH", "1Chr"), ("2CH", "2Chr"), ("EZR", "Ezra"),
+    ("NEH", "Neh"), ("EST", "Esth"), ("JOB", "Job"), ("PSA", "Ps"), ("PRO", "Prov"),
+    ("ECC", "Eccl"), ("SNG", "Song"), ("ISA", "Isa"), ("JER", "Jer"), ("LAM", "Lam"),
+    ("EZK", "Ezek"), ("DAN", "Dan"), ("HOS", "Hos"), ("JOL", "Joel"), ("AMO", "Amos"),
+    ("OBA", "Obad"), ("JON", "Jonah"), ("MIC", "Mic"), ("NAM", "Nah"), ("HAB", "Hab"),
+    ("ZEP", "Zeph"), ("HAG", "Hag"), ("ZEC", "Zech"), ("MAL", "Mal"),
+    ("TOB", "Tob"), ("JDT", "Jdt"), ("ESG", "EsthGr"), ("WIS", "Wis"), ("SIR", "Sir"),
+    ("BAR", "Bar"), ("LJE", "EpJer"), ("S3Y", "PrAzar"), ("SUS", "Sus"), ("BEL", "Bel"),
+    ("1MA", "1Macc"), ("2MA", "2Macc"), ("1ES", "1Esd"), ("MAN", "PrMan"), ("2ES", "2Esd"),
+    ("MAT", "Matt"), ("MRK", "Mark"), ("LUK", "Luke"), ("JHN", "John"), ("ACT", "Acts"),
+    ("ROM", "Rom"), ("1CO", "1Cor"), ("2CO", "2Cor"), ("GAL", "Gal"), ("EPH", "Eph"),
+    ("PHP", "Phil"), ("COL", "Col"), ("1TH", "1Thess"), ("2TH", "2Thess"), ("1TI", "1Tim"),
+    ("2TI", "2Tim"), ("TIT", "Titus"), ("PHM", "Phlm"), ("HEB", "Heb"), ("JAS", "Jas"),
+    ("1PE", "1Pet"), ("2PE", "2Pet"), ("1JN", "1John"), ("2JN", "2John"), ("3JN", "3John"),
+    ("JUD", "Jude"), ("REV", "Rev"),
+];
+
+/// Books that have a single chapter in every tradition, which are referred to by verse.
+const SINGLE_CHAPTER: &[&str] = &[
+    "Obad", "Phlm", "2John", "3John", "Jude", "EpJer", "PrAzar", "Sus", "Bel", "PrMan",
+];
+
+pub fn book_info(osis_id: &str) -> Option<&'static BookInfo> {
+    BOOKS.iter().find(|b| b.osis_id == osis_id)
+}
+
+/// The English name of the book, or the ID itself if it is not a known book.
+pub fn book_name(osis_id: &str) -> &str {
+    book_info(osis_id).map_or(osis_id, |b| b.name)
+}
+
+/// The OSIS ID of a USFM book code, e.g. "1CO" -> "1Cor".  Front and back matter have none.
+pub fn osis_id_from_usfm(code: &str) -> Option<&'static str> {
+    USFM_CODES
+        .iter()
+        .find(|(usfm, _)| usfm.eq_ignore_ascii_case(code))
+        .map(|(_, osis_id)| *osis_id)
+}
+
+/// The OSIS ID of a Zefania book number.  1 to 66 are the protestant canon, the apocrypha
+/// follow in the order of the German Bibles the format comes from.
+pub fn osis_id_from_number(number: usize) -> Option<&'static str> {
+    const APOCRYPHA: [&str; 10] = [
+        "Jdt", "Wis", "Tob", "Sir", "Bar", "1Macc", "2Macc", "AddDan", "EsthGr", "PrMan",
+    ];
+    let mut protestant = BOOKS.iter().filter(|b| b.testament != AP);
+    match number {
+        1..=66 => protestant.nth(number - 1).map(|b| b.osis_id),
+        _ => APOCRYPHA.get(number.checked_sub(67)?).copied(),
+    }
+}
+
+/// Whether the book has a single chapter, e.g. "Jude 3" is a verse rather than a chapter.  This is
+/// about the canon, a file can have one chapter of any book.
+pub fn has_single_chapter(osis_id: &str) -> bool {
+    SINGLE_CHAPTER.contains(&osis_id)
+}
+
+/// Finds a book by its OSIS ID, English name or one of the common abbreviations.
+pub fn find_book(name: &str) -> Option<&'static BookInfo> {
+    let needle = normalize_name(name);
+    BOOKS.iter().find(|b| {
+        [b.osis_id, b.name]
+            .iter()
+            .chain(b.abbreviations)
+            .any(|n| normalize_name(n) == needle)
+    })
+}
+
+/// Position of the book in `BOOKS`, unknown books come last.
+pub fn canonical_position(osis_id: &str) -> usize {
+    BOOKS
+        .iter()
+        .position(|b| b.osis_id == osis_id)
+        .unwrap_or(BOOKS.len())
+}
+
+/// Normalizes a book name for matching, ignoring case, whitespace and dots.  Roman numerals
+/// are also accepted for numbered books ("II Kings" -> "2kings").
+pub fn normalize_name(name: &str) -> String {
+    let name = name.trim();
+    let name = [("III ", "3"), ("II ", "2"), ("I ", "1")]
+        .iter()
+        .find_map(|(roman, n)| name.strip_prefix(roman).map(|rest| format!("{n}{rest}")))
+        .unwrap_or_else(|| name.to_string());
+
+    name.chars()
+        .filter(|c| !c.is_whitespace() && *c != '.')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_names() {
+        assert_eq!(normalize_name(" 1 Cor. "), "1cor");
+        assert_eq!(normalize_name("II Kings"), "2kings");
+        assert_eq!(normalize_name("III John"), "3john");
+        assert_eq!(normalize_name("Isaiah"), "isaiah");
+    }
+
+    #[test]
+    fn names_books_by_their_id() {
+        assert_eq!(book_name("1Cor"), "1 Corinthians");
+        assert_eq!(book_name("Nope"), "Nope");
+        assert_eq!(book_info("Rev").map(|b| b.testament), Some(Testament::New));
+    }
+}
@@ -0,0 +1,731 @@
+use crate::bible::Bible;
+use crate::components::book_column::ColumnChapter;
+use crate::concordance::{Concordance, Term};
+use crate::crossrefs::CrossReferences;
+use crate::editor::{self, EditRequest};
+use crate::icons::IconSet;
+use crate::import;
+use crate::lexicon::Lexicon;
+use crate::notes::{Notes, osis_reference};
+use crate::prelude::*;
+use crate::reference::{Reference, format_range};
+use crate::search::{SearchIndex, SearchRequest};
+use crate::sword::{self, ModuleKind};
+use crate::translations::TranslationRegistry;
+use clap::{Parser, Subcommand, ValueEnum};
+use ratatui::backend::IntoCrossterm;
+use ratatui::crossterm::style::{Attribute, ContentStyle};
+use ratatui::style::Modifier;
+use std::io::{IsTerminal, Write};
+use std::process::ExitCode;
+
+/// Command line arguments.  Without a command the reader is started.
+#[derive(Debug, Parser)]
+#[command(
+    name = "logos",
+    version,
+    about = "Read and search the Bible in the terminal"
+)]
+pub struct Cli {
+    /// Config file to use instead of the default one.
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    /// Glyphs to use, instead of the `icons` setting or detecting them.
+    #[arg(long, global = true, value_enum)]
+    pub icons: Option<IconSet>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print a passage, e.g. "John 3:16-18" or "Ps 23".
+    Read {
+        reference: String,
+        #[command(flatten)]
+        output: OutputArgs,
+        /// Wrap at this width, 0 for a verse per line.  Defaults to the width of the terminal.
+        #[arg(long)]
+        width: Option<usize>,
+        /// Colour the words of Christ.
+        #[arg(long)]
+        red_letter: bool,
+    },
+    /// Print the verses matching a search, e.g. "faith hope in:nt".
+    Search {
+        query: String,
+        #[command(flatten)]
+        output: OutputArgs,
+        /// Print at most this many verses.
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Print every occurrence of a word or Strong's number in context, e.g. "faith" or "G4102".
+    Concordance {
+        term: String,
+        #[command(flatten)]
+        output: OutputArgs,
+        /// Print the number of occurrences per book instead.
+        #[arg(long)]
+        books: bool,
+    },
+    /// List the books of a translation.
+    Books {
+        /// Translation to use, or the path of a bible file.  The default one if left out.
+        #[arg(long, short)]
+        translation: Option<String>,
+    },
+    /// Manage the installed translations.
+    Translations {
+        #[command(subcommand)]
+        command: TranslationsCommand,
+    },
+    /// Read the installed SWORD modules, e.g. in ~/.sword.
+    Modules {
+        #[command(subcommand)]
+        command: ModulesCommand,
+    },
+    /// Manage the Strong's dictionaries of the Strong's panel.
+    Lexicon {
+        #[command(subcommand)]
+        command: LexiconCommand,
+    },
+    /// Manage the cross reference dataset of the references panel.
+    Crossrefs {
+        #[command(subcommand)]
+        command: CrossrefsCommand,
+    },
+    /// Read and write the notes of the notes panel.
+    Notes {
+        #[command(subcommand)]
+        command: NotesCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum NotesCommand {
+    /// List the notes with their first line, in the numbering of the KJV.
+    List,
+    /// Print the notes about a verse, e.g. "John 3:16".
+    Show {
+        reference: String,
+        /// Translation to look up the verse and print the references in.
+        #[arg(long, short)]
+        translation: Option<String>,
+    },
+    /// Write the note about a passage in `$EDITOR`, e.g. "John 3:16-18".
+    Edit {
+        reference: String,
+        /// Translation to look up the passage in.
+        #[arg(long, short)]
+        translation: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CrossrefsCommand {
+    /// Read a cross reference dataset, replacing the imported one.  Either the TSV of
+    /// OpenBible.info or the Treasury of Scripture Knowledge as a verse and a list of references
+    /// per line.
+    Import { path: PathBuf },
+    /// Print the cross references of a verse by relevance, e.g. "John 3:16".
+    Show {
+        reference: String,
+        /// Translation to look up the verse and print the references in.
+        #[arg(long, short)]
+        translation: Option<String>,
+        /// Print at most this many references.
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LexiconCommand {
+    /// Read a Strong's dictionary in XML into the lexicon, e.g. the Hebrew one of Open Scriptures.
+    Import { path: PathBuf },
+    /// Print the entry of a Strong's number, e.g. "H7225" or "G3056".
+    Show { number: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ModulesCommand {
+    /// List the modules that can be read.
+    List,
+    /// Print the entries of a commentary for a passage, or of a lexicon for a key like "G3056".
+    Show {
+        module: String,
+        key: String,
+        /// Translation to look up the book names of the passage in.
+        #[arg(long, short)]
+        translation: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TranslationsCommand {
+    /// List the installed and bundled translations.
+    List,
+    /// Check a bible file from anywhere and copy it into the translations directory.
+    #[command(visible_alias = "import")]
+    Install {
+        path: PathBuf,
+        /// Replace an installed translation of the same name.
+        #[arg(long)]
+        force: bool,
+        /// Only print the report, without installing.
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+/// Arguments of the commands that print verses.
+#[derive(Debug, clap::Args)]
+pub struct OutputArgs {
+    /// Translation to use, or the path of a bible file.  The default one if left out.
+    #[arg(long, short)]
+    translation: Option<String>,
+    /// Style the text with the colours of the theme.
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Only when printing to a terminal and `NO_COLOR` is not set.
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Auto => {
+                std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Runs a command without the reader.  Fails with 1 when a search or lookup finds nothing, like
+/// `grep`.
+pub fn run(command: Command) -> Result<ExitCode> {
+    let mut out = std::io::stdout().lock();
+    match command {
+        Command::Read {
+            reference,
+            output,
+            width,
+            red_letter,
+        } => {
+            let bible = load_bible(output.translation.as_deref())?;
+            let reference = Reference::parse(&reference, &bible)?;
+            let color = output.color.enabled();
+
+            let chapter = bible.get_chapter(&reference.book, reference.chapter)?;
+            let mut chapter = ColumnChapter::from_chapter(&bible, chapter);
+            if let Some(verses) = &reference.verses {
+                chapter.verses.retain(|v| verses.contains(&v.number));
+                chapter.show_heading = false;
+            }
+
+            match width.or_else(terminal_width) {
+                Some(width) if width > 0 => {
+                    for line in chapter.build(width, &[], &[], red_letter) {
+                        write_line(&mut out, &line, color)?;
+                    }
+                }
+                // A verse per line, for scripts.
+                _ => {
+                    if chapter.show_heading {
+                        write_line(&mut out, &ColumnChapter::heading(chapter.number), color)?;
+                    }
+                    for verse in &chapter.verses {
+                        let spans = verse.build(
+                            0..verse.words.len(),
+                            Style::default(),
+                            &[],
+                            false,
+                            red_letter,
+                        );
+                        write_line(&mut out, &Line::from(spans), color)?;
+                    }
+                }
+            }
+        }
+
+        Command::Search {
+            query,
+            output,
+            limit,
+        } => {
+            let bible = load_bible(output.translation.as_deref())?;
+            let request = SearchRequest::parse(&query, &bible)?;
+            let results = SearchIndex::build(&bible).search(&bible, &request);
+            let color = output.color.enabled();
+
+            for hit in results.hits.iter().take(limit.unwrap_or(usize::MAX)) {
+                let mut spans = vec![Span::raw(hit.reference.to_string()), Span::raw("\t")];
+                let mut end = 0;
+                for range in &hit.highlights {
+                    spans.push(Span::raw(&hit.text[end..range.start]));
+                    spans.push(Span::styled(&hit.text[range.clone()], theme().search_hit));
+                    end = range.end;
+                }
+                spans.push(Span::raw(&hit.text[end..]));
+                write_line(&mut out, &Line::from(spans), color)?;
+            }
+            if results.hits.is_empty() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+
+        Command::Concordance {
+            term,
+            output,
+            books,
+        } => {
+            let bible = load_bible(output.translation.as_deref())?;
+            let term = Term::parse(&term)?;
+            let results = Concordance::load(&bible).lookup(&bible, term);
+            let color = output.color.enabled();
+
+            if books {
+                for (book, count) in &results.books {
+                    writeln!(out, "{book}\t{count}")?;
+                }
+            } else {
+                for hit in &results.hits {
+                    let spans = vec![
+                        Span::raw(hit.reference.to_string()),
+                        Span::raw("\t"),
+                        Span::raw(&hit.text[..hit.range.start]),
+                        Span::raw("\t"),
+                        Span::styled(&hit.text[hit.range.clone()], theme().search_hit),
+                        Span::raw("\t"),
+                        Span::raw(&hit.text[hit.range.end..]),
+                    ];
+                    write_line(&mut out, &Line::from(spans), color)?;
+                }
+            }
+            if results.hits.is_empty() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+
+        Command::Books { translation } => {
+            let bible = load_bible(translation.as_deref())?;
+            for (id, book) in bible.iter_books() {
+                writeln!(out, "{id}\t{}\t{}", book.name, book.chapters.len())?;
+            }
+        }
+
+        Command::Translations {
+            command: TranslationsCommand::List,
+        } => {
+            for info in TranslationRegistry::discover().iter() {
+                writeln!(out, "{}\t{}\t{}", info.name, info.language, info.title)?;
+            }
+        }
+
+        Command::Translations {
+            command: TranslationsCommand::Install { path, force, check },
+        } => {
+            let report = import::validate(&path)?;
+            for issue in &report.issues {
+                writeln!(out, "{}:{issue}", path.display())?;
+            }
+            writeln!(
+                out,
+                "{} {}: {} books, {} chapters, {} verses, {} errors, {} warnings",
+                report.format.name(),
+                path.display(),
+                report.books,
+                report.chapters,
+                report.verses,
+                report.errors(),
+                report.warnings()
+            )?;
+            if check {
+                return Ok(match report.errors() {
+                    0 => ExitCode::SUCCESS,
+                    _ => ExitCode::FAILURE,
+                });
+            }
+            let info = import::install(&report, force)?;
+            writeln!(
+                out,
+                "Installed {} ({}) to {:?}",
+                info.name, info.title, info.path
+            )?;
+        }
+
+        Command::Modules {
+            command: ModulesCommand::List,
+        } => {
+            for module in sword::discover() {
+                let conf = &module.conf;
+                writeln!(
+                    out,
+                    "{}\t{}\t{}\t{}",
+                    module.name,
+                    module.kind,
+                    conf.language(),
+                    conf.description()
+                )?;
+            }
+        }
+
+        Command::Modules {
+            command:
+                ModulesCommand::Show {
+                    module,
+                    key,
+                    translation,
+                },
+        } => {
+            let module = sword::find(&module)?;
+            let mut found = false;
+            if module.kind == ModuleKind::Lexicon {
+                if let Some(entry) = module.lexicon_entry(&key)? {
+                    writeln!(out, "{}", sword::plain_text(module.markup, &entry))?;
+                    found = true;
+                }
+            } else {
+                let bible = load_bible(translation.as_deref())?;
+                let reference = Reference::parse(&key, &bible)?;
+                let chapter = bible.get_chapter(&reference.book, reference.chapter)?;
+                let mut verses = module.verses()?;
+                for verse in &chapter.verses {
+                    if reference
+                        .verses
+                        .as_ref()
+                        .is_some_and(|v| !v.contains(&verse.number))
+                    {
+                        continue;
+                    }
+                    let Some(entry) =
+                        verses.entry(&reference.book, reference.chapter, verse.number)?
+                    else {
+                        continue;
+                    };
+                    let location = format_range(
+                        &reference.book,
+                        (reference.chapter, verse.number),
+                        (reference.chapter, verse.number),
+                    );
+                    writeln!(
+                        out,
+                        "{location}\n{}\n",
+                        sword::plain_text(module.markup, &entry)
+                    )?;
+                    found = true;
+                }
+            }
+            if !found {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+
+        Command::Lexicon {
+            command: LexiconCommand::Import { path },
+        } => {
+            let mut lexicon = Lexicon::load()?;
+            let count = lexicon.import(&path)?;
+            writeln!(
+                out,
+                "Imported {count} entries from {}, the lexicon has {} entries",
+                path.display(),
+                lexicon.len()
+            )?;
+        }
+
+        Command::Lexicon {
+            command: LexiconCommand::Show { number },
+        } => {
+            let Some(entry) = Lexicon::load()?.get(&number) else {
+                return Ok(ExitCode::FAILURE);
+            };
+            writeln!(
+                out,
+                "{}\t{}\t{}\t{}",
+                entry.number, entry.lemma, entry.transliteration, entry.pronunciation
+            )?;
+            writeln!(out, "{}", entry.definition)?;
+            if !entry.kjv_usage.is_empty() {
+                writeln!(out, "KJV: {}", entry.kjv_usage)?;
+            }
+        }
+
+        Command::Crossrefs {
+            command: CrossrefsCommand::Import { path },
+        } => {
+            let mut cross_references = CrossReferences::load()?;
+            let count = cross_references.import(&path)?;
+            writeln!(
+                out,
+                "Imported {count} cross references from {}",
+                path.display()
+            )?;
+        }
+
+        Command::Crossrefs {
+            command:
+                CrossrefsCommand::Show {
+                    reference,
+                    translation,
+                    limit,
+                },
+        } => {
+            let bible = load_bible(translation.as_deref())?;
+            let reference = Reference::parse(&reference, &bible)?;
+            let versification = bible.get_versification();
+            let (chapter, verse) = versification.map_to_kjv(
+                &reference.book,
+                reference.chapter,
+                reference.first_verse(),
+            );
+
+            let cross_references = CrossReferences::load()?;
+            let mut found: Vec<_> = cross_references
+                .get(&reference.book, chapter, verse)
+                .iter()
+                .enumerate()
+                .collect();
+            found.sort_by_key(|(rank, r)| (std::cmp::Reverse(r.votes), *rank));
+            for (_, cross_reference) in found.iter().take(limit.unwrap_or(usize::MAX)) {
+                let Some((book, start, end)) = cross_reference.range() else {
+                    continue;
+                };
+                let start = versification.map_from_kjv(book, start.0, start.1);
+                let end = versification.map_from_kjv(book, end.0, end.1);
+                let votes = cross_reference
+                    .votes
+                    .map(|v| v.to_string())
+                    .unwrap_or_default();
+                writeln!(out, "{}\t{votes}", format_range(book, start, end))?;
+            }
+            if found.is_empty() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+
+        Command::Notes {
+            command: NotesCommand::List,
+        } => {
+            for note in Notes::load()?.iter() {
+                let Some((book, start, end)) = note.range() else {
+                    continue;
+                };
+                let first_line = note.text.lines().next().unwrap_or_default();
+                writeln!(out, "{}\t{first_line}", format_range(book, start, end))?;
+            }
+        }
+
+        Command::Notes {
+            command:
+                NotesCommand::Show {
+                    reference,
+                    translation,
+                },
+        } => {
+            let bible = load_bible(translation.as_deref())?;
+            let reference = Reference::parse(&reference, &bible)?;
+            let versification = bible.get_versification();
+            let (chapter, verse) = versification.map_to_kjv(
+                &reference.book,
+                reference.chapter,
+                reference.first_verse(),
+            );
+
+            let notes = Notes::load()?;
+            let found = notes.for_verse(&reference.book, chapter, verse);
+            for (i, note) in found.iter().enumerate() {
+                let Some((book, start, end)) = note.range() else {
+                    continue;
+                };
+                let start = versification.map_from_kjv(book, start.0, start.1);
+                let end = versification.map_from_kjv(book, end.0, end.1);
+                if i > 0 {
+                    writeln!(out)?;
+                }
+                writeln!(out, "{}", format_range(book, start, end))?;
+                writeln!(out, "{}", note.text)?;
+            }
+            if found.is_empty() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+
+        Command::Notes {
+            command:
+                NotesCommand::Edit {
+                    reference,
+                    translation,
+                },
+        } => {
+            let bible = load_bible(translation.as_deref())?;
+            let reference = Reference::parse(&reference, &bible)?;
+            let versification = bible.get_versification();
+            // A whole chapter is a note about all of its verses.
+            let verses = match reference.verses.clone() {
+                Some(verses) => verses,
+                None => {
+                    let chapter = bible.get_chapter(&reference.book, reference.chapter)?;
+                    1..=chapter.verses.last().map_or(1, |v| v.number)
+                }
+            };
+            let start =
+                versification.map_to_kjv(&reference.book, reference.chapter, *verses.start());
+            let end = versification.map_to_kjv(&reference.book, reference.chapter, *verses.end());
+            let osis = osis_reference(&reference.book, start, end);
+
+            let mut notes = Notes::load()?;
+            let text = notes
+                .get(&osis)
+                .map(|note| format!("{}\n", note.text))
+                .unwrap_or_default();
+            let edited = editor::edit(&EditRequest {
+                name: osis.clone(),
+                text,
+            })?;
+            notes.set(&osis, &edited)?;
+            let location = format_range(
+                &reference.book,
+                (reference.chapter, *verses.start()),
+                (reference.chapter, *verses.end()),
+            );
+            match notes.get(&osis) {
+                Some(_) => writeln!(out, "Saved note on {location}")?,
+                None => writeln!(out, "No note on {location}")?,
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Loads an installed translation, or a bible file that is not installed, e.g. a draft.
+fn load_bible(translation: Option<&str>) -> Result<Bible> {
+    if let Some(path) = translation.map(Path::new)
+        && path.exists()
+    {
+        return Bible::from_file(path);
+    }
+    let registry = TranslationRegistry::discover();
+    let info = match translation {
+        Some(name) => registry.get(name)?,
+        None => registry.get_default()?,
+    };
+    Bible::from_translation(info)
+}
+
+fn terminal_width() -> Option<usize> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    ratatui::crossterm::terminal::size()
+        .ok()
+        .map(|(width, _)| width as usize)
+}
+
+/// Writes the line with the styles as ANSI escape codes, or as plain text.
+fn write_line(out: &mut impl Write, line: &Line, color: bool) -> Result<()> {
+    for span in &line.spans {
+        match color {
+            true => write!(
+                out,
+                "{}",
+                ansi_style(line.style.patch(span.style)).apply(&span.content)
+            )?,
+            false => write!(out, "{}", span.content)?,
+        }
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+fn ansi_style(style: Style) -> ContentStyle {
+    let mut ansi = ContentStyle::new();
+    ansi.foreground_color = style.fg.map(IntoCrossterm::into_crossterm);
+    ansi.background_color = style.bg.map(IntoCrossterm::into_crossterm);
+    let attributes = [
+        (Modifier::BOLD, Attribute::Bold),
+        (Modifier::DIM, Attribute::Dim),
+        (Modifier::ITALIC, Attribute::Italic),
+        (Modifier::UNDERLINED, Attribute::Underlined),
+        (Modifier::REVERSED, Attribute::Reverse),
+        (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+    ];
+    for (modifier, attribute) in attributes {
+        if style.add_modifier.contains(modifier) {
+            ansi.attributes.set(attribute);
+        }
+    }
+    ansi
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn parses_the_command_line() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from([
+            "logos",
+            "read",
+            "John 3:16",
+            "-t",
+            "web",
+            "--width",
+            "0",
+            "--icons",
+            "ascii",
+        ])
+        .unwrap();
+        assert_eq!(cli.icons, Some(IconSet::Ascii));
+        let Some(Command::Read {
+            reference,
+            output,
+            width,
+            red_letter,
+        }) = cli.command
+        else {
+            panic!("{:?}", cli.command);
+        };
+        assert_eq!(reference, "John 3:16");
+        assert_eq!(output.translation.as_deref(), Some("web"));
+        assert_eq!(output.color, ColorChoice::Auto);
+        assert_eq!((width, red_letter), (Some(0), false));
+
+        assert!(Cli::try_parse_from(["logos"]).unwrap().command.is_none());
+        assert!(Cli::try_parse_from(["logos", "search", "love", "--color", "sometimes"]).is_err());
+        assert!(Cli::try_parse_from(["logos", "translations"]).is_err());
+    }
+
+    #[test]
+    fn writes_lines_with_or_without_colours() {
+        let line = Line::from(vec![
+            Span::raw("It "),
+            Span::styled("is", Style::new().italic()),
+        ]);
+        let write = |color| {
+            let mut out = Vec::new();
+            write_line(&mut out, &line, color).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        assert_eq!(write(false), "It is\n");
+        let colored = write(true);
+        assert!(colored.starts_with("It "), "{colored:?}");
+        assert!(colored.contains("\x1b[3mis"), "{colored:?}");
+    }
+}
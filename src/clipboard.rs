@@ -0,0 +1,71 @@
+use crate::prelude::*;
+use base64::Engine;
+use std::io::Write;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Where yanked text goes, selected with the `LOGOS_CLIPBOARD` environment variable:
+/// - "osc52" (default): The system clipboard, through the terminal.
+/// - "stdout": Written as is, only useful when the output is captured.
+/// - "file:<path>": Appended to the file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipboardSink {
+    Osc52,
+    Stdout,
+    File(PathBuf),
+}
+
+impl ClipboardSink {
+    pub fn from_env() -> ClipboardSink {
+        match std::env::var(CLIPBOARD_ENV) {
+            Ok(sink) => Self::parse(&sink).unwrap_or_else(|| {
+                warn!("Unknown {CLIPBOARD_ENV} {sink:?}, using the terminal clipboard");
+                ClipboardSink::Osc52
+            }),
+            Err(_) => ClipboardSink::Osc52,
+        }
+    }
+
+    pub fn parse(sink: &str) -> Option<ClipboardSink> {
+        match sink {
+            "osc52" => Some(ClipboardSink::Osc52),
+            "stdout" => Some(ClipboardSink::Stdout),
+            _ => sink
+                .strip_prefix("file:")
+                .filter(|path| !path.is_empty())
+                .map(|path| ClipboardSink::File(PathBuf::from(path))),
+        }
+    }
+
+    pub fn copy(&self, text: &str) -> Result<()> {
+        match self {
+            ClipboardSink::Osc52 => {
+                let mut stdout = std::io::stdout();
+                stdout.write_all(osc52(text).as_bytes())?;
+                stdout.flush()?;
+            }
+            ClipboardSink::Stdout => {
+                let mut stdout = std::io::stdout();
+                writeln!(stdout, "{text}")?;
+                stdout.flush()?;
+            }
+            ClipboardSink::File(path) => {
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)?;
+                writeln!(file, "{text}")?;
+            }
+        }
+        debug!("Copied {} bytes to {self:?}", text.len());
+        Ok(())
+    }
+}
+
+/// The escape sequence that asks the terminal to set the clipboard to `text`.
+fn osc52(text: &str) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    format!("\x1b]52;c;{encoded}\x07")
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
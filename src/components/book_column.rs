@@ -1,8 +1,9 @@
 use crate::{
-    bible::{Bible, Chapter},
+    bible::{Bible, Chapter, Inline, VerseView, WordTag, text_at},
     components::Component,
     prelude::*,
 };
+use std::ops::{Range, RangeInclusive};
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -10,26 +11,42 @@ use crate::{
 pub struct Column {
     pub width: usize,
     pub chapters: Vec<ColumnChapter>,
+    /// Verses that should be styled differently when rendering.
+    pub highlights: Vec<VerseHighlight>,
+    /// (chapter, verses) that have notes, marked after the verse number.
+    pub noted: Vec<(usize, RangeInclusive<usize>)>,
+    /// Colour the words of Christ.
+    pub red_letter: bool,
 }
 
 impl Column {
-    pub fn new(
+    /// Fills a column of `width * height` characters, starting with the `overflow` of the previous
+    /// column and then pulling chapters from `chapters` until the budget runs out.
+    ///
+    /// Returns the column and the part of the last chapter that did not fit, which should be
+    /// passed as the `overflow` of the next column.
+    pub fn new<'a>(
         width: usize,
         height: usize,
         bible: &Bible,
-        start_chapter: &Chapter,
-        overflow: Option<ColumnChapter>,
+        chapters: &mut impl Iterator<Item = &'a Chapter>,
+        mut overflow: Option<ColumnChapter>,
     ) -> (Column, Option<ColumnChapter>) {
         let mut remaining_budget = width * height;
-        let mut chapters: Vec<ColumnChapter> = Vec::new();
-
-        // TODO: Handle.
-        assert!(overflow.is_none());
+        let mut column_chapters: Vec<ColumnChapter> = Vec::new();
+        let mut remainder = None;
+
+        while remaining_budget > 0 {
+            let chapter = match overflow.take() {
+                Some(overflow) => overflow,
+                None => match chapters.next() {
+                    Some(chapter) => ColumnChapter::from_chapter(bible, chapter),
+                    None => break,
+                },
+            };
 
-        let remainder = None;
-        loop {
-            let chapter = ColumnChapter::from_chapter_naive(bible, start_chapter, width);
-            let (fit, remainder) = chapter.split(width, remaining_budget);
+            let (fit, rest) = chapter.split(width, remaining_budget);
+            remainder = rest;
 
             match fit {
                 None => {
@@ -39,22 +56,26 @@ impl Column {
 
                 Some(fit) => {
                     let fit_consumed = fit.consumed_chars(width);
-                    debug!("consumed; {fit_consumed}");
-                    debug!("budhet: {remaining_budget}");
-                    chapters.push(fit);
-                    assert!(fit_consumed <= remaining_budget); // Fit can't be larger than the budet.
+                    assert!(fit_consumed <= remaining_budget); // Fit can't be larger than the budget.
+                    column_chapters.push(fit);
                     remaining_budget -= fit_consumed;
                     remaining_budget = remaining_budget.saturating_sub(width); // Newline.
                 }
             }
 
-            // If we had to split, or there is no budget left, time to stop.
-            if remainder.is_some() || remaining_budget == 0 {
+            // If we had to split, time to stop.
+            if remainder.is_some() {
                 break;
             }
         }
 
-        let column = Column { width, chapters };
+        let column = Column {
+            width,
+            chapters: column_chapters,
+            highlights: Vec::new(),
+            noted: Vec::new(),
+            red_letter: false,
+        };
         (column, remainder)
     }
 
@@ -72,6 +93,20 @@ impl Column {
             })
             .sum()
     }
+
+    /// The (chapter, verse) the column starts with.
+    pub fn first_verse(&self) -> Option<(usize, usize)> {
+        let chapter = self.chapters.first()?;
+        let verse = chapter.verses.first()?;
+        Some((chapter.number, verse.number))
+    }
+
+    pub fn contains(&self, chapter: usize, verse: usize) -> bool {
+        self.chapters
+            .iter()
+            .filter(|c| c.number == chapter)
+            .any(|c| c.verses.iter().any(|v| v.number == verse))
+    }
 }
 
 impl Component for Column {
@@ -82,17 +117,15 @@ impl Component for Column {
     fn render(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
         let mut lines: Vec<Line> = Vec::new();
 
-        // TODO: Build
         for (i, chapter) in self.chapters.iter().enumerate() {
             if i > 0 {
                 lines.push(Line::raw(""));
             }
-            lines.extend(chapter.build());
+            lines.extend(chapter.build(self.width, &self.highlights, &self.noted, self.red_letter));
         }
 
-        Paragraph::new(lines)
-            .wrap(ratatui::widgets::Wrap { trim: false })
-            .render(area, buf);
+        // Wrapping is done while building, so that it matches the layout.
+        Paragraph::new(lines).render(area, buf);
 
         Ok(())
     }
@@ -107,157 +140,205 @@ pub struct ColumnChapter {
     pub verses: Vec<ColumnVerseSegment>,
 }
 
+/// A run of words from a single verse that is placed on a row.
+#[derive(Debug, Clone)]
+struct RowItem {
+    /// Index into `ColumnChapter::verses`.
+    verse: usize,
+    /// Range of words in the verse.
+    words: std::ops::Range<usize>,
+}
+
+#[derive(Debug, Default)]
+struct Row {
+    /// Leading whitespace, used for poetry.
+    indent: usize,
+    items: Vec<RowItem>,
+}
+
+/// Characters of indentation per poetry level.
+const POETRY_INDENT: usize = 2;
+
 impl ColumnChapter {
-    /// Does not check splitting for the chapter, but does split verses.
-    pub fn from_chapter_naive(bible: &Bible, chapter: &Chapter, width: usize) -> Self {
-        // TODO: Tweak reserve.
-        let mut column_verses = Vec::new();
-        column_verses.reserve(10);
-
-        // Create all of the verses, splitting as we go.
-        let current_offset = 0;
-        chapter.get_verses().for_each(|verse| {
-            let mut remainder = ColumnVerseSegment::new_naive(
-                verse.number,
-                &verse.collect_string(bible.get_raw_data()),
-            );
-
-            // Need to split as we go.
-            loop {
-                let (first, next) = remainder.split_at_wrap(width, current_offset);
-                match first {
-                    None => break,
-                    Some(first) => column_verses.push(first),
-                };
-                match next {
-                    None => break,
-                    Some(r) => remainder = r,
-                }
-            }
-        });
+    /// Does not check splitting for the chapter, that is done by `split`.
+    pub fn from_chapter(bible: &Bible, chapter: &Chapter) -> Self {
+        let verses = chapter
+            .get_verses()
+            .map(|verse| ColumnVerseSegment::from_verse(bible, verse))
+            .collect();
 
         ColumnChapter {
             show_heading: true,
             number: chapter.number,
-            verses: column_verses,
+            verses,
+        }
+    }
+
+    /// Word wraps the verses into rows of `width`.  The heading is not included.
+    ///
+    /// The verse number is kept on the same row as the first word of the verse.  Words that are
+    /// wider than the row get a row of their own and are cut off when rendering.  Poetry lines
+    /// start on a new row and are indented, with a hanging indent when they wrap.
+    fn rows(&self, width: usize) -> Vec<Row> {
+        let mut rows: Vec<Row> = Vec::new();
+        let mut row = Row::default();
+        let mut row_len = 0;
+        let mut level = 0;
+
+        for (v, verse) in self.verses.iter().enumerate() {
+            for (w, word) in verse.words.iter().enumerate() {
+                let mut word_len = word.len();
+                if w == 0 {
+                    word_len += verse.get_number_char_size();
+                }
+
+                // Going in and out of poetry also starts a new row.
+                let new_line = word.break_before || word.level != level;
+                level = word.level;
+
+                // The first word in the row does not need a leading whitespace.
+                let consumption = if row.items.is_empty() {
+                    word_len
+                } else {
+                    word_len + 1
+                };
+                if !row.items.is_empty() && (new_line || row_len + consumption > width) {
+                    rows.push(std::mem::take(&mut row));
+                    row.indent = match new_line {
+                        true => level * POETRY_INDENT,
+                        false if level > 0 => (level + 1) * POETRY_INDENT,
+                        false => 0,
+                    };
+                    row_len = row.indent + word_len;
+                } else {
+                    if row.items.is_empty() {
+                        row.indent = level * POETRY_INDENT;
+                        row_len = row.indent;
+                    }
+                    row_len += consumption;
+                }
+
+                match row.items.last_mut() {
+                    Some(item) if item.verse == v => item.words.end = w + 1,
+                    _ => row.items.push(RowItem {
+                        verse: v,
+                        words: w..w + 1,
+                    }),
+                }
+            }
+        }
+
+        if !row.items.is_empty() {
+            rows.push(row);
+        }
+        rows
+    }
+
+    fn heading_rows(&self) -> usize {
+        match self.show_heading {
+            true => 1,
+            false => 0,
         }
     }
 
     // Does not include the potential gap before chapter.
     pub fn consumed_chars(&self, width: usize) -> usize {
-        let header = if self.show_heading { width } else { 0 };
-        let verse_cost: usize = self.verses.iter().map(|v| v.consumed_chars(width, 0)).sum();
-
-        let padding = width - (verse_cost % width);
-        header + verse_cost + padding
+        (self.heading_rows() + self.rows(width).len()) * width
     }
 
-    /// TODO: Doc.
+    /// Splits the chapter so that the first part fits into `budget` characters.  Splitting
+    /// happens on row boundaries, so a verse can be split over the two parts.
+    ///
+    /// Returns (fit, remainder), where `fit` is `None` if not even the heading and a single row
+    /// of verses fit into the budget.
     pub fn split(
         self,
         width: usize,
         budget: usize,
     ) -> (Option<ColumnChapter>, Option<ColumnChapter>) {
         // It does not makes sense for us to get a budget that is not `N * rows`.
-        assert!(budget % width == 0);
+        assert!(budget.is_multiple_of(width));
         // Budget of 0 also does not makes sense.
         assert!(budget > 0);
 
         // If chapter fits into budget no split will occur.
-        if self.consumed_chars(width) <= budget {
+        let rows = self.rows(width);
+        let available_rows = (budget / width).saturating_sub(self.heading_rows());
+        if rows.len() <= available_rows {
             return (Some(self), None);
         }
-        // Need to be able to fit at least the header and a single verse.
-        if budget < (width * 2) {
+        // Need to be able to fit at least the header and a single row.
+        if available_rows == 0 {
             return (None, Some(self));
         }
 
-        let verses_clone = self.verses.clone(); // TODO: Fix.
-        let mut num_fitting_verses = 0;
-        let mut current_row_offset = 0;
-        let mut first_split: Option<ColumnVerseSegment> = None;
-        let mut second_split: Option<ColumnVerseSegment> = None;
-
-        let mut remaining_budget = match self.show_heading {
-            false => budget,
-            true => budget - width,
-        };
-
-        for verse in self.verses {
-            let verse_chars = verse.consumed_chars(width, current_row_offset);
-
-            // Verse fits, no issues.
-            if verse_chars <= remaining_budget {
-                num_fitting_verses += 1;
-                current_row_offset = (current_row_offset + verse_chars) % width;
-                remaining_budget -= verse_chars;
-                continue;
-            }
-
-            debug!("verse chars: {verse_chars}");
-            debug!("remaining budget: {remaining_budget}");
-
-            // Verse did not fit, have to split.
-            (first_split, second_split) = verse.split_at_wrap(width, current_row_offset);
-            break;
+        // The first row that does not fit is where the split happens.
+        let split_at = &rows[available_rows].items[0];
+        let mut second = self.verses;
+        let mut first: Vec<ColumnVerseSegment> = second.drain(..split_at.verse).collect();
+        if split_at.words.start > 0 {
+            let (head, tail) = second.remove(0).split_at_word(split_at.words.start);
+            first.push(head);
+            second.insert(0, tail);
         }
 
-        let (first, second) = verses_clone.split_at(num_fitting_verses);
-        let mut first = first.to_vec();
-        debug!("{first:?}");
-        let mut second = second.to_vec();
-
-        if let Some(split) = first_split {
-            first.push(split);
-        }
-        debug!("{first:?}");
-        if let Some(split) = second_split {
-            debug!("before: {:?}", second[0]);
-            second[0] = split;
-            debug!("after: {:?}", second[0]);
-        }
+        let first = ColumnChapter {
+            show_heading: self.show_heading,
+            number: self.number,
+            verses: first,
+        };
+        let second = ColumnChapter {
+            show_heading: false,
+            number: self.number,
+            verses: second,
+        };
+        (Some(first), Some(second))
+    }
 
-        // TODO: I don't like the clones here.  Should be &str.
-        let to_chapter =
-            |verses: Vec<ColumnVerseSegment>, show_heading: bool| -> Option<ColumnChapter> {
-                (!verses.is_empty()).then(|| ColumnChapter {
-                    show_heading,
-                    number: self.number,
-                    verses,
-                })
-            };
-        (to_chapter(first, true), to_chapter(second, false))
+    pub fn heading(number: usize) -> Line<'static> {
+        Line::styled(format!("Chapter {number}"), theme().chapter_heading)
     }
 
-    pub fn build(&self) -> Vec<Line> {
-        assert!(!self.verses.is_empty());
+    pub fn build(
+        &self,
+        width: usize,
+        highlights: &[VerseHighlight],
+        noted: &[(usize, RangeInclusive<usize>)],
+        red_letter: bool,
+    ) -> Vec<Line<'_>> {
+        // An empty chapter is skipped, there is nothing to show under the heading.
+        if self.verses.is_empty() {
+            return Vec::new();
+        }
 
-        let mut lines: Vec<Line> = Vec::new();
-        // TODO: Tweak reserve.
-        lines.reserve(25);
+        let rows = self.rows(width);
+        let mut lines: Vec<Line> = Vec::with_capacity(rows.len() + self.heading_rows());
 
         // Heading.
         if self.show_heading {
-            lines.push(Line::styled(
-                format!("Chapter {}", self.number),
-                Style::default().italic().blue(),
-            ));
+            lines.push(Self::heading(self.number));
         }
 
         // Verses.
-        let mut spans: Vec<Span> = Vec::new();
-        // TODO: Tweak reserve.
-        spans.reserve(25);
-
-        for verse in &self.verses {
-            let (number, text) = verse.build();
-            if let Some(n) = number {
-                spans.push(n);
+        for row in rows {
+            let mut spans: Vec<Span> = Vec::with_capacity(row.items.len() * 2 + 1);
+            if row.indent > 0 {
+                spans.push(Span::raw(" ".repeat(row.indent)));
             }
-            spans.push(text);
+            for (i, item) in row.items.iter().enumerate() {
+                let verse = &self.verses[item.verse];
+                let style = VerseHighlight::style_for(highlights, self.number, verse.number);
+                let words = VerseHighlight::word_styles_for(highlights, self.number, verse.number);
+                let has_note = noted.iter().any(|(chapter, verses)| {
+                    *chapter == self.number && verses.contains(&verse.number)
+                });
+                if i > 0 {
+                    spans.push(Span::styled(" ", style));
+                }
+                spans.extend(verse.build(item.words.clone(), style, &words, has_note, red_letter));
+            }
+            lines.push(Line::from(spans));
         }
-        assert!(!spans.is_empty());
-        lines.push(Line::from(spans));
 
         lines
     }
@@ -269,197 +350,457 @@ impl ColumnChapter {
 pub struct ColumnVerseSegment {
     pub show_number: bool,
     pub number: usize,
-    // TODO: This should be &str.
-    pub text: String,
+    /// Index of the first word in the whole verse, more than 0 when the verse was split.
+    pub first_word: usize,
+    pub words: Vec<Word>,
 }
 
-impl ColumnVerseSegment {
-    /// Creates a naive verse without any splitting.
-    pub fn new_naive(number: usize, text: &str) -> Self {
-        ColumnVerseSegment {
-            show_number: true,
-            number,
-            text: text.trim_end().trim_start().to_string(),
-        }
+/// A word of a verse, possibly made up of differently styled parts, e.g. "(<added>is</added>".
+#[derive(Debug, Clone, Default)]
+pub struct Word {
+    pub parts: Vec<(String, TextStyle)>,
+    /// Starts a new row, e.g. at the start of a poetry line.
+    pub break_before: bool,
+    /// Poetry level of the line the word is on, 0 for prose.
+    pub level: usize,
+    /// Lemma and morphology, in sources that tag the words.
+    pub tag: Option<WordTag>,
+}
+
+impl Word {
+    /// Length in characters.
+    pub fn len(&self) -> usize {
+        self.parts
+            .iter()
+            .map(|(text, _)| text.chars().count())
+            .sum()
     }
 
-    /// Calculates the verse rows crated by wrapping.  Each vec is a row.
-    ///
-    /// Returns:
-    /// - The consumed chars for the row (including numbers and whitespace)
-    /// - The raw index that can be used to index into the existing string.
-    ///
-    /// WARN: This function is nasty.
-    pub fn calculate_verse_rows_from_wrapping(
-        &self,
-        width: usize,
-        starting_offset: usize,
-    ) -> Vec<(usize, usize)> {
-        // TODO: Tweak reserving.
-        let mut rows = Vec::new();
-        rows.reserve(10);
-
-        let mut current_string_index = 0; // Used to index into memory.
-        let mut remaining_chars_in_row = width - starting_offset;
-        let mut current_row_raw_size = 0;
-
-        // This is 0 when the number won't be showed so no need for extra checking.
-        // TODO: Currently this assumes the verse number can be split from the first word.
-        // Decide what we want and revisit.
-        let number_size = self.get_number_char_size();
-        // Number does not fit, we need to leave it empty and wrap.
-        if number_size > remaining_chars_in_row {
-            rows.push((remaining_chars_in_row, 0));
-            remaining_chars_in_row = width - number_size;
-            current_row_raw_size = 0;
-        }
-        // Number does fit.
-        // Number not part of raw text, so don't increase `current_raw_text_size`.
-        else {
-            current_row_raw_size += number_size;
-            remaining_chars_in_row -= number_size;
+    fn push(&mut self, c: char, style: &TextStyle) {
+        match self.parts.last_mut() {
+            Some((text, s)) if s == style => text.push(c),
+            _ => self.parts.push((c.to_string(), style.clone())),
         }
+    }
+}
 
-        // Now check words.
-        let mut first_word_in_row = starting_offset == 0;
-        self.text.split_whitespace().for_each(|word| {
-            let word_len = word.len();
-
-            // The first word in the row does not need a leading whitespace,
-            let word_consumption = if first_word_in_row {
-                word_len
-            } else {
-                word_len + 1
-            };
-
-            // Check if word fits (no wrap).
-            if word_consumption <= remaining_chars_in_row {
-                current_row_raw_size += word_consumption;
-                remaining_chars_in_row -= word_consumption;
-                current_string_index += word_consumption;
-                first_word_in_row = false;
-            }
-            // Wrap.
-            else {
-                // Consume trailing whitespace for previous word.
-                current_row_raw_size += remaining_chars_in_row;
-                rows.push((current_row_raw_size, current_string_index));
-
-                // Reset, going to a new line.
-                remaining_chars_in_row = width - word_len;
-
-                // Usage on next line.
-                current_row_raw_size = word_len; // No leading whitespace.
-                current_string_index = word_consumption;
-            }
-        });
-
-        // Push last consumption.  Do not use trailing whitespace, the next verse can fit.
-        rows.push((current_row_raw_size, current_string_index));
+/// The meaning of a run of text, from the inline markup of the source.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextStyle {
+    /// Supplied by the translators.
+    pub added: bool,
+    /// Inside a quotation, with the speaker if the source has one.
+    pub quote: Option<Option<String>>,
+}
 
-        rows
+impl TextStyle {
+    /// Quotes without a speaker are never coloured, there is no telling who is speaking.
+    pub fn is_words_of_christ(&self) -> bool {
+        matches!(&self.quote, Some(Some(who)) if is_christ(who))
     }
 
-    /// This includes leading whitespace used by the verse, and not trailing whitespace.
-    pub fn consumed_chars(&self, width: usize, starting_offset: usize) -> usize {
-        self.calculate_verse_rows_from_wrapping(width, starting_offset)
-            .iter()
-            .map(|(a, _)| a)
-            .sum::<usize>()
+    pub fn style(&self, red_letter: bool) -> Style {
+        let mut style = Style::default();
+        if self.added {
+            style = style.patch(theme().added);
+        }
+        if red_letter && self.is_words_of_christ() {
+            style = style.patch(theme().words_of_christ);
+        } else if self.quote.is_some() {
+            style = style.patch(theme().quote);
+        }
+        style
     }
+}
 
-    /// Splits the verse where it wraps, returning the remainder.
-    ///
-    /// NOTE: The second segment is not guaranteed to fit a row, check manually.
-    /// NOTE: `character_budget` includes whitespace, i.e. it is the rendering budget.
-    fn split_at_wrap(
-        self,
-        width: usize,
-        starting_offset: usize,
-    ) -> (Option<ColumnVerseSegment>, Option<ColumnVerseSegment>) {
-        let mut character_budget = width - starting_offset;
-        let mut accumulated_index = 0;
-
-        let regions = self.calculate_verse_rows_from_wrapping(width, starting_offset);
+/// Whether the `who` of a quote refers to Christ.
+pub fn is_christ(who: &str) -> bool {
+    ["Jesus", "Christ", "Jesus Christ"]
+        .iter()
+        .any(|name| who.eq_ignore_ascii_case(name))
+}
 
-        // No verses in first segment.
-        if regions.first().expect("There should be a region here").1 == 0 {
-            return (None, Some(self));
-        }
+impl ColumnVerseSegment {
+    /// Creates a verse without any splitting, keeping the inline markup.
+    pub fn from_verse(bible: &Bible, verse: &VerseView) -> Self {
+        let raw = bible.get_raw_data();
+        let mut words: Vec<Word> = Vec::new();
+        let mut word: Option<Word> = None;
+        let mut quotes: Vec<Option<String>> = Vec::new();
+        let mut tags: Vec<WordTag> = Vec::new();
+        let mut level = 0;
+        let mut break_before = false;
+
+        for inline in &verse.inlines {
+            let (range, added) = match inline {
+                Inline::Text(s, e) => (*s..*e, false),
+                Inline::Added(s, e) => (*s..*e, true),
+                Inline::QuoteStart { who } => {
+                    quotes.push(who.clone());
+                    continue;
+                }
+                Inline::QuoteEnd => {
+                    quotes.pop();
+                    continue;
+                }
+                Inline::LineStart { level: l } => {
+                    words.extend(word.take());
+                    level = *l;
+                    break_before = true;
+                    continue;
+                }
+                Inline::LineEnd => {
+                    words.extend(word.take());
+                    level = 0;
+                    continue;
+                }
+                Inline::LineBreak => {
+                    words.extend(word.take());
+                    break_before = true;
+                    continue;
+                }
+                Inline::WordStart(tag) => {
+                    tags.push(tag.clone());
+                    continue;
+                }
+                Inline::WordEnd => {
+                    tags.pop();
+                    continue;
+                }
+            };
 
-        // Find point where we have to split.
-        for (consumed, raw_index) in &regions {
-            if *consumed <= character_budget {
-                character_budget -= consumed;
-                accumulated_index += raw_index;
-            } else {
-                break;
+            let style = TextStyle {
+                added,
+                quote: quotes.last().cloned(),
+            };
+            for c in text_at(raw, range.start, range.end).chars() {
+                if c.is_whitespace() {
+                    words.extend(word.take());
+                    continue;
+                }
+                let word = word.get_or_insert_with(|| Word {
+                    parts: Vec::new(),
+                    break_before: std::mem::take(&mut break_before),
+                    level,
+                    tag: None,
+                });
+                // Punctuation before the tagged part, e.g. "(God", is part of the word too.
+                if word.tag.is_none() {
+                    word.tag = tags.last().cloned();
+                }
+                word.push(c, &style);
             }
         }
+        words.extend(word);
 
-        // Trimming here just to clean up.
-        let first = self.text[..accumulated_index].trim_end().to_string();
-        let second = self.text[accumulated_index..].trim_start().to_string();
-
-        let remainder = match second.is_empty() {
-            true => None,
-            false => Some(ColumnVerseSegment {
-                show_number: false,
-                number: self.number,
-                text: second,
-            }),
-        };
+        ColumnVerseSegment {
+            show_number: true,
+            number: verse.number,
+            first_word: 0,
+            words,
+        }
+    }
 
+    /// Splits the verse before word `index`.  The second segment continues the verse, so it does
+    /// not show the number.
+    fn split_at_word(mut self, index: usize) -> (ColumnVerseSegment, ColumnVerseSegment) {
+        let second = self.words.split_off(index);
         (
-            Some(ColumnVerseSegment {
+            ColumnVerseSegment {
                 show_number: self.show_number,
                 number: self.number,
-                text: first,
-            }),
-            remainder,
+                first_word: self.first_word,
+                words: self.words,
+            },
+            ColumnVerseSegment {
+                show_number: false,
+                number: self.number,
+                first_word: self.first_word + index,
+                words: second,
+            },
         )
     }
 
-    /// Returns:
-    /// (verse number, verse text)
-    ///
-    /// TODO: TOO MANY CLONES!
-    pub fn build(&self) -> (Option<Span<'_>>, Span<'_>) {
-        assert!(!self.text.is_empty());
-
-        let number = if self.show_number {
-            // TODO: Cleanup
-            if self.number == 1 {
-                Some(Span::styled(
-                    format!("{} ", self.number),
-                    Style::default().dark_gray(),
-                ))
-            } else {
-                Some(Span::styled(
-                    format!(" {} ", self.number),
-                    Style::default().dark_gray(),
-                ))
-            }
-        } else {
-            None
-        };
+    /// Builds the `words` of the verse that are on a single row, patching `style` over the
+    /// styles of the text.  The (range, style) of `word_styles` are patched over the words in
+    /// the range, and the whitespace between them, the indices are in the whole verse.  Verses
+    /// with a note are marked in place of the whitespace after the number, so that the layout
+    /// does not change.
+    pub fn build(
+        &self,
+        words: Range<usize>,
+        style: Style,
+        word_styles: &[(Range<usize>, Style)],
+        has_note: bool,
+        red_letter: bool,
+    ) -> Vec<Span<'_>> {
+        let mut spans = Vec::new();
+        if self.show_number && words.start == 0 {
+            spans.push(Span::styled(
+                self.number.to_string(),
+                theme().verse_number.patch(style),
+            ));
+            spans.push(match has_note {
+                true => Span::styled(icon(Icon::Note), theme().note_marker.patch(style)),
+                false => Span::styled(" ", style),
+            });
+        }
 
-        let text = Span::raw(self.text.to_string());
-        (number, text)
+        // The style of the words from `start` to `end`, which is the style of the whitespace
+        // between them when they are different words.
+        let style_of = |start: usize, end: usize| {
+            word_styles
+                .iter()
+                .filter(|(range, _)| range.contains(&start) && range.contains(&end))
+                .fold(style, |style, (_, word_style)| style.patch(*word_style))
+        };
+        let first = self.first_word + words.start;
+        for (i, w) in self.words[words].iter().enumerate() {
+            let index = first + i;
+            if i > 0 {
+                spans.push(Span::styled(" ", style_of(index - 1, index)));
+            }
+            let style = style_of(index, index);
+            for (text, text_style) in &w.parts {
+                spans.push(Span::styled(
+                    text,
+                    text_style.style(red_letter).patch(style),
+                ));
+            }
+        }
+        spans
     }
 
-    // Includes leading whitespace.  Excludes trailing whitespace.
+    // Includes the whitespace between the number and the text.
     fn get_number_char_size(&self) -> usize {
         match self.show_number {
             false => 0,
-            true if self.number == 1 => 1,
             true => {
-                // Includes leading whitespace.
                 let digits = self.number.checked_ilog10().unwrap_or(0) as usize + 1;
-                digits
+                digits + 1
             }
         }
     }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A range of verses in a chapter that is styled differently from the rest of the text.
+#[derive(Debug, Clone)]
+pub struct VerseHighlight {
+    pub chapter: usize,
+    pub verses: RangeInclusive<usize>,
+    pub style: Style,
+    /// Only the words in this range of the verse, e.g. the word cursor.
+    pub words: Option<Range<usize>>,
+}
+
+impl VerseHighlight {
+    /// The combined style of all of the highlights that apply to the whole verse.
+    pub fn style_for(highlights: &[VerseHighlight], chapter: usize, verse: usize) -> Style {
+        highlights
+            .iter()
+            .filter(|h| h.words.is_none() && h.chapter == chapter && h.verses.contains(&verse))
+            .fold(Style::default(), |style, h| style.patch(h.style))
+    }
+
+    /// The (range, style) of the highlighted words of the verse, in the order of the highlights.
+    pub fn word_styles_for(
+        highlights: &[VerseHighlight],
+        chapter: usize,
+        verse: usize,
+    ) -> Vec<(Range<usize>, Style)> {
+        highlights
+            .iter()
+            .filter(|h| h.chapter == chapter && h.verses.contains(&verse))
+            .filter_map(|h| Some((h.words.clone()?, h.style)))
+            .collect()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bible::tests::bible_from;
+
+    /// John with two chapters of verses that are long enough to be split over columns.
+    fn bible() -> Bible {
+        let chapter = |c: usize| {
+            (1..=12)
+                .map(|v| {
+                    let words: Vec<String> = (1..=3 + v).map(|w| format!("w{c}.{v}.{w}")).collect();
+                    format!(
+                        r#"<verse sID="John.{c}.{v}"/>{}<verse eID="John.{c}.{v}"/>"#,
+                        words.join(" ")
+                    )
+                })
+                .collect::<String>()
+        };
+        bible_from(
+            "columns.xml",
+            &format!(
+                r#"<osis><osisText><div type="book"><title short="John">John</title>
+<chapter sID="John.1"/>{}<chapter eID="John.1"/><chapter sID="John.2"/>{}<chapter eID="John.2"/>
+</div></osisText></osis>"#,
+                chapter(1),
+                chapter(2)
+            ),
+        )
+    }
+
+    /// Lays out the whole book in columns of `width * height`.
+    fn columns(bible: &Bible, width: usize, height: usize) -> Vec<Column> {
+        let book = bible.get_book_index("John").unwrap();
+        let mut chapters = book.get_chapters();
+        let mut columns = Vec::new();
+        let mut overflow = None;
+        loop {
+            let (column, rest) = Column::new(width, height, bible, &mut chapters, overflow);
+            if column.chapters.is_empty() && rest.is_none() {
+                break;
+            }
+            columns.push(column);
+            overflow = rest;
+        }
+        columns
+    }
+
+    fn text(word: &Word) -> String {
+        word.parts.iter().map(|(text, _)| text.as_str()).collect()
+    }
+
+    #[test]
+    fn carries_split_verses_into_the_next_column() {
+        let bible = bible();
+        for (width, height) in [(20, 4), (24, 7), (30, 5), (40, 3)] {
+            let columns = columns(&bible, width, height);
+            assert!(columns.len() > 2);
+
+            // The words of every verse, in the order they are shown.
+            let mut shown: Vec<(usize, usize, Vec<String>)> = Vec::new();
+            let mut splits = 0;
+            for column in &columns {
+                for chapter in &column.chapters {
+                    for segment in &chapter.verses {
+                        let words = segment.words.iter().map(text);
+                        match shown.last_mut() {
+                            Some((c, v, shown)) if (*c, *v) == (chapter.number, segment.number) => {
+                                // The rest of a verse that was split, without its number.
+                                assert!(!segment.show_number);
+                                assert_eq!(segment.first_word, shown.len());
+                                shown.extend(words);
+                                splits += 1;
+                            }
+                            _ => {
+                                assert!(segment.show_number);
+                                assert_eq!(segment.first_word, 0);
+                                shown.push((chapter.number, segment.number, words.collect()));
+                            }
+                        }
+                    }
+                }
+            }
+            assert!(splits > 0, "no verse was split in {width}x{height}");
+
+            let expected: Vec<(usize, usize, Vec<String>)> = (1..=2)
+                .flat_map(|c| (1..=12).map(move |v| (c, v)))
+                .map(|(c, v)| (c, v, (1..=3 + v).map(|w| format!("w{c}.{v}.{w}")).collect()))
+                .collect();
+            assert_eq!(shown, expected, "{width}x{height}");
+        }
+    }
+
+    #[test]
+    fn columns_fit_into_their_budget() {
+        let bible = bible();
+        for (width, height) in [(20, 4), (24, 7), (30, 5), (40, 3), (80, 40)] {
+            for column in columns(&bible, width, height) {
+                let consumed = column.chars_consumed();
+                assert!(consumed <= width * height, "{consumed} in {width}x{height}");
+                let lines: usize = column
+                    .chapters
+                    .iter()
+                    .map(|c| c.build(width, &[], &[], false).len())
+                    .sum::<usize>()
+                    + column.chapters.len().saturating_sub(1);
+                assert!(lines <= height, "{lines} lines in {width}x{height}");
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_the_inline_markup_of_words() {
+        let bible = bible_from(
+            "words.xml",
+            r#"<osis><osisText><div type="book" osisID="John"><chapter sID="John.1" osisID="John.1"/>
+<verse sID="John.1.1" osisID="John.1.1"/>It <q who="Jesus">(<transChange type="added">is</transChange>)</q><verse eID="John.1.1"/>
+<verse sID="John.1.2" osisID="John.1.2"/><l level="2">Line one</l><lb/>more<verse eID="John.1.2"/>
+<chapter eID="John.1"/></div></osisText></osis>"#,
+        );
+        let mut verses = bible.get_chapter("John", 1).unwrap().get_verses();
+
+        let verse = ColumnVerseSegment::from_verse(&bible, verses.next().unwrap());
+        let jesus = Some(Some("Jesus".to_string()));
+        let parts = |added, quote: &Option<Option<String>>| TextStyle {
+            added,
+            quote: quote.clone(),
+        };
+        assert_eq!(
+            verse.words[0].parts,
+            [("It".to_string(), parts(false, &None))]
+        );
+        assert_eq!(
+            verse.words[1].parts,
+            [
+                ("(".to_string(), parts(false, &jesus)),
+                ("is".to_string(), parts(true, &jesus)),
+                (")".to_string(), parts(false, &jesus)),
+            ]
+        );
+
+        let verse = ColumnVerseSegment::from_verse(&bible, verses.next().unwrap());
+        let words: Vec<(String, bool, usize)> = verse
+            .words
+            .iter()
+            .map(|w| (text(w), w.break_before, w.level))
+            .collect();
+        assert_eq!(
+            words,
+            [
+                ("Line".to_string(), true, 2),
+                ("one".to_string(), false, 2),
+                ("more".to_string(), true, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn colours_the_words_of_christ_in_red_letter() {
+        assert!(is_christ("jesus") && is_christ("Jesus Christ"));
+        assert!(!is_christ("Peter"));
+
+        let quote = |who: Option<&str>| TextStyle {
+            added: true,
+            quote: Some(who.map(str::to_string)),
+        };
+        assert_eq!(
+            quote(Some("Jesus")).style(true),
+            Style::default().italic().red()
+        );
+        assert_eq!(quote(Some("Jesus")).style(false), Style::default().italic());
+        // There is no telling who speaks in quotes without a speaker.
+        assert_eq!(quote(None).style(true), Style::default().italic());
+    }
+
+    #[test]
+    fn skips_an_empty_chapter() {
+        let chapter = ColumnChapter {
+            show_heading: true,
+            number: 2,
+            verses: Vec::new(),
+        };
+        assert!(chapter.build(20, &[], &[], false).is_empty());
+    }
+}
@@ -1,70 +1,508 @@
 use ratatui::layout::Direction;
-use ratatui::widgets::{Block, BorderType, Borders};
+use ratatui::widgets::{Block, Borders};
 
 use crate::app::events::{AppEvent, UserAction};
 use crate::bible::Bible;
+use crate::bookmarks::Bookmark;
 use crate::components::Component;
-use crate::components::book_column::Column;
+use crate::components::book_column::{Column, ColumnVerseSegment, VerseHighlight, Word};
+use crate::crossrefs::Passage;
+use crate::highlights::Highlights;
+use crate::notes::Notes;
 use crate::prelude::*;
+use crate::reference::{Reference, format_range};
+use std::ops::{Range, RangeInclusive};
+
+/// Verses, or words of a verse, to highlight in the numbering of the translation.
+pub struct HighlightTarget {
+    /// OSIS ID of the book.
+    pub book: String,
+    /// (chapter, verse) of the first verse.
+    pub start: (usize, usize),
+    /// (chapter, verse) of the last verse.
+    pub end: (usize, usize),
+    /// Only these words of the verse.
+    pub words: Option<Range<usize>>,
+}
 
 pub struct BookReader {
     bible: Arc<Bible>,
-    current_book_name: String,
+    /// OSIS ID of the book.
+    current_book: String,
+    /// Index of the first visible column.
     scrolled_offset: usize,
     focused: bool,
     book_changed: bool,
     num_columns: usize,
     // For lazy loading.
-    column: Option<Column>,
+    columns: Vec<Column>,
+    /// The (width, height) the columns were built for.
+    column_size: (usize, usize),
+    /// The passage that was jumped to.
+    highlight: Option<VerseHighlight>,
+    /// (chapter, verse) that should be scrolled into view once the columns are built.
+    pending_jump: Option<(usize, usize)>,
+    /// Colour the words of Christ.
+    pub red_letter: bool,
+    /// (chapter, verse) of the verse cursor, placed on the first move.
+    cursor: Option<(usize, usize)>,
+    /// Index of the word cursor in the verse of the cursor, placed on the first move by words.
+    word: Option<usize>,
+    /// (chapter, verse) where the visual selection started.
+    visual_anchor: Option<(usize, usize)>,
+    /// Index of the word where the visual selection started, if it started at the word cursor.
+    word_anchor: Option<usize>,
+    /// (text, reference) of the last yank.
+    yanked: Option<(String, String)>,
+    bookmark: Option<Bookmark>,
+    /// The first and last (chapter, verse) to write a note about.
+    note: Option<((usize, usize), (usize, usize))>,
+    /// (chapter, verses) of the book that have notes.
+    noted: Vec<(usize, RangeInclusive<usize>)>,
+    /// The verses to highlight in a category.
+    highlight_target: Option<HighlightTarget>,
+    /// The highlights of the user in the book, in the styles of their categories.
+    highlighted: Vec<VerseHighlight>,
+    /// Offset to scroll to on the next render, if it shows the verse that is jumped to.
+    restored_offset: Option<usize>,
 }
 
 impl BookReader {
-    pub fn new(bible: Arc<Bible>, current_book_name: String) -> Self {
+    pub fn new(bible: Arc<Bible>, current_book: String) -> Self {
         BookReader {
             bible,
-            current_book_name,
+            current_book,
             scrolled_offset: 0,
             focused: false,
             book_changed: true,
-            column: None,
-            // TODO: Change based on terminal size.
-            num_columns: 2,
+            columns: Vec::new(),
+            column_size: (0, 0),
+            num_columns: 1,
+            highlight: None,
+            pending_jump: None,
+            red_letter: false,
+            cursor: None,
+            word: None,
+            visual_anchor: None,
+            word_anchor: None,
+            yanked: None,
+            bookmark: None,
+            note: None,
+            noted: Vec::new(),
+            highlight_target: None,
+            highlighted: Vec::new(),
+            restored_offset: None,
         }
     }
 
     pub fn set_book(&mut self, book: &str) {
-        if self.current_book_name != book {
-            self.current_book_name = book.to_string();
+        if self.current_book != book {
+            self.current_book = book.to_string();
             self.scrolled_offset = 0;
             self.book_changed = true;
+            self.highlight = None;
+            self.pending_jump = None;
+            self.cursor = None;
+            self.word = None;
+            self.visual_anchor = None;
+        }
+    }
+
+    /// Scrolls the verse into view and puts the cursor on it.
+    pub fn scroll_to(&mut self, book: &str, chapter: usize, verse: usize) {
+        self.set_book(book);
+        self.pending_jump = Some((chapter, verse));
+        self.cursor = Some((chapter, verse));
+        self.word = None;
+    }
+
+    /// Scrolls the reference into view and highlights the verses.
+    pub fn jump_to(&mut self, reference: Reference) {
+        self.scroll_to(&reference.book, reference.chapter, reference.first_verse());
+        self.highlight = reference.verses.map(|verses| VerseHighlight {
+            chapter: reference.chapter,
+            verses,
+            style: theme().highlight,
+            words: None,
+        });
+    }
+
+    /// The (book, chapter, verse) that is being read, the cursor or the start of the first
+    /// visible column.
+    pub fn position(&self) -> (&str, usize, usize) {
+        let (chapter, verse) = self
+            .pending_jump
+            .or(self.cursor)
+            .or_else(|| self.first_visible_verse())
+            .unwrap_or((1, 1));
+        (&self.current_book, chapter, verse)
+    }
+
+    /// Scrolls to the column of a previous session, which only lines up when the terminal has the
+    /// same size.  Used after `scroll_to`.
+    pub fn restore_offset(&mut self, offset: usize) {
+        self.restored_offset = Some(offset);
+    }
+
+    pub fn scrolled_offset(&self) -> usize {
+        self.scrolled_offset
+    }
+
+    /// The (text, reference) of the verses that were yanked, if any.
+    pub fn take_yanked(&mut self) -> Option<(String, String)> {
+        self.yanked.take()
+    }
+
+    /// The bookmark of the verses that were selected, if any.
+    pub fn take_bookmark(&mut self) -> Option<Bookmark> {
+        self.bookmark.take()
+    }
+
+    /// The (book, start, end) of the verses to write a note about, if any.
+    pub fn take_note(&mut self) -> Option<Passage<'_>> {
+        let (start, end) = self.note.take()?;
+        Some((&self.current_book, start, end))
+    }
+
+    /// Marks the verses of the book that have notes, in the numbering of the translation.
+    pub fn set_notes(&mut self, notes: &Notes) {
+        let versification = self.bible.get_versification();
+        self.noted = notes
+            .in_book(&self.current_book)
+            .filter_map(|note| note.range())
+            .flat_map(|(book, start, end)| {
+                let start = versification.map_from_kjv(book, start.0, start.1);
+                let end = versification.map_from_kjv(book, end.0, end.1);
+                chapter_ranges(start, end)
+            })
+            .collect();
+    }
+
+    /// The verses or words that were selected to highlight, if any.
+    pub fn take_highlight(&mut self) -> Option<HighlightTarget> {
+        self.highlight_target.take()
+    }
+
+    /// Shows the highlights of the book in the styles of their categories.  Highlights of words
+    /// are only shown in the translation they were made in, the words of others differ.
+    pub fn set_highlights(&mut self, highlights: &Highlights) {
+        let versification = self.bible.get_versification();
+        let translation = &self.bible.get_translation().name;
+        self.highlighted = highlights
+            .in_book(&self.current_book)
+            .filter(|h| {
+                h.words
+                    .as_ref()
+                    .is_none_or(|w| w.translation == *translation)
+            })
+            .filter_map(|h| {
+                let style = settings().highlight_style(&h.category)?;
+                let (book, start, end) = h.range()?;
+                let start = versification.map_from_kjv(book, start.0, start.1);
+                let end = versification.map_from_kjv(book, end.0, end.1);
+                let words = h.words.as_ref().map(|w| w.range());
+                Some(
+                    chapter_ranges(start, end).map(move |(chapter, verses)| VerseHighlight {
+                        chapter,
+                        verses,
+                        style,
+                        words: words.clone(),
+                    }),
+                )
+            })
+            .flatten()
+            .collect();
+    }
+
+    /// The word under the word cursor.
+    pub fn cursor_word(&self) -> Option<Word> {
+        let words = self.verse_words(self.cursor?);
+        words.into_iter().nth(self.word?)
+    }
+
+    /// The words of a verse, as they are laid out.
+    fn verse_words(&self, (chapter, verse): (usize, usize)) -> Vec<Word> {
+        self.bible
+            .get_chapter(&self.current_book, chapter)
+            .ok()
+            .and_then(|c| c.get_verses().find(|v| v.number == verse))
+            .map(|v| ColumnVerseSegment::from_verse(&self.bible, v).words)
+            .unwrap_or_default()
+    }
+
+    fn first_visible_verse(&self) -> Option<(usize, usize)> {
+        self.columns.get(self.scrolled_offset)?.first_verse()
+    }
+
+    fn is_visible(&self, (chapter, verse): (usize, usize)) -> bool {
+        self.columns
+            .iter()
+            .skip(self.scrolled_offset)
+            .take(self.num_columns)
+            .any(|c| c.contains(chapter, verse))
+    }
+
+    /// (chapter, verse) of all of the verses in the book, in order.
+    fn book_verses(&self) -> Vec<(usize, usize)> {
+        self.bible
+            .get_book_index(&self.current_book)
+            .map(|book| {
+                book.get_chapters()
+                    .flat_map(|c| c.get_verses().map(|v| (c.number, v.number)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Moves the cursor by `delta` verses, the first move only places it on the first visible
+    /// verse.
+    fn move_cursor(&mut self, delta: isize) {
+        let Some(cursor) = self.cursor else {
+            self.cursor = self.first_visible_verse();
+            return;
+        };
+
+        let verses = self.book_verses();
+        let Some(i) = verses.iter().position(|v| *v == cursor) else {
+            return;
+        };
+        let i = i.saturating_add_signed(delta).min(verses.len() - 1);
+        self.cursor = Some(verses[i]);
+
+        // Keep the cursor in view.
+        if let Some(column) = self
+            .columns
+            .iter()
+            .position(|c| c.contains(verses[i].0, verses[i].1))
+        {
+            if column < self.scrolled_offset {
+                self.scrolled_offset = column;
+            } else if column >= self.scrolled_offset + self.num_columns {
+                self.scrolled_offset = column + 1 - self.num_columns;
+            }
+        }
+    }
+
+    /// Moves the word cursor by `delta` words, continuing in the next or previous verse at the
+    /// ends of the verse.  The first move only places it on the first word of the verse cursor.
+    fn move_word(&mut self, delta: isize) {
+        let (Some(cursor), Some(word)) = (self.cursor, self.word) else {
+            self.cursor = self.cursor.or_else(|| self.first_visible_verse());
+            self.word = Some(0);
+            return;
+        };
+
+        let count = self.verse_words(cursor).len();
+        match word.checked_add_signed(delta) {
+            Some(word) if word < count => self.word = Some(word),
+            _ => {
+                self.move_cursor(delta.signum());
+                if self.cursor == Some(cursor) {
+                    // There is no verse to continue in.
+                    return;
+                }
+                self.word = match delta < 0 {
+                    true => self.cursor.map(|c| self.verse_words(c).len().saturating_sub(1)),
+                    false => Some(0),
+                };
+            }
+        }
+    }
+
+    /// The first and last (chapter, verse) of the selection, only the cursor outside of visual
+    /// mode.
+    fn selection(&self) -> Option<((usize, usize), (usize, usize))> {
+        let cursor = self.cursor?;
+        let anchor = self.visual_anchor.unwrap_or(cursor);
+        Some((cursor.min(anchor), cursor.max(anchor)))
+    }
+
+    /// The words of the selection, when it is within the verse of the cursor: the word cursor,
+    /// or the words from where the visual selection started at the word cursor.
+    fn word_selection(&self) -> Option<Range<usize>> {
+        let word = self.word?;
+        match (self.visual_anchor, self.word_anchor) {
+            (None, _) => Some(word..word + 1),
+            (Some(anchor), Some(start)) if Some(anchor) == self.cursor => {
+                Some(start.min(word)..start.max(word) + 1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Highlights of the selection, split up per chapter.
+    fn selection_highlights(&self) -> Vec<VerseHighlight> {
+        let (Some(cursor), Some((start, end))) = (self.cursor, self.selection()) else {
+            return Vec::new();
+        };
+
+        let mut highlights: Vec<VerseHighlight> = match (self.visual_anchor, self.word_selection())
+        {
+            (None, _) => Vec::new(),
+            (Some(_), Some(words)) => vec![VerseHighlight {
+                chapter: cursor.0,
+                verses: cursor.1..=cursor.1,
+                style: theme().selection,
+                words: Some(words),
+            }],
+            (Some(_), None) => chapter_ranges(start, end)
+                .map(|(chapter, verses)| VerseHighlight {
+                    chapter,
+                    verses,
+                    style: theme().selection,
+                    words: None,
+                })
+                .collect(),
+        };
+        // Selected words are not covered by the verse cursor, the word cursor shows where it is.
+        if self.visual_anchor.is_none() || self.word_selection().is_none() {
+            highlights.push(VerseHighlight {
+                chapter: cursor.0,
+                verses: cursor.1..=cursor.1,
+                style: theme().cursor,
+                words: None,
+            });
+        }
+        if let Some(word) = self.word {
+            highlights.push(VerseHighlight {
+                chapter: cursor.0,
+                verses: cursor.1..=cursor.1,
+                style: theme().word_cursor,
+                words: Some(word..word + 1),
+            });
+        }
+        highlights
+    }
+
+    /// Formats the selected verses, or the selected words of a verse, with their reference, and
+    /// leaves visual mode.
+    fn yank(&mut self) {
+        let Some((start, end)) = self.selection() else {
+            return;
+        };
+        let raw = self.bible.get_raw_data();
+        let Ok(book) = self.bible.get_book_index(&self.current_book) else {
+            return;
+        };
+
+        let mut text: Vec<String> = Vec::new();
+        if let Some(words) = self.word_selection() {
+            // The words are cited with the reference of their verse.
+            text = self
+                .verse_words(start)
+                .iter()
+                .skip(words.start)
+                .take(words.len())
+                .map(|word| word.parts.iter().map(|(text, _)| text.as_str()).collect())
+                .collect();
+        } else {
+            for chapter in book.get_chapters() {
+                for verse in chapter.get_verses() {
+                    let location = (chapter.number, verse.number);
+                    if location < start || location > end {
+                        continue;
+                    }
+                    let verse_text = verse.collect_string(raw);
+                    text.push(match (start == end, chapter.number == start.0) {
+                        (true, _) => verse_text,
+                        (false, true) => format!("{} {verse_text}", verse.number),
+                        (false, false) => {
+                            format!("{}:{} {verse_text}", chapter.number, verse.number)
+                        }
+                    });
+                }
+            }
         }
+
+        let reference = format!(
+            "{} ({})",
+            format_range(&self.current_book, start, end),
+            self.bible.get_translation().name
+        );
+        info!("Yanked {reference}");
+        self.yanked = Some((format!("{}\n{reference}", text.join(" ")), reference));
+        self.visual_anchor = None;
     }
 
-    /// TODO: Simulate scrollling.
-    fn layout(area: Rect, _scrolled_offset: usize, _num_columns: usize) -> Rect {
-        Layout::default()
+    /// Fit as many columns as possible into the width, within limits.
+    fn columns_for_width(width: usize) -> usize {
+        let per_column = READER_MIN_COLUMN_WIDTH + READER_COLUMN_GAP;
+        ((width + READER_COLUMN_GAP) / per_column).clamp(1, READER_MAX_COLUMNS)
+    }
+
+    /// Returns the areas of the visible columns.
+    fn layout(area: Rect, num_columns: usize) -> Vec<Rect> {
+        let padded = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
                 Constraint::Length(2),
                 Constraint::Min(0),
                 Constraint::Length(2),
             ])
-            .split(area)[1]
-    }
-
-    /// TODO: This function will have to be very smart.  Handle the different columns etc...
-    /// For now, just build one so that we can debug.
-    fn build_columns(&self, width: usize, height: usize) -> Result<Column> {
-        let (first, _second) = Column::new(
-            width,
-            height,
-            self.bible.as_ref(),
-            &self.bible.get_book(&self.current_book_name).chapters[0],
-            None,
+            .split(area)[1];
+
+        // Columns are built for a fixed width, so they all need to be the same size.
+        let gaps = (num_columns - 1) * READER_COLUMN_GAP;
+        let column_width = (padded.width as usize).saturating_sub(gaps) / num_columns;
+        Layout::horizontal(vec![Constraint::Length(column_width as u16); num_columns])
+            .spacing(READER_COLUMN_GAP as u16)
+            .split(padded)
+            .to_vec()
+    }
+
+    /// Lays out the entire book across columns, carrying the overflow of each column into the
+    /// next.
+    fn build_columns(&self, width: usize, height: usize) -> Result<Vec<Column>> {
+        let mut columns = Vec::new();
+        if width == 0 || height == 0 {
+            return Ok(columns);
+        }
+
+        let book = self.bible.get_book_index(&self.current_book)?;
+        let mut chapters = book.get_chapters();
+        let mut overflow = None;
+        loop {
+            let (column, remainder) =
+                Column::new(width, height, self.bible.as_ref(), &mut chapters, overflow);
+            debug_assert!(column.chars_consumed() <= width * height);
+
+            // Nothing fit, the area is too small to make progress.
+            if column.chapters.is_empty() {
+                if remainder.is_some() {
+                    warn!("Reader area too small to fit a chapter ({width}x{height})");
+                }
+                break;
+            }
+
+            columns.push(column);
+            overflow = remainder;
+        }
+
+        debug!(
+            "Built {} columns for {} ({width}x{height})",
+            columns.len(),
+            self.current_book
         );
+        Ok(columns)
+    }
+
+    fn max_scrolled_offset(&self) -> usize {
+        self.columns.len().saturating_sub(self.num_columns)
+    }
 
-        // debug!("{:?}", second);
-        Ok(first)
+    fn scroll_by(&mut self, delta: isize) {
+        self.scrolled_offset = self
+            .scrolled_offset
+            .saturating_add_signed(delta)
+            .min(self.max_scrolled_offset());
+
+        // The cursor follows when it is scrolled out of view.
+        if let Some(cursor) = self.cursor
+            && !self.is_visible(cursor)
+        {
+            self.cursor = self.first_visible_verse();
+            self.word = None;
+        }
     }
 }
 
@@ -74,11 +512,56 @@ impl Component for BookReader {
             AppEvent::Focus => self.focused = true,
             AppEvent::Defocus => self.focused = false,
             AppEvent::UserAction(action) if self.focused => match action {
-                UserAction::MoveDown => {
-                    self.scrolled_offset = self.scrolled_offset.saturating_add(1);
+                UserAction::MoveDown => self.scroll_by(1),
+                UserAction::MoveUp => self.scroll_by(-1),
+                UserAction::PageDown => self.scroll_by(self.num_columns as isize),
+                UserAction::PageUp => self.scroll_by(-(self.num_columns as isize)),
+                UserAction::NextVerse => {
+                    self.move_cursor(1);
+                    self.word = None;
+                }
+                UserAction::PrevVerse => {
+                    self.move_cursor(-1);
+                    self.word = None;
+                }
+                UserAction::NextWord => self.move_word(1),
+                UserAction::PrevWord => self.move_word(-1),
+                UserAction::ToggleVisual => match self.visual_anchor {
+                    Some(_) => self.visual_anchor = None,
+                    None => {
+                        self.cursor = self.cursor.or_else(|| self.first_visible_verse());
+                        self.visual_anchor = self.cursor;
+                        self.word_anchor = self.word;
+                    }
+                },
+                UserAction::Yank => self.yank(),
+                UserAction::AddBookmark => {
+                    self.cursor = self.cursor.or_else(|| self.first_visible_verse());
+                    if let Some((start, end)) = self.selection() {
+                        self.bookmark = Some(Bookmark::new(&self.current_book, start, end));
+                    }
+                    self.visual_anchor = None;
                 }
-                UserAction::MoveUp => {
-                    self.scrolled_offset = self.scrolled_offset.saturating_sub(1);
+                UserAction::Highlight => {
+                    self.cursor = self.cursor.or_else(|| self.first_visible_verse());
+                    if let Some((start, end)) = self.selection() {
+                        self.highlight_target = Some(HighlightTarget {
+                            book: self.current_book.clone(),
+                            start,
+                            end,
+                            words: self.word_selection(),
+                        });
+                    }
+                    self.visual_anchor = None;
+                }
+                UserAction::EditNote => {
+                    self.cursor = self.cursor.or_else(|| self.first_visible_verse());
+                    self.note = self.selection();
+                    self.visual_anchor = None;
+                }
+                UserAction::Close => {
+                    self.visual_anchor = None;
+                    self.word = None;
                 }
                 _ => {}
             },
@@ -88,29 +571,207 @@ impl Component for BookReader {
     }
 
     fn render(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
-        let title = format!(" [2] {} ", self.current_book_name);
+        let inner = Block::default().borders(Borders::ALL).inner(area);
+        self.num_columns = Self::columns_for_width(inner.width as usize);
+        let column_areas = Self::layout(inner, self.num_columns);
+
+        let size = (
+            column_areas[0].width as usize,
+            column_areas[0].height as usize,
+        );
+        if self.book_changed || self.column_size != size {
+            // Keep the same text in view when only the size changed.
+            let anchor = match self.book_changed {
+                true => None,
+                false => self
+                    .columns
+                    .get(self.scrolled_offset)
+                    .and_then(|c| c.first_verse()),
+            };
+
+            self.columns = self.build_columns(size.0, size.1)?;
+            self.column_size = size;
+            self.book_changed = false;
+
+            if let Some((chapter, verse)) = anchor {
+                self.scrolled_offset = self
+                    .columns
+                    .iter()
+                    .position(|c| c.contains(chapter, verse))
+                    .unwrap_or(0);
+            }
+        }
+        if let Some(offset) = self.restored_offset.take()
+            && let Some((chapter, verse)) = self.pending_jump
+            && self
+                .columns
+                .iter()
+                .skip(offset)
+                .take(self.num_columns)
+                .any(|c| c.contains(chapter, verse))
+        {
+            self.scrolled_offset = offset;
+            self.pending_jump = None;
+        }
+        if let Some((chapter, verse)) = self.pending_jump.take() {
+            self.scrolled_offset = self
+                .columns
+                .iter()
+                .position(|c| c.contains(chapter, verse))
+                .unwrap_or(0);
+        }
+        self.scrolled_offset = self.scrolled_offset.min(self.max_scrolled_offset());
+
+        let name = self
+            .bible
+            .get_book_index(&self.current_book)
+            .map_or(self.current_book.as_str(), |b| b.name.as_str());
+        let translation = &self.bible.get_translation().name;
+        let mut title = format!(" [2] {name} [{translation}] ");
+        if self.visual_anchor.is_some() {
+            title.push_str("-- VISUAL -- ");
+        }
+        if !self.columns.is_empty() {
+            let last_visible = (self.scrolled_offset + self.num_columns).min(self.columns.len());
+            title.push_str(&format!(
+                "({}-{}/{}) ",
+                self.scrolled_offset + 1,
+                last_visible,
+                self.columns.len()
+            ));
+        }
         let block = Block::default()
             .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
-            .title(title.yellow().bold())
-            .border_style(if self.focused {
-                Style::default().blue()
-            } else {
-                Style::default()
-            });
-
-        let padded = Self::layout(block.inner(area), self.scrolled_offset, self.num_columns);
+            .border_set(border_set())
+            .title(Span::styled(title, theme().title))
+            .border_style(theme().border(self.focused));
         block.render(area, buf);
 
-        if self.column.is_none() || self.book_changed {
-            self.column = Some(self.build_columns(padded.width as usize, padded.height as usize)?);
-            self.book_changed = false;
+        // The highlights of the user are below the passage that was jumped to and the selection.
+        let mut highlights = self.highlighted.clone();
+        highlights.extend(self.highlight.iter().cloned());
+        if self.focused {
+            highlights.extend(self.selection_highlights());
+        }
+        for (column, column_area) in self
+            .columns
+            .iter_mut()
+            .skip(self.scrolled_offset)
+            .zip(column_areas)
+        {
+            column.highlights = highlights.clone();
+            column.noted = self.noted.clone();
+            column.red_letter = self.red_letter;
+            column.render(column_area, buf)?;
         }
-        self.column
-            .as_mut()
-            .expect("Just created the column")
-            .render(padded, buf)?;
 
         Ok(())
     }
 }
+
+/// The verses from `start` to `end` as (chapter, verses) per chapter.
+fn chapter_ranges(
+    start: (usize, usize),
+    end: (usize, usize),
+) -> impl Iterator<Item = (usize, RangeInclusive<usize>)> {
+    (start.0..=end.0).map(move |chapter| {
+        let verses = match chapter {
+            c if c == start.0 && c == end.0 => start.1..=end.1,
+            c if c == start.0 => start.1..=usize::MAX,
+            c if c == end.0 => 1..=end.1,
+            _ => 1..=usize::MAX,
+        };
+        (chapter, verses)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bible::tests::bible_from;
+
+    fn reader() -> BookReader {
+        let bible = bible_from(
+            "yank.xml",
+            r#"<osis><osisText><div type="book" osisID="John">
+<chapter sID="John.1" osisID="John.1"/>
+<verse sID="John.1.1" osisID="John.1.1"/>In the beginning was the Word,<verse eID="John.1.1"/>
+<verse sID="John.1.2" osisID="John.1.2"/>The same was in the beginning with God.<verse eID="John.1.2"/>
+<chapter eID="John.1"/><chapter sID="John.2" osisID="John.2"/>
+<verse sID="John.2.1" osisID="John.2.1"/>And the third day there was a marriage.<verse eID="John.2.1"/>
+<chapter eID="John.2"/></div></osisText></osis>"#,
+        );
+        let mut reader = BookReader::new(Arc::new(bible), "John".to_string());
+        reader.update(&AppEvent::Focus).unwrap();
+        reader.cursor = Some((1, 1));
+        reader
+    }
+
+    fn press(reader: &mut BookReader, action: UserAction) {
+        reader.update(&AppEvent::UserAction(action)).unwrap();
+    }
+
+    #[test]
+    fn yanks_the_verse_under_the_cursor() {
+        let mut reader = reader();
+        press(&mut reader, UserAction::NextVerse);
+        press(&mut reader, UserAction::Yank);
+        let name = reader.bible.get_translation().name.clone();
+        let (text, reference) = reader.take_yanked().unwrap();
+        assert_eq!(reference, format!("John 1:2 ({name})"));
+        assert_eq!(
+            text,
+            format!("The same was in the beginning with God.\n{reference}")
+        );
+        assert!(reader.take_yanked().is_none());
+    }
+
+    #[test]
+    fn yanks_the_selected_verses() {
+        let mut reader = reader();
+        press(&mut reader, UserAction::ToggleVisual);
+        press(&mut reader, UserAction::NextVerse);
+        press(&mut reader, UserAction::Yank);
+        let name = reader.bible.get_translation().name.clone();
+        let (text, reference) = reader.take_yanked().unwrap();
+        assert_eq!(reference, format!("John 1:1-2 ({name})"));
+        assert_eq!(
+            text,
+            format!(
+                "1 In the beginning was the Word, 2 The same was in the beginning with God.\n\
+                 John 1:1-2 ({name})"
+            )
+        );
+        // Yanking leaves visual mode.
+        assert!(reader.visual_anchor.is_none());
+    }
+
+    #[test]
+    fn yanks_selections_across_chapters() {
+        let mut reader = reader();
+        press(&mut reader, UserAction::NextVerse);
+        press(&mut reader, UserAction::ToggleVisual);
+        press(&mut reader, UserAction::NextVerse);
+        press(&mut reader, UserAction::Yank);
+        let (text, reference) = reader.take_yanked().unwrap();
+        assert!(reference.starts_with("John 1:2-2:1 ("));
+        assert!(text.starts_with(
+            "2 The same was in the beginning with God. 2:1 And the third day there was a marriage."
+        ));
+    }
+
+    #[test]
+    fn yanks_the_selected_words() {
+        let mut reader = reader();
+        press(&mut reader, UserAction::NextWord);
+        press(&mut reader, UserAction::NextWord);
+        press(&mut reader, UserAction::ToggleVisual);
+        press(&mut reader, UserAction::NextWord);
+        press(&mut reader, UserAction::NextWord);
+        press(&mut reader, UserAction::Yank);
+        let name = reader.bible.get_translation().name.clone();
+        let (text, reference) = reader.take_yanked().unwrap();
+        assert_eq!(reference, format!("John 1:1 ({name})"));
+        assert_eq!(text, format!("the beginning was\n{reference}"));
+    }
+}
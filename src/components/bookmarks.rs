@@ -0,0 +1,170 @@
+use crate::app::events::{AppEvent, UserAction};
+use crate::bookmarks::{Bookmark, Bookmarks};
+use crate::components::Component;
+use crate::prelude::*;
+use crate::reference::Reference;
+use crossterm::event::KeyCode;
+use ratatui::widgets::{Block, Borders};
+
+/// Lists the bookmarks, which can be renamed, deleted and jumped to.
+pub struct BookmarksPanel {
+    bookmarks: Bookmarks,
+    selected: usize,
+    scrolled_offset: usize,
+    focused: bool,
+    /// The new name while renaming the selected bookmark.
+    renaming: Option<String>,
+    selected_reference: Option<Reference>,
+}
+
+impl BookmarksPanel {
+    pub fn new(bookmarks: Bookmarks) -> Self {
+        Self {
+            bookmarks,
+            selected: 0,
+            scrolled_offset: 0,
+            focused: false,
+            renaming: None,
+            selected_reference: None,
+        }
+    }
+
+    /// Adds the bookmark and selects it.
+    pub fn add(&mut self, bookmark: Bookmark) -> Result<()> {
+        info!(
+            "Adding bookmark {:?} at {}",
+            bookmark.name,
+            bookmark.location()
+        );
+        self.bookmarks.add(bookmark)?;
+        self.selected = self.bookmarks.len() - 1;
+        Ok(())
+    }
+
+    /// Whether a bookmark is being renamed, which needs raw key input.
+    pub fn is_editing(&self) -> bool {
+        self.renaming.is_some()
+    }
+
+    /// The reference of the bookmark that was selected with enter, if any.
+    pub fn take_selected(&mut self) -> Option<Reference> {
+        self.selected_reference.take()
+    }
+
+    fn edit_name(&mut self, key: KeyCode) -> Result<()> {
+        let Some(name) = &mut self.renaming else {
+            return Ok(());
+        };
+        match key {
+            KeyCode::Enter => {
+                let name = name.trim().to_string();
+                self.renaming = None;
+                if !name.is_empty() {
+                    self.bookmarks.rename(self.selected, name)?;
+                }
+            }
+            KeyCode::Esc => self.renaming = None,
+            KeyCode::Backspace => {
+                name.pop();
+            }
+            KeyCode::Char(c) => name.push(c),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn build_lines<'a>(
+        bookmark: &'a Bookmark,
+        selected: bool,
+        renaming: Option<&'a str>,
+    ) -> Vec<Line<'a>> {
+        let name = match renaming {
+            Some(name) => Line::from(vec![Span::raw(name), Span::styled(" ", theme().cursor)]),
+            None if selected => Line::styled(bookmark.name.as_str(), theme().selected),
+            None => Line::from(bookmark.name.as_str()),
+        };
+        vec![
+            name,
+            Line::styled(format!("  {}", bookmark.location()), theme().dim),
+        ]
+    }
+}
+
+impl Component for BookmarksPanel {
+    /// Errors are from saving the bookmarks, the change is kept in memory.
+    fn update(&mut self, event: &AppEvent) -> Result<()> {
+        match event {
+            AppEvent::Focus => self.focused = true,
+            AppEvent::Defocus => {
+                self.focused = false;
+                self.renaming = None;
+            }
+            AppEvent::KeyInput(key) => self.edit_name(key.code)?,
+            AppEvent::UserAction(action) if self.focused => match action {
+                UserAction::MoveDown if self.selected + 1 < self.bookmarks.len() => {
+                    self.selected += 1;
+                }
+                UserAction::MoveUp => self.selected = self.selected.saturating_sub(1),
+                UserAction::Select => {
+                    self.selected_reference =
+                        self.bookmarks.get(self.selected).map(|b| b.reference());
+                }
+                UserAction::Rename => {
+                    self.renaming = self.bookmarks.get(self.selected).map(|b| b.name.clone());
+                }
+                UserAction::Delete if self.selected < self.bookmarks.len() => {
+                    self.bookmarks.remove(self.selected)?;
+                    self.selected = self.selected.min(self.bookmarks.len().saturating_sub(1));
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        let title = format!(" [6] Bookmarks ({}) ", self.bookmarks.len());
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(border_set())
+            .title(Span::styled(title, theme().title))
+            .border_style(theme().border(self.focused));
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if self.bookmarks.is_empty() {
+            Line::from(Span::styled(
+                "No bookmarks, [m] in the reader adds one",
+                theme().dim,
+            ))
+            .render(inner, buf);
+            return Ok(());
+        }
+
+        // Scrolling logic, every bookmark takes two rows.
+        let visible = (inner.height as usize / 2).max(1);
+        if self.selected < self.scrolled_offset {
+            self.scrolled_offset = self.selected;
+        }
+        if self.selected >= self.scrolled_offset + visible {
+            self.scrolled_offset = self.selected + 1 - visible;
+        }
+
+        let lines: Vec<Line> = self
+            .bookmarks
+            .iter()
+            .enumerate()
+            .skip(self.scrolled_offset)
+            .take(visible)
+            .flat_map(|(i, bookmark)| {
+                let selected = i == self.selected && self.focused;
+                let renaming = self.renaming.as_deref().filter(|_| i == self.selected);
+                Self::build_lines(bookmark, selected, renaming)
+            })
+            .collect();
+        Paragraph::new(lines).render(inner, buf);
+        Ok(())
+    }
+}
@@ -1,10 +1,11 @@
 use crate::app::events::{AppEvent, UserAction};
+use crate::bible::Bible;
 use crate::components::Component;
 use crate::prelude::*;
-use ratatui::prelude::Stylize;
-use ratatui::widgets::{Block, BorderType, Borders};
+use ratatui::widgets::{Block, Borders};
 
 pub struct BooksView {
+    /// OSIS IDs of the books.
     books: Vec<String>,
     selected_book_index: usize,
     scrolled_offset: usize,
@@ -17,12 +18,14 @@ pub struct BooksView {
 }
 
 impl BooksView {
-    pub fn new(books: Vec<String>) -> Self {
-        let all_lines = BooksView::build_line_cache(&books, "   ");
-        // TODO: Only use nerd font icons if available.
-        let selected_lines = BooksView::build_line_cache(&books, "  ")
+    pub fn new(bible: &Bible) -> Self {
+        let books = bible.get_books().clone();
+        let names: Vec<&str> = bible.iter_books().map(|(_, b)| b.name.as_str()).collect();
+        let all_lines = BooksView::build_line_cache(&names, "   ");
+        let selected_prefix = format!(" {} ", icon(Icon::Selected));
+        let selected_lines = BooksView::build_line_cache(&names, &selected_prefix)
             .into_iter()
-            .map(|l| l.cyan().bold())
+            .map(|l| l.style(theme().selected))
             .collect();
         BooksView {
             books,
@@ -38,12 +41,14 @@ impl BooksView {
         &self.books[self.selected_book_index]
     }
 
-    pub fn focused(&self) -> bool {
-        self.focused
+    pub fn select_book(&mut self, book: &str) {
+        if let Some(i) = self.books.iter().position(|b| b == book) {
+            self.selected_book_index = i;
+        }
     }
 
-    fn build_line_cache(books: &Vec<String>, prefix: &str) -> Vec<Line<'static>> {
-        books
+    fn build_line_cache(names: &[&str], prefix: &str) -> Vec<Line<'static>> {
+        names
             .iter()
             .map(|b| Line::from(format!("{}{}", prefix, b)))
             .collect()
@@ -56,15 +61,11 @@ impl Component for BooksView {
             AppEvent::Focus => self.focused = true,
             AppEvent::Defocus => self.focused = false,
             AppEvent::UserAction(action) if self.focused => match action {
-                UserAction::MoveDown => {
-                    if self.selected_book_index < self.books.len() - 1 {
-                        self.selected_book_index += 1;
-                    }
+                UserAction::MoveDown if self.selected_book_index < self.books.len() - 1 => {
+                    self.selected_book_index += 1;
                 }
-                UserAction::MoveUp => {
-                    if self.selected_book_index > 0 {
-                        self.selected_book_index -= 1;
-                    }
+                UserAction::MoveUp if self.selected_book_index > 0 => {
+                    self.selected_book_index -= 1;
                 }
                 _ => {}
             },
@@ -76,13 +77,9 @@ impl Component for BooksView {
     fn render(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
         let block = Block::default()
             .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
-            .title(" [1] Books ".yellow().bold())
-            .border_style(if self.focused {
-                Style::default().blue()
-            } else {
-                Style::default()
-            });
+            .border_set(border_set())
+            .title(Span::styled(" [1] Books ", theme().title))
+            .border_style(theme().border(self.focused));
 
         let inner = block.inner(area);
         block.render(area, buf);
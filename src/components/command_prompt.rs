@@ -0,0 +1,120 @@
+use crate::app::events::AppEvent;
+use crate::components::Component;
+use crate::prelude::*;
+use crossterm::event::KeyCode;
+
+/// What the input of the prompt will be used for.
+#[derive(Clone, Copy, PartialEq)]
+pub enum PromptKind {
+    /// Jump to a reference.
+    Jump,
+    Search,
+    /// Look up a word or Strong's number in the concordance.
+    Concordance,
+}
+
+impl PromptKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            PromptKind::Jump => " :",
+            PromptKind::Search => " /",
+            PromptKind::Concordance => " *",
+        }
+    }
+}
+
+/// A single line prompt for typing commands, shown in place of the footer.
+pub struct CommandPrompt {
+    kind: PromptKind,
+    input: String,
+    active: bool,
+    submitted: Option<(PromptKind, String)>,
+    /// Shown until the next key press, (text, is error).
+    notice: Option<(String, bool)>,
+}
+
+impl CommandPrompt {
+    pub fn new() -> Self {
+        Self {
+            kind: PromptKind::Jump,
+            input: String::new(),
+            active: false,
+            submitted: None,
+            notice: None,
+        }
+    }
+
+    pub fn open(&mut self, kind: PromptKind) {
+        self.kind = kind;
+        self.active = true;
+        self.input.clear();
+        self.notice = None;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.active || self.notice.is_some()
+    }
+
+    /// The input that was submitted with enter, if any.
+    pub fn take_submitted(&mut self) -> Option<(PromptKind, String)> {
+        self.submitted.take()
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.notice = Some((error, true));
+    }
+
+    pub fn set_message(&mut self, message: String) {
+        self.notice = Some((message, false));
+    }
+}
+
+impl Component for CommandPrompt {
+    fn update(&mut self, event: &AppEvent) -> Result<()> {
+        match event {
+            AppEvent::KeyInput(key) if self.active => match key.code {
+                KeyCode::Enter => {
+                    self.active = false;
+                    self.submitted = Some((self.kind, std::mem::take(&mut self.input)));
+                }
+                KeyCode::Esc => {
+                    self.active = false;
+                    self.input.clear();
+                }
+                // Backspacing past the start closes the prompt, like vim.
+                KeyCode::Backspace if self.input.is_empty() => self.active = false,
+                KeyCode::Backspace => {
+                    self.input.pop();
+                }
+                KeyCode::Char(c) => self.input.push(c),
+                _ => {}
+            },
+            AppEvent::UserAction(_) => self.notice = None,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        let line = match &self.notice {
+            Some((error, true)) if !self.active => {
+                Line::from(Span::styled(format!(" {error}"), theme().error))
+            }
+            Some((message, false)) if !self.active => {
+                Line::from(Span::styled(format!(" {message}"), theme().text))
+            }
+            _ => Line::from(vec![
+                Span::styled(self.kind.prefix(), theme().title),
+                Span::raw(self.input.as_str()),
+                Span::styled(" ", theme().cursor),
+            ]),
+        };
+
+        line.style(theme().bar).render(area, buf);
+        Ok(())
+    }
+}
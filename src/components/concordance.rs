@@ -0,0 +1,305 @@
+use crate::app::events::{AppEvent, UserAction};
+use crate::bible::Bible;
+use crate::canon;
+use crate::components::Component;
+use crate::concordance::{Concordance, ConcordanceHit, ConcordanceResults, Term};
+use crate::prelude::*;
+use crate::reference::Reference;
+use ratatui::widgets::{Block, Borders};
+
+/// Width of the histogram of occurrences per book, left of the occurrences.
+const HISTOGRAM_WIDTH: u16 = 32;
+/// Width of the "chapter:verse" of an occurrence.
+const LOCATION_WIDTH: usize = 8;
+
+/// A row of the occurrences.
+enum Row {
+    /// Heading of the book at this index of `ConcordanceResults::books`.
+    Book(usize),
+    /// The hit at this index.
+    Hit(usize),
+}
+
+/// Every occurrence of a word or Strong's number, grouped by book, with a histogram of the
+/// occurrences per book.  Shown in place of the search results.
+pub struct ConcordancePanel {
+    bible: Arc<Bible>,
+    // Loaded on the first lookup, it is not needed otherwise.
+    index: Option<Concordance>,
+    results: Option<ConcordanceResults>,
+    rows: Vec<Row>,
+    /// Row of every hit.
+    hit_rows: Vec<usize>,
+    /// Index of the selected hit.
+    selected: usize,
+    scrolled_offset: usize,
+    histogram_offset: usize,
+    focused: bool,
+    open: bool,
+    selected_reference: Option<Reference>,
+}
+
+impl ConcordancePanel {
+    pub fn new(bible: Arc<Bible>) -> Self {
+        Self {
+            bible,
+            index: None,
+            results: None,
+            rows: Vec::new(),
+            hit_rows: Vec::new(),
+            selected: 0,
+            scrolled_offset: 0,
+            histogram_offset: 0,
+            focused: false,
+            open: false,
+            selected_reference: None,
+        }
+    }
+
+    /// Looks up a word or Strong's number, e.g. "faith" or "G4102".
+    pub fn lookup(&mut self, input: &str) -> Result<()> {
+        let term = Term::parse(input)?;
+        let index = self
+            .index
+            .get_or_insert_with(|| Concordance::load(&self.bible));
+        let results = index.lookup(&self.bible, term);
+
+        self.rows.clear();
+        self.hit_rows.clear();
+        for (i, hit) in results.hits.iter().enumerate() {
+            let book = results
+                .books
+                .iter()
+                .position(|(b, _)| *b == hit.reference.book);
+            if i == 0 || hit.reference.book != results.hits[i - 1].reference.book {
+                self.rows.extend(book.map(Row::Book));
+            }
+            self.hit_rows.push(self.rows.len());
+            self.rows.push(Row::Hit(i));
+        }
+        self.results = Some(results);
+        self.selected = 0;
+        self.scrolled_offset = 0;
+        self.histogram_offset = 0;
+        self.open = true;
+        Ok(())
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// The word or Strong's number that was looked up, if the panel is open.
+    pub fn query(&self) -> Option<&str> {
+        let results = self.results.as_ref().filter(|_| self.open)?;
+        Some(results.term.key())
+    }
+
+    /// The reference of the occurrence that was selected with enter, if any.
+    pub fn take_selected(&mut self) -> Option<Reference> {
+        self.selected_reference.take()
+    }
+
+    fn hits(&self) -> &[ConcordanceHit] {
+        self.results.as_ref().map_or(&[], |r| &r.hits)
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let last = self.hits().len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// The keyword in context, aligned on the keyword.
+    fn build_line(hit: &ConcordanceHit, selected: bool, width: usize) -> Line<'_> {
+        let location = format!(
+            "{:<LOCATION_WIDTH$}",
+            format!("{}:{}", hit.reference.chapter, hit.reference.first_verse())
+        );
+        let mut spans = vec![match selected {
+            true => Span::styled(location, theme().selected),
+            false => Span::styled(location, theme().dim),
+        }];
+
+        // The keyword starts in the middle of the text, cutting off the context before it.
+        let context = width.saturating_sub(LOCATION_WIDTH) / 2;
+        let before = &hit.text[..hit.range.start];
+        let count = before.chars().count();
+        let before = match count > context {
+            true => {
+                let ellipsis = icon(Icon::Ellipsis);
+                let skip = count + ellipsis.chars().count() - context;
+                format!(
+                    "{ellipsis}{}",
+                    before.chars().skip(skip).collect::<String>()
+                )
+            }
+            false => format!("{before:>context$}"),
+        };
+        spans.push(Span::raw(before));
+        spans.push(Span::styled(
+            &hit.text[hit.range.clone()],
+            theme().search_hit,
+        ));
+        spans.push(Span::raw(&hit.text[hit.range.end..]));
+        Line::from(spans)
+    }
+
+    fn render_histogram(&mut self, results: &ConcordanceResults, area: Rect, buf: &mut Buffer) {
+        let selected_book = results.hits.get(self.selected).and_then(|hit| {
+            results
+                .books
+                .iter()
+                .position(|(b, _)| *b == hit.reference.book)
+        });
+
+        // Scrolling logic, following the book of the selected hit.
+        let visible = (area.height as usize).max(1);
+        if let Some(book) = selected_book {
+            if book < self.histogram_offset {
+                self.histogram_offset = book;
+            }
+            if book >= self.histogram_offset + visible {
+                self.histogram_offset = book + 1 - visible;
+            }
+        }
+
+        let max = results.books.iter().map(|(_, n)| *n).max().unwrap_or(1);
+        let name_width = 7;
+        let bar_width = (area.width as usize).saturating_sub(name_width + 6).max(1);
+        for (row, (i, (book, count))) in results
+            .books
+            .iter()
+            .enumerate()
+            .skip(self.histogram_offset)
+            .take(visible)
+            .enumerate()
+        {
+            let name = format!("{book:<name_width$}");
+            let bar = icon(Icon::Bar).repeat((count * bar_width / max).max(1));
+            let line = Line::from(vec![
+                match Some(i) == selected_book {
+                    true => Span::styled(name, theme().selected),
+                    false => Span::styled(name, theme().dim),
+                },
+                Span::styled(bar, theme().histogram),
+                Span::styled(format!(" {count}"), theme().text),
+            ]);
+            buf.set_line(area.x, area.y + row as u16, &line, area.width);
+        }
+    }
+}
+
+impl Component for ConcordancePanel {
+    fn update(&mut self, event: &AppEvent) -> Result<()> {
+        match event {
+            AppEvent::Focus => self.focused = true,
+            AppEvent::Defocus => self.focused = false,
+            AppEvent::UserAction(action) if self.focused => match action {
+                UserAction::MoveDown => self.move_selection(1),
+                UserAction::MoveUp => self.move_selection(-1),
+                UserAction::PageDown => self.move_selection(10),
+                UserAction::PageUp => self.move_selection(-10),
+                UserAction::Select => {
+                    self.selected_reference =
+                        self.hits().get(self.selected).map(|h| h.reference.clone());
+                }
+                UserAction::Close => self.open = false,
+                _ => {}
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        let title = match &self.results {
+            Some(r) => {
+                let term = match &r.term {
+                    Term::Word(word) => word,
+                    Term::Strongs(number) => number,
+                };
+                format!(
+                    " [5] Concordance: {term} ({} occurrences in {} verses, {} books) ",
+                    r.hits.len(),
+                    r.verses,
+                    r.books.len()
+                )
+            }
+            None => " [5] Concordance ".to_string(),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(border_set())
+            .title(Span::styled(title, theme().title))
+            .border_style(theme().border(self.focused));
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let Some(results) = self.results.take() else {
+            return Ok(());
+        };
+        if results.hits.is_empty() {
+            Line::from(Span::styled("No occurrences", theme().dim)).render(inner, buf);
+            self.results = Some(results);
+            return Ok(());
+        }
+
+        let [histogram, _, occurrences] = Layout::horizontal([
+            Constraint::Length(HISTOGRAM_WIDTH.min(inner.width / 3)),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+        self.render_histogram(&results, histogram, buf);
+
+        // Scrolling logic, showing the heading of the book above its first hit.
+        let visible = (occurrences.height as usize).max(1);
+        let row = self.hit_rows.get(self.selected).copied().unwrap_or(0);
+        let top = match row.checked_sub(1).map(|r| &self.rows[r]) {
+            Some(Row::Book(_)) => row - 1,
+            _ => row,
+        };
+        if top < self.scrolled_offset {
+            self.scrolled_offset = top;
+        }
+        if row >= self.scrolled_offset + visible {
+            self.scrolled_offset = row + 1 - visible;
+        }
+
+        for (y, row) in self
+            .rows
+            .iter()
+            .skip(self.scrolled_offset)
+            .take(visible)
+            .enumerate()
+        {
+            let line = match row {
+                Row::Book(i) => {
+                    let (book, count) = &results.books[*i];
+                    Line::from(vec![
+                        Span::styled(canon::book_name(book), theme().chapter_heading),
+                        Span::styled(format!(" ({count})"), theme().dim),
+                    ])
+                }
+                Row::Hit(i) => Self::build_line(
+                    &results.hits[*i],
+                    *i == self.selected,
+                    occurrences.width as usize,
+                ),
+            };
+            buf.set_line(
+                occurrences.x,
+                occurrences.y + y as u16,
+                &line,
+                occurrences.width,
+            );
+        }
+        self.results = Some(results);
+        Ok(())
+    }
+}
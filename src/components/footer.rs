@@ -12,10 +12,14 @@ pub struct LogosFooter {
 impl LogosFooter {
     pub fn new() -> Self {
         Self {
-            // TODO: Check for these icons support before just rendering it.
-            app_name: "   logos ".to_string(),
-            version: format!("[{}]", VERSION.to_string()),
-            keymaps: String::from("[q] quit "),
+            app_name: match icon(Icon::Logo) {
+                "" => " logos ".to_string(),
+                logo => format!(" {logo}  logos "),
+            },
+            version: format!("[{VERSION}]"),
+            keymaps: String::from(
+                "[:] jump  [/] search  [v] select  [y] yank  [m] bookmark  [n] note  [H] highlight  [t] translation  [p] parallel  [R] red letter  [q] quit ",
+            ),
         }
     }
 }
@@ -33,16 +37,14 @@ impl Component for LogosFooter {
         .areas(area);
 
         Line::from(vec![
-            Span::styled(&self.app_name, Style::new().white().bold()),
-            Span::styled(&self.version, Style::new().dark_gray().bold()),
+            Span::styled(&self.app_name, theme().text.bold()),
+            Span::styled(&self.version, theme().dim.bold()),
         ])
-        .bg(Color::Black)
+        .style(theme().bar)
         .render(left_area, buf);
 
         Line::from(self.keymaps.as_str())
-            .bg(Color::Black)
-            .bold()
-            .red()
+            .style(theme().bar.patch(theme().hint))
             .render(right_area, buf);
 
         Ok(())
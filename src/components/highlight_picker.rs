@@ -0,0 +1,113 @@
+use crate::app::events::{AppEvent, UserAction};
+use crate::components::Component;
+use crate::components::book_reader::HighlightTarget;
+use crate::prelude::*;
+use crate::reference::format_range;
+use ratatui::widgets::{Block, Borders, Clear};
+
+const WIDTH: u16 = 40;
+
+/// Popup listing the categories of highlights of the settings, to highlight the selection of the
+/// reader in.  It is modal like the translation picker.
+pub struct HighlightPicker {
+    target: Option<HighlightTarget>,
+    /// Index of the selected category, the one after the last removes the highlights.
+    selected: usize,
+    picked: Option<(HighlightTarget, Option<String>)>,
+}
+
+impl HighlightPicker {
+    pub fn new() -> Self {
+        Self {
+            target: None,
+            selected: 0,
+            picked: None,
+        }
+    }
+
+    /// Opens the picker for the verses or words, keeping the category that was picked last.
+    pub fn open(&mut self, target: HighlightTarget) {
+        self.target = Some(target);
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// The target and the name of the category that was picked with enter, `None` to remove the
+    /// highlights of the target.
+    pub fn take_picked(&mut self) -> Option<(HighlightTarget, Option<String>)> {
+        self.picked.take()
+    }
+}
+
+impl Component for HighlightPicker {
+    fn update(&mut self, event: &AppEvent) -> Result<()> {
+        let categories = &settings().highlights;
+        match event {
+            AppEvent::UserAction(action) if self.is_open() => match action {
+                UserAction::MoveDown if self.selected < categories.len() => self.selected += 1,
+                UserAction::MoveUp => self.selected = self.selected.saturating_sub(1),
+                UserAction::Select => {
+                    let category = categories.get(self.selected).map(|c| c.name.clone());
+                    self.picked = self.target.take().map(|target| (target, category));
+                }
+                UserAction::Close | UserAction::Highlight => self.target = None,
+                _ => {}
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        let Some(target) = &self.target else {
+            return Ok(());
+        };
+        let categories = &settings().highlights;
+        self.selected = self.selected.min(categories.len());
+
+        // Centered popup that fits the categories and removing the highlights.
+        let width = area.width.min(WIDTH);
+        let height = area.height.min(categories.len() as u16 + 3);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let mut title = format_range(&target.book, target.start, target.end);
+        if let Some(words) = &target.words {
+            title = match words.len() {
+                1 => format!("{title} word {}", words.start + 1),
+                _ => format!("{title} words {}-{}", words.start + 1, words.end),
+            };
+        }
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(border_set())
+            .title(Span::styled(format!(" Highlight {title} "), theme().title))
+            .border_style(theme().border(true));
+        let inner = block.inner(popup);
+        Clear.render(popup, buf);
+        block.render(popup, buf);
+
+        // Keep the selection in view.
+        let visible = inner.height as usize;
+        let offset = (self.selected + 1).saturating_sub(visible);
+        let names = categories
+            .iter()
+            .map(|c| (format!(" {} ", c.name), c.style.0))
+            .chain([(" Remove highlights ".to_string(), theme().dim)]);
+        for (row, (i, (name, style))) in names.enumerate().skip(offset).take(visible).enumerate() {
+            let marker = match i == self.selected {
+                true => Span::styled(format!("{} ", icon(Icon::Selected)), theme().selected),
+                false => Span::raw("  "),
+            };
+            let line = Line::from(vec![marker, Span::styled(name, style)]);
+            buf.set_line(inner.x, inner.y + row as u16, &line, inner.width);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,317 @@
+use crate::app::events::{AppEvent, UserAction};
+use crate::bible::Bible;
+use crate::canon;
+use crate::components::Component;
+use crate::components::book_column::ColumnVerseSegment;
+use crate::highlights::{Highlight, Highlights};
+use crate::prelude::*;
+use crate::reference::{Reference, format_range};
+use ratatui::widgets::{Block, Borders};
+
+/// Width of the location of a highlight, in front of the category.
+const LABEL_WIDTH: usize = 24;
+
+/// A highlight, resolved against the translation.
+struct Item {
+    highlight: Highlight,
+    /// e.g. "John 3:16-18" or "John 3:16 words 2-4", in the numbering of the translation.
+    label: String,
+    /// Where to jump to, `None` if the translation does not have the verse.
+    reference: Option<Reference>,
+    /// The highlighted words, or the text of the first verse.
+    preview: String,
+}
+
+impl Item {
+    fn new(bible: &Bible, highlight: &Highlight) -> Option<Item> {
+        let (book, (c1, v1), (c2, v2)) = highlight.range()?;
+        let versification = bible.get_versification();
+        let start = versification.map_from_kjv(book, c1, v1);
+        let end = versification.map_from_kjv(book, c2, v2);
+
+        let verse = bible
+            .get_chapter(book, start.0)
+            .ok()
+            .and_then(|c| c.get_verses().find(|v| v.number == start.1));
+        let reference = verse.map(|_| Reference {
+            book: book.to_string(),
+            chapter: start.0,
+            verses: Some(match end.0 == start.0 {
+                true => start.1..=end.1.max(start.1),
+                false => start.1..=start.1,
+            }),
+        });
+
+        let mut label = format_range(book, start, end);
+        let words = highlight.words.as_ref();
+        if let Some(words) = words {
+            label = match words.end - words.start {
+                1 => format!("{label} word {}", words.start + 1),
+                _ => format!("{label} words {}-{}", words.start + 1, words.end),
+            };
+        }
+        let preview = match (verse, words) {
+            (None, _) => format!("Not in {}", bible.get_translation().name),
+            (Some(_), Some(words)) if words.translation != bible.get_translation().name => {
+                format!("Words in {}", words.translation)
+            }
+            (Some(verse), Some(words)) => ColumnVerseSegment::from_verse(bible, verse)
+                .words
+                .iter()
+                .skip(words.start)
+                .take(words.end - words.start)
+                .map(|word| {
+                    word.parts
+                        .iter()
+                        .map(|(text, _)| text.as_str())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join(" "),
+            (Some(verse), None) => verse.collect_string(bible.get_raw_data()),
+        };
+
+        Some(Item {
+            highlight: highlight.clone(),
+            label,
+            reference,
+            preview,
+        })
+    }
+}
+
+/// Overview of the highlights, filtered by category and by book.  Shown in place of the search
+/// results.
+pub struct HighlightsPanel {
+    highlights: Highlights,
+    bible: Arc<Bible>,
+    /// Name of the category that is shown, `None` for all of them.
+    category: Option<String>,
+    /// OSIS ID of the book that is shown, `None` for all of them.
+    book: Option<String>,
+    items: Vec<Item>,
+    selected: usize,
+    scrolled_offset: usize,
+    focused: bool,
+    open: bool,
+    selected_reference: Option<Reference>,
+}
+
+impl HighlightsPanel {
+    pub fn new(highlights: Highlights, bible: Arc<Bible>) -> Self {
+        Self {
+            highlights,
+            bible,
+            category: None,
+            book: None,
+            items: Vec::new(),
+            selected: 0,
+            scrolled_offset: 0,
+            focused: false,
+            open: false,
+            selected_reference: None,
+        }
+    }
+
+    pub fn highlights(&self) -> &Highlights {
+        &self.highlights
+    }
+
+    /// Labels the highlights in the numbering of another translation.
+    pub fn set_bible(&mut self, bible: Arc<Bible>) {
+        self.bible = bible;
+        self.build_items();
+    }
+
+    pub fn open(&mut self) {
+        self.build_items();
+        self.open = true;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// The reference of the highlight that was selected with enter, if any.
+    pub fn take_selected(&mut self) -> Option<Reference> {
+        self.selected_reference.take()
+    }
+
+    /// See `Highlights::add`.
+    pub fn add(&mut self, highlight: Highlight) -> Result<()> {
+        let result = self.highlights.add(highlight);
+        self.build_items();
+        result
+    }
+
+    /// See `Highlights::clear`.
+    pub fn clear(
+        &mut self,
+        book: &str,
+        start: (usize, usize),
+        end: (usize, usize),
+    ) -> Result<usize> {
+        let result = self.highlights.clear(book, start, end);
+        self.build_items();
+        result
+    }
+
+    fn build_items(&mut self) {
+        self.items = self
+            .highlights
+            .iter()
+            .filter(|h| self.category.as_ref().is_none_or(|c| *c == h.category))
+            .filter(|h| {
+                self.book
+                    .as_ref()
+                    .is_none_or(|b| h.range().is_some_and(|(book, _, _)| book == b))
+            })
+            .filter_map(|h| Item::new(&self.bible, h))
+            .collect();
+        self.selected = self.selected.min(self.items.len().saturating_sub(1));
+    }
+
+    /// Shows the next category of the settings, after the last one all of them.
+    fn cycle_category(&mut self) {
+        let categories = &settings().highlights;
+        let next = match &self.category {
+            None => 0,
+            Some(name) => categories
+                .iter()
+                .position(|c| c.name == *name)
+                .map_or(0, |i| i + 1),
+        };
+        self.category = categories.get(next).map(|c| c.name.clone());
+        self.selected = 0;
+        self.build_items();
+    }
+
+    /// Shows the next book with highlights, after the last one all of them.
+    fn cycle_book(&mut self) {
+        let mut books: Vec<&str> = self
+            .highlights
+            .iter()
+            .filter_map(|h| h.range().map(|(book, _, _)| book))
+            .collect();
+        // The highlights are in canon order.
+        books.dedup();
+        let next = match &self.book {
+            None => 0,
+            Some(name) => books.iter().position(|b| b == name).map_or(0, |i| i + 1),
+        };
+        self.book = books.get(next).map(|b| b.to_string());
+        self.selected = 0;
+        self.build_items();
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let last = self.items.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    fn build_line(item: &Item, selected: bool) -> Line<'_> {
+        let label = format!("{:<LABEL_WIDTH$}", item.label);
+        let category = format!(" {} ", item.highlight.category);
+        Line::from(vec![
+            match selected {
+                true => Span::styled(label, theme().selected),
+                false => Span::raw(label),
+            },
+            match settings().highlight_style(&item.highlight.category) {
+                Some(style) => Span::styled(category, style),
+                None => Span::styled(category, theme().dim),
+            },
+            Span::styled(format!(" {}", item.preview), theme().dim),
+        ])
+    }
+
+    fn empty_message(&self) -> &'static str {
+        match self.highlights.iter().next() {
+            None => "No highlights, [H] in the reader highlights the selection",
+            Some(_) => "No highlights of this category in this book, [f] and [F] change them",
+        }
+    }
+}
+
+impl Component for HighlightsPanel {
+    /// Errors are from saving the highlights, the change is kept in memory.
+    fn update(&mut self, event: &AppEvent) -> Result<()> {
+        match event {
+            AppEvent::Focus => self.focused = true,
+            AppEvent::Defocus => self.focused = false,
+            AppEvent::UserAction(action) if self.focused => match action {
+                UserAction::MoveDown => self.move_selection(1),
+                UserAction::MoveUp => self.move_selection(-1),
+                UserAction::PageDown => self.move_selection(10),
+                UserAction::PageUp => self.move_selection(-10),
+                UserAction::Select => {
+                    self.selected_reference = self
+                        .items
+                        .get(self.selected)
+                        .and_then(|item| item.reference.clone());
+                }
+                UserAction::Delete => {
+                    if let Some(item) = self.items.get(self.selected) {
+                        let result = self.highlights.remove(&item.highlight);
+                        self.build_items();
+                        result?;
+                    }
+                }
+                UserAction::FilterCategory => self.cycle_category(),
+                UserAction::FilterBook => self.cycle_book(),
+                UserAction::Close => self.open = false,
+                _ => {}
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        let category = self.category.as_deref().unwrap_or("all categories");
+        let book = self.book.as_deref().map_or("all books", canon::book_name);
+        let title = format!(
+            " [5] Highlights ({}, {category}, {book}) ",
+            self.items.len()
+        );
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(border_set())
+            .title(Span::styled(title, theme().title))
+            .border_style(theme().border(self.focused));
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if self.items.is_empty() {
+            Line::styled(self.empty_message(), theme().dim).render(inner, buf);
+            return Ok(());
+        }
+
+        // Scrolling logic.
+        let visible = (inner.height as usize).max(1);
+        if self.selected < self.scrolled_offset {
+            self.scrolled_offset = self.selected;
+        }
+        if self.selected >= self.scrolled_offset + visible {
+            self.scrolled_offset = self.selected + 1 - visible;
+        }
+
+        for (row, (i, item)) in self
+            .items
+            .iter()
+            .enumerate()
+            .skip(self.scrolled_offset)
+            .take(visible)
+            .enumerate()
+        {
+            let line = Self::build_line(item, i == self.selected && self.focused);
+            buf.set_line(inner.x, inner.y + row as u16, &line, inner.width);
+        }
+        Ok(())
+    }
+}
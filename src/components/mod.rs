@@ -1,10 +1,19 @@
 pub mod book_column;
 pub mod book_reader;
+pub mod bookmarks;
 pub mod books_view;
+pub mod command_prompt;
+pub mod concordance;
 pub mod footer;
+pub mod highlight_picker;
+pub mod highlights;
+pub mod notes;
+pub mod parallel_view;
 pub mod references;
+pub mod search_panel;
 pub mod splash_screen;
 pub mod strongs;
+pub mod translation_picker;
 
 use crate::app::events::AppEvent;
 use crate::prelude::*;
@@ -0,0 +1,307 @@
+use crate::app::events::{AppEvent, UserAction};
+use crate::bible::Bible;
+use crate::canon;
+use crate::components::Component;
+use crate::crossrefs::osis_range;
+use crate::editor::EditRequest;
+use crate::notes::{Note, Notes};
+use crate::prelude::*;
+use crate::reference::format_range;
+use ratatui::widgets::{Block, Borders, Wrap};
+
+/// Rows that paging scrolls the text of the notes by.
+const PAGE_ROWS: u16 = 5;
+
+/// The notes about the verse that is being read, rendered from markdown.  Notes are written in
+/// the editor of the user, see `EditRequest`.
+pub struct NotesPanel {
+    notes: Notes,
+    bible: Arc<Bible>,
+    /// (book, chapter, verse) in the numbering of the KJV.
+    verse: Option<(String, usize, usize)>,
+    /// Index of the selected note of the verse, the ones before it are scrolled out of view.
+    selected: usize,
+    /// Rows of text scrolled out of view, below the selected note.
+    scrolled_rows: u16,
+    focused: bool,
+    /// Reference of the note that is being edited.
+    editing: Option<String>,
+    edit_request: Option<EditRequest>,
+}
+
+impl NotesPanel {
+    pub fn new(notes: Notes, bible: Arc<Bible>) -> Self {
+        Self {
+            notes,
+            bible,
+            verse: None,
+            selected: 0,
+            scrolled_rows: 0,
+            focused: false,
+            editing: None,
+            edit_request: None,
+        }
+    }
+
+    pub fn notes(&self) -> &Notes {
+        &self.notes
+    }
+
+    /// Labels the notes in the numbering of another translation.
+    pub fn set_bible(&mut self, bible: Arc<Bible>) {
+        self.bible = bible;
+        self.verse = None;
+    }
+
+    /// Shows the notes of the verse, in the numbering of the translation.
+    pub fn set_verse(&mut self, book: &str, chapter: usize, verse: usize) {
+        let (chapter, verse) = self
+            .bible
+            .get_versification()
+            .map_to_kjv(book, chapter, verse);
+        if self
+            .verse
+            .as_ref()
+            .is_some_and(|(b, c, v)| (b.as_str(), *c, *v) == (book, chapter, verse))
+        {
+            return;
+        }
+        self.verse = Some((book.to_string(), chapter, verse));
+        self.selected = 0;
+        self.scrolled_rows = 0;
+    }
+
+    /// Opens the note of the OSIS reference in the editor, a new one if there is none yet.
+    pub fn edit(&mut self, reference: &str) {
+        let text = match self.notes.get(reference) {
+            Some(note) => format!("{}\n", note.text),
+            None => String::new(),
+        };
+        self.editing = Some(reference.to_string());
+        self.edit_request = Some(EditRequest {
+            name: reference.to_string(),
+            text,
+        });
+    }
+
+    /// The note to open in the editor, if any.
+    pub fn take_edit_request(&mut self) -> Option<EditRequest> {
+        self.edit_request.take()
+    }
+
+    /// Saves the text of the note that was edited, an empty text removes the note.  Returns a
+    /// message for the user.
+    pub fn finish_edit(&mut self, text: &str) -> Result<String> {
+        let Some(reference) = self.editing.take() else {
+            return Ok(String::new());
+        };
+        let location = self.location(&reference);
+        let existed = self.notes.get(&reference).is_some();
+        self.notes.set(&reference, text)?;
+        Ok(match (existed, self.notes.get(&reference).is_some()) {
+            (_, true) => format!("Saved note on {location}"),
+            (true, false) => format!("Removed note on {location}"),
+            (false, false) => format!("No note on {location}, it was left empty"),
+        })
+    }
+
+    /// The notes of the verse that is shown.
+    fn shown(&self) -> Vec<&Note> {
+        match &self.verse {
+            Some((book, chapter, verse)) => self.notes.for_verse(book, *chapter, *verse),
+            None => Vec::new(),
+        }
+    }
+
+    /// e.g. "John 3:16-18" for "John.3.16-John.3.18", in the numbering of the translation.
+    fn location(&self, reference: &str) -> String {
+        let Some((book, start, end)) = osis_range(reference) else {
+            return reference.to_string();
+        };
+        let versification = self.bible.get_versification();
+        let start = versification.map_from_kjv(book, start.0, start.1);
+        let end = versification.map_from_kjv(book, end.0, end.1);
+        format_range(book, start, end)
+    }
+}
+
+impl Component for NotesPanel {
+    /// Errors are from saving the notes, the change is kept in memory.
+    fn update(&mut self, event: &AppEvent) -> Result<()> {
+        match event {
+            AppEvent::Focus => self.focused = true,
+            AppEvent::Defocus => self.focused = false,
+            AppEvent::UserAction(action) if self.focused => match action {
+                UserAction::MoveDown if self.selected + 1 < self.shown().len() => {
+                    self.selected += 1;
+                    self.scrolled_rows = 0;
+                }
+                UserAction::MoveUp => {
+                    self.selected = self.selected.saturating_sub(1);
+                    self.scrolled_rows = 0;
+                }
+                UserAction::PageDown => self.scrolled_rows += PAGE_ROWS,
+                UserAction::PageUp => {
+                    self.scrolled_rows = self.scrolled_rows.saturating_sub(PAGE_ROWS)
+                }
+                UserAction::EditNote => {
+                    if let Some(reference) =
+                        self.shown().get(self.selected).map(|n| n.reference.clone())
+                    {
+                        self.edit(&reference);
+                    }
+                }
+                UserAction::Delete => {
+                    if let Some(reference) =
+                        self.shown().get(self.selected).map(|n| n.reference.clone())
+                    {
+                        self.notes.remove(&reference)?;
+                        self.selected = self.selected.min(self.shown().len().saturating_sub(1));
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        let shown = self.shown();
+        let title = match &self.verse {
+            Some((book, chapter, verse)) if !shown.is_empty() => {
+                let (chapter, verse) = self
+                    .bible
+                    .get_versification()
+                    .map_from_kjv(book, *chapter, *verse);
+                format!(
+                    " [7] Notes: {} {chapter}:{verse} ({}) ",
+                    canon::book_name(book),
+                    shown.len()
+                )
+            }
+            _ => " [7] Notes ".to_string(),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(border_set())
+            .title(Span::styled(title, theme().title))
+            .border_style(theme().border(self.focused));
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if shown.is_empty() {
+            Line::styled("No notes, [n] in the reader writes one", theme().dim).render(inner, buf);
+            return Ok(());
+        }
+
+        // The selected note is at the top, the text of the notes after it scrolls.
+        let mut lines: Vec<Line> = Vec::new();
+        for (i, note) in shown.iter().enumerate().skip(self.selected) {
+            if i > self.selected {
+                lines.push(Line::raw(""));
+            }
+            let location = self.location(&note.reference);
+            lines.push(match i == self.selected && self.focused {
+                true => Line::styled(location, theme().selected),
+                false => Line::styled(location, theme().chapter_heading),
+            });
+            lines.extend(markdown_lines(&note.text));
+        }
+        self.scrolled_rows = self.scrolled_rows.min(lines.len().saturating_sub(1) as u16);
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scrolled_rows, 0))
+            .render(inner, buf);
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Renders the markdown that notes are usually written with: headings, lists, quotes, code
+/// blocks and inline emphasis.  Anything else is shown as it is.
+fn markdown_lines(text: &str) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut code_block = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            code_block = !code_block;
+            continue;
+        }
+        if code_block {
+            lines.push(Line::styled(format!("  {line}"), theme().dim));
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        let heading = trimmed.trim_start_matches('#');
+        if heading.len() < trimmed.len() && (heading.is_empty() || heading.starts_with(' ')) {
+            lines.push(Line::styled(heading.trim().to_string(), theme().title));
+        } else if let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|bullet| trimmed.strip_prefix(bullet))
+        {
+            let mut spans = vec![Span::raw(format!(
+                "{}{} ",
+                " ".repeat(indent),
+                icon(Icon::Bullet)
+            ))];
+            spans.extend(inline_spans(item));
+            lines.push(Line::from(spans));
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            let spans = inline_spans(quote.trim_start())
+                .into_iter()
+                .map(|span| span.patch_style(theme().dim));
+            let mut line = Line::from(Span::styled("> ", theme().dim));
+            line.extend(spans);
+            lines.push(line);
+        } else {
+            lines.push(Line::from(inline_spans(line)));
+        }
+    }
+    lines
+}
+
+/// Styles `**strong**`, `*emphasis*` and `` `code` ``.  Markers that are not closed are shown as
+/// they are.
+fn inline_spans(text: &str) -> Vec<Span<'static>> {
+    const MARKERS: [&str; 3] = ["**", "*", "`"];
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let styled = MARKERS.iter().find_map(|marker| {
+            let inner = rest.strip_prefix(marker)?;
+            let end = inner.find(marker).filter(|end| *end > 0)?;
+            Some((marker, &inner[..end], &inner[end + marker.len()..]))
+        });
+        match styled {
+            Some((marker, inner, after)) => {
+                if !plain.is_empty() {
+                    spans.push(Span::raw(std::mem::take(&mut plain)));
+                }
+                let style = match *marker {
+                    "**" => Style::new().bold(),
+                    "`" => theme().dim,
+                    _ => Style::new().italic(),
+                };
+                spans.push(Span::styled(inner.to_string(), style));
+                rest = after;
+            }
+            None => {
+                let mut chars = rest.chars();
+                plain.extend(chars.next());
+                rest = chars.as_str();
+            }
+        }
+    }
+    if !plain.is_empty() {
+        spans.push(Span::raw(plain));
+    }
+    spans
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,426 @@
+use std::collections::BTreeSet;
+
+use ratatui::widgets::{Block, Borders};
+
+use crate::app::events::{AppEvent, UserAction};
+use crate::bible::Bible;
+use crate::components::Component;
+use crate::components::book_column::{ColumnChapter, ColumnVerseSegment, VerseHighlight};
+use crate::prelude::*;
+use crate::reference::Reference;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A row of the parallel view that lines up across all of the translations.
+#[derive(Debug)]
+enum AlignedRow {
+    Heading(usize),
+    Verse {
+        chapter: usize,
+        verse: usize,
+        /// The verse in each translation, `None` where the translation does not have it.
+        segments: Vec<Option<ColumnChapter>>,
+    },
+}
+
+impl AlignedRow {
+    /// Rows of text needed for the row to fit in every translation.
+    fn height(&self, width: usize, first: bool) -> usize {
+        match self {
+            // Gap before all but the first heading.
+            AlignedRow::Heading(_) if first => 1,
+            AlignedRow::Heading(_) => 2,
+            AlignedRow::Verse { segments, .. } => segments
+                .iter()
+                .flatten()
+                .map(|s| s.consumed_chars(width) / width)
+                .max()
+                .unwrap_or(1)
+                .max(1),
+        }
+    }
+
+    fn chapter(&self) -> usize {
+        match self {
+            AlignedRow::Heading(chapter) => *chapter,
+            AlignedRow::Verse { chapter, .. } => *chapter,
+        }
+    }
+
+    /// The lines of translation `i`, padded to `height`.
+    fn build(
+        &self,
+        i: usize,
+        width: usize,
+        height: usize,
+        highlights: &[VerseHighlight],
+        red_letter: bool,
+    ) -> Vec<Line<'_>> {
+        let mut lines = match self {
+            AlignedRow::Heading(chapter) => {
+                let mut lines = vec![Line::raw(""); height - 1];
+                lines.push(ColumnChapter::heading(*chapter));
+                lines
+            }
+            AlignedRow::Verse { segments, .. } => match &segments[i] {
+                Some(segment) => segment.build(width, highlights, &[], red_letter),
+                None => vec![self.gap()],
+            },
+        };
+        lines.resize(height, Line::raw(""));
+        lines
+    }
+
+    /// Shown where a translation does not have the verse, so that the gap is visible.
+    fn gap(&self) -> Line<'static> {
+        let verse = match self {
+            AlignedRow::Verse { verse, .. } => *verse,
+            AlignedRow::Heading(_) => 0,
+        };
+        Line::from(vec![
+            Span::styled(format!("{verse} "), theme().dim),
+            Span::styled(icon(Icon::Separator), theme().dim),
+        ])
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Shows a book in multiple translations side by side, aligned by chapter and verse number.
+pub struct ParallelView {
+    bibles: Vec<Arc<Bible>>,
+    /// OSIS ID of the book.
+    book: String,
+    rows: Vec<AlignedRow>,
+    /// Index of the first visible row.
+    scrolled_offset: usize,
+    /// Rows that fit on the last render, used for paging.
+    visible_rows: usize,
+    /// The passage that was jumped to.
+    highlight: Option<VerseHighlight>,
+    /// Colour the words of Christ.
+    pub red_letter: bool,
+}
+
+impl ParallelView {
+    pub fn new(bibles: Vec<Arc<Bible>>, book: String) -> Self {
+        let mut view = ParallelView {
+            bibles,
+            book,
+            rows: Vec::new(),
+            scrolled_offset: 0,
+            visible_rows: 1,
+            highlight: None,
+            red_letter: false,
+        };
+        view.rows = view.align();
+        view
+    }
+
+    pub fn get_bibles(&self) -> &[Arc<Bible>] {
+        &self.bibles
+    }
+
+    /// Replaces the translations, keeping the same verse in view.
+    pub fn set_bibles(&mut self, bibles: Vec<Arc<Bible>>) {
+        let (_, chapter, verse) = self.position();
+        self.bibles = bibles;
+        self.rows = self.align();
+        self.scroll_to_verse(chapter, verse);
+    }
+
+    /// The (book, chapter, verse) at the top of the view.
+    pub fn position(&self) -> (&str, usize, usize) {
+        let (chapter, verse) = self
+            .rows
+            .iter()
+            .skip(self.scrolled_offset)
+            .find_map(|row| match row {
+                AlignedRow::Verse { chapter, verse, .. } => Some((*chapter, *verse)),
+                AlignedRow::Heading(_) => None,
+            })
+            .unwrap_or((1, 1));
+        (&self.book, chapter, verse)
+    }
+
+    /// Scrolls the verse to the top of the view.
+    pub fn scroll_to(&mut self, book: &str, chapter: usize, verse: usize) {
+        if self.book != book {
+            self.book = book.to_string();
+            self.rows = self.align();
+            self.highlight = None;
+        }
+        self.scroll_to_verse(chapter, verse);
+    }
+
+    /// Scrolls the reference into view and highlights the verses.
+    pub fn jump_to(&mut self, reference: Reference) {
+        self.scroll_to(&reference.book, reference.chapter, reference.first_verse());
+        self.highlight = reference.verses.map(|verses| VerseHighlight {
+            chapter: reference.chapter,
+            verses,
+            style: theme().highlight,
+            words: None,
+        });
+    }
+
+    fn scroll_to_verse(&mut self, chapter: usize, verse: usize) {
+        let row = self.rows.iter().position(|row| match row {
+            AlignedRow::Verse {
+                chapter: c,
+                verse: v,
+                ..
+            } => *c == chapter && *v == verse,
+            AlignedRow::Heading(_) => false,
+        });
+        self.scrolled_offset = match row {
+            // Keep the heading in view at the start of a chapter.
+            Some(row) if row > 0 && matches!(self.rows[row - 1], AlignedRow::Heading(_)) => row - 1,
+            Some(row) => row,
+            None => self
+                .rows
+                .iter()
+                .position(|row| row.chapter() == chapter)
+                .unwrap_or(0),
+        };
+    }
+
+    /// Lines up the chapters and verses of the book in all of the translations.  Rows are
+    /// numbered as in the first translation, verses of the others are converted from their own
+    /// versification, so that e.g. Malachi 4:1 lines up with 3:19.  Every verse that appears in
+    /// any translation gets a row.
+    fn align(&self) -> Vec<AlignedRow> {
+        let start = Instant::now();
+        let Some(system) = self.bibles.first().map(|b| b.get_versification()) else {
+            return Vec::new();
+        };
+        let books: Vec<_> = self
+            .bibles
+            .iter()
+            .map(|bible| bible.get_book_index(&self.book).ok())
+            .collect();
+
+        let verses: BTreeSet<(usize, usize)> = books
+            .iter()
+            .zip(&self.bibles)
+            .filter_map(|(book, bible)| Some(((*book)?, bible.get_versification())))
+            .flat_map(|(book, own)| {
+                book.get_chapters().flat_map(move |c| {
+                    c.get_verses()
+                        .map(move |v| own.convert(system, &self.book, c.number, v.number))
+                })
+            })
+            .collect();
+
+        let mut rows = Vec::new();
+        for (chapter, verse) in verses {
+            if rows.last().is_none_or(|row: &AlignedRow| row.chapter() != chapter) {
+                rows.push(AlignedRow::Heading(chapter));
+            }
+            let segments = books
+                .iter()
+                .zip(&self.bibles)
+                .map(|(book, bible)| {
+                    let (c, v) =
+                        system.convert(bible.get_versification(), &self.book, chapter, verse);
+                    let view = (*book)?
+                        .get_chapters()
+                        .find(|ch| ch.number == c)?
+                        .get_verses()
+                        .find(|view| view.number == v)?;
+                    Some(ColumnChapter {
+                        show_heading: false,
+                        number: c,
+                        verses: vec![ColumnVerseSegment::from_verse(bible, view)],
+                    })
+                })
+                .collect();
+            rows.push(AlignedRow::Verse {
+                chapter,
+                verse,
+                segments,
+            });
+        }
+
+        debug!(
+            "Aligned {} rows of {} in {:?}",
+            rows.len(),
+            self.book,
+            start.elapsed()
+        );
+        rows
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        self.scrolled_offset = self
+            .scrolled_offset
+            .saturating_add_signed(delta)
+            .min(self.rows.len().saturating_sub(1));
+    }
+}
+
+impl Component for ParallelView {
+    fn update(&mut self, event: &AppEvent) -> Result<()> {
+        if let AppEvent::UserAction(action) = event {
+            match action {
+                UserAction::MoveDown => self.scroll_by(1),
+                UserAction::MoveUp => self.scroll_by(-1),
+                UserAction::PageDown => self.scroll_by(self.visible_rows as isize),
+                UserAction::PageUp => self.scroll_by(-(self.visible_rows as isize)),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        let panes =
+            Layout::horizontal(vec![Constraint::Fill(1); self.bibles.len().max(1)]).split(area);
+        let (_, chapter, verse) = self.position();
+
+        // The same layout is used for every pane, so they line up.
+        let inner = |pane: Rect| {
+            let inner = Block::default().borders(Borders::ALL).inner(pane);
+            Rect {
+                x: inner.x + 1,
+                width: inner.width.saturating_sub(2),
+                ..inner
+            }
+        };
+        let text_area = inner(panes[0]);
+        let (width, height) = (text_area.width as usize, text_area.height as usize);
+
+        // Rows that fit, the last one may be cut off.
+        let mut heights = Vec::new();
+        let mut used = 0;
+        for (i, row) in self.rows.iter().enumerate().skip(self.scrolled_offset) {
+            if used >= height || width == 0 {
+                break;
+            }
+            let h = row.height(width, i == self.scrolled_offset);
+            heights.push(h);
+            used += h;
+        }
+        self.visible_rows = heights.len().saturating_sub(1).max(1);
+
+        let highlights: Vec<VerseHighlight> = self.highlight.iter().cloned().collect();
+        for (i, (bible, pane)) in self.bibles.iter().zip(panes.iter()).enumerate() {
+            let name = bible
+                .get_book_index(&self.book)
+                .map_or(crate::canon::book_name(&self.book), |b| b.name.as_str());
+            // The chapter in the numbering of the translation.
+            let (chapter, _) = self.bibles[0].get_versification().convert(
+                bible.get_versification(),
+                &self.book,
+                chapter,
+                verse,
+            );
+            let title = format!(" {name} {chapter} [{}] ", bible.get_translation().name);
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(border_set())
+                .title(Span::styled(title, theme().title))
+                .border_style(match i {
+                    0 => theme().border(true),
+                    _ => theme().border(false),
+                })
+                .render(*pane, buf);
+
+            let lines: Vec<Line> = self
+                .rows
+                .iter()
+                .skip(self.scrolled_offset)
+                .zip(&heights)
+                .flat_map(|(row, h)| row.build(i, width, *h, &highlights, self.red_letter))
+                .collect();
+            // Wrapping is done while building, so that it matches the layout.
+            Paragraph::new(lines).render(inner(*pane), buf);
+        }
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bible::tests::bible_from;
+
+    /// John with the verses of each chapter in `chapters`.
+    fn bible(chapters: &[&[usize]]) -> Arc<Bible> {
+        let chapters: String = chapters
+            .iter()
+            .zip(1..)
+            .map(|(verses, c)| {
+                let verses: String = verses
+                    .iter()
+                    .map(|v| {
+                        let id = format!("John.{c}.{v}");
+                        format!(r#"<verse sID="{id}" osisID="{id}"/>Verse {v}.<verse eID="{id}"/>"#)
+                    })
+                    .collect();
+                format!(r#"<chapter sID="John.{c}" osisID="John.{c}"/>{verses}<chapter eID="John.{c}"/>"#)
+            })
+            .collect();
+        Arc::new(bible_from(
+            "parallel.xml",
+            &format!(
+                r#"<osis><osisText><div type="book" osisID="John">{chapters}</div></osisText></osis>"#
+            ),
+        ))
+    }
+
+    fn view() -> ParallelView {
+        let bibles = vec![bible(&[&[1, 2, 3], &[1]]), bible(&[&[1, 3]])];
+        ParallelView::new(bibles, "John".to_string())
+    }
+
+    #[test]
+    fn aligns_verses_by_number() {
+        let rows: Vec<(usize, usize, Vec<bool>)> = view()
+            .rows
+            .iter()
+            .map(|row| match row {
+                AlignedRow::Heading(chapter) => (*chapter, 0, Vec::new()),
+                AlignedRow::Verse {
+                    chapter,
+                    verse,
+                    segments,
+                } => (
+                    *chapter,
+                    *verse,
+                    segments.iter().map(Option::is_some).collect(),
+                ),
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                (1, 0, vec![]),
+                (1, 1, vec![true, true]),
+                (1, 2, vec![true, false]),
+                (1, 3, vec![true, true]),
+                (2, 0, vec![]),
+                (2, 1, vec![true, false]),
+            ]
+        );
+    }
+
+    #[test]
+    fn scrolls_to_verses() {
+        let mut view = view();
+        view.scroll_to("John", 1, 3);
+        assert_eq!(view.position(), ("John", 1, 3));
+        // The heading stays in view at the start of a chapter.
+        view.scroll_to("John", 2, 1);
+        assert!(matches!(
+            view.rows[view.scrolled_offset],
+            AlignedRow::Heading(2)
+        ));
+        assert_eq!(view.position(), ("John", 2, 1));
+        // The translations are aligned again, the verse stays in view.
+        view.set_bibles(vec![bible(&[&[1], &[1, 2]])]);
+        assert_eq!(view.position(), ("John", 2, 1));
+    }
+}
@@ -1,15 +1,175 @@
-use crate::app::events::AppEvent;
+use crate::app::events::{AppEvent, UserAction};
+use crate::bible::Bible;
+use crate::canon;
 use crate::components::Component;
+use crate::crossrefs::{CrossReference, CrossReferences};
 use crate::prelude::*;
-use ratatui::widgets::{Block, BorderType, Borders};
+use crate::reference::{Reference, format_range};
+use ratatui::widgets::{Block, Borders};
 
+/// How the cross references are ordered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SortOrder {
+    /// By votes, or in the order of the dataset if it has none.
+    Relevance,
+    /// In the order of the books, chapters and verses.
+    Canon,
+}
+
+/// A cross reference of the verse, resolved against the translation.
+struct Item {
+    /// e.g. "John 1:1-3", in the numbering of the translation.
+    label: String,
+    votes: Option<i32>,
+    /// Where to jump to, `None` if the translation does not have the verse.
+    reference: Option<Reference>,
+    /// Text of the first verse.
+    preview: String,
+    /// Position in the dataset.
+    rank: usize,
+    /// (book, chapter, verse) of the KJV, for sorting in canon order.
+    position: (usize, usize, usize),
+}
+
+impl Item {
+    fn new(bible: &Bible, rank: usize, cross_reference: &CrossReference) -> Option<Item> {
+        let (book, (c1, v1), (c2, v2)) = cross_reference.range()?;
+        let versification = bible.get_versification();
+        let start = versification.map_from_kjv(book, c1, v1);
+        let end = versification.map_from_kjv(book, c2, v2);
+
+        let chapter = bible.get_chapter(book, start.0).ok();
+        let verse = chapter.and_then(|c| c.get_verses().find(|v| v.number == start.1));
+        let reference = verse.map(|_| Reference {
+            book: book.to_string(),
+            chapter: start.0,
+            verses: Some(match end.0 == start.0 {
+                true => start.1..=end.1.max(start.1),
+                false => {
+                    start.1
+                        ..=chapter
+                            .and_then(|c| c.verses.last())
+                            .map_or(start.1, |v| v.number)
+                }
+            }),
+        });
+        let preview = match verse {
+            Some(verse) => verse.collect_string(bible.get_raw_data()),
+            None => format!("Not in {}", bible.get_translation().name),
+        };
+
+        Some(Item {
+            label: format_range(book, start, end),
+            votes: cross_reference.votes,
+            reference,
+            preview,
+            rank,
+            position: (canon::canonical_position(book), c1, v1),
+        })
+    }
+}
+
+/// The cross references of the verse that is being read.
 pub struct References {
     focused: bool,
+    cross_references: Arc<CrossReferences>,
+    bible: Arc<Bible>,
+    /// (book, chapter, verse) the items are for, in the numbering of the translation.
+    verse: Option<(String, usize, usize)>,
+    items: Vec<Item>,
+    sort: SortOrder,
+    selected: usize,
+    scrolled_offset: usize,
+    selected_reference: Option<Reference>,
 }
 
 impl References {
-    pub fn new() -> Self {
-        Self { focused: false }
+    pub fn new(cross_references: Arc<CrossReferences>, bible: Arc<Bible>) -> Self {
+        Self {
+            focused: false,
+            cross_references,
+            bible,
+            verse: None,
+            items: Vec::new(),
+            sort: SortOrder::Relevance,
+            selected: 0,
+            scrolled_offset: 0,
+            selected_reference: None,
+        }
+    }
+
+    /// Uses another translation for the previews and the references that are jumped to.
+    pub fn set_bible(&mut self, bible: Arc<Bible>) {
+        self.bible = bible;
+        self.verse = None;
+    }
+
+    /// Lists the cross references of the verse, if they are not the ones that are listed already.
+    pub fn set_verse(&mut self, book: &str, chapter: usize, verse: usize) {
+        if self
+            .verse
+            .as_ref()
+            .is_some_and(|(b, c, v)| (b.as_str(), *c, *v) == (book, chapter, verse))
+        {
+            return;
+        }
+
+        let (kjv_chapter, kjv_verse) = self
+            .bible
+            .get_versification()
+            .map_to_kjv(book, chapter, verse);
+        self.items = self
+            .cross_references
+            .get(book, kjv_chapter, kjv_verse)
+            .iter()
+            .enumerate()
+            .filter_map(|(rank, r)| Item::new(&self.bible, rank, r))
+            .collect();
+        self.sort_items();
+        self.verse = Some((book.to_string(), chapter, verse));
+        self.selected = 0;
+        self.scrolled_offset = 0;
+    }
+
+    /// The reference that was selected with enter, if any.
+    pub fn take_selected(&mut self) -> Option<Reference> {
+        self.selected_reference.take()
+    }
+
+    fn sort_items(&mut self) {
+        match self.sort {
+            SortOrder::Relevance => self
+                .items
+                .sort_by_key(|item| (std::cmp::Reverse(item.votes), item.rank)),
+            SortOrder::Canon => self.items.sort_by_key(|item| item.position),
+        }
+    }
+
+    fn build_lines(item: &Item, selected: bool) -> Vec<Line<'_>> {
+        let mut heading = vec![match selected {
+            true => Span::styled(item.label.as_str(), theme().selected),
+            false => Span::raw(item.label.as_str()),
+        }];
+        if let Some(votes) = item.votes {
+            heading.push(Span::styled(format!("  {votes} votes"), theme().dim));
+        }
+        vec![
+            Line::from(heading),
+            Line::styled(format!("  {}", item.preview), theme().dim),
+        ]
+    }
+
+    fn empty_message(&self) -> String {
+        if self.cross_references.is_empty() {
+            return "No cross references, see `logos crossrefs import`".to_string();
+        }
+        match &self.verse {
+            Some((book, chapter, verse)) => format!(
+                "No cross references for {} {chapter}:{verse}",
+                canon::book_name(book)
+            ),
+            None => String::new(),
+        }
     }
 }
 
@@ -18,23 +178,70 @@ impl Component for References {
         match event {
             AppEvent::Focus => self.focused = true,
             AppEvent::Defocus => self.focused = false,
+            AppEvent::UserAction(action) if self.focused => match action {
+                UserAction::MoveDown if self.selected + 1 < self.items.len() => {
+                    self.selected += 1;
+                }
+                UserAction::MoveUp => self.selected = self.selected.saturating_sub(1),
+                UserAction::Select => {
+                    self.selected_reference = self
+                        .items
+                        .get(self.selected)
+                        .and_then(|item| item.reference.clone());
+                }
+                UserAction::Sort => {
+                    self.sort = match self.sort {
+                        SortOrder::Relevance => SortOrder::Canon,
+                        SortOrder::Canon => SortOrder::Relevance,
+                    };
+                    self.sort_items();
+                    self.selected = 0;
+                }
+                _ => {}
+            },
             _ => {}
         }
         Ok(())
     }
 
     fn render(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        let order = match self.sort {
+            SortOrder::Relevance => "ranked",
+            SortOrder::Canon => "in order",
+        };
+        let title = format!(" [3] References ({}, {order}) ", self.items.len());
         let block = Block::default()
             .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
-            .title(" [3] References ".yellow().bold())
-            .border_style(if self.focused {
-                Style::default().blue()
-            } else {
-                Style::default()
-            });
+            .border_set(border_set())
+            .title(Span::styled(title, theme().title))
+            .border_style(theme().border(self.focused));
 
+        let inner = block.inner(area);
         block.render(area, buf);
+
+        if self.items.is_empty() {
+            Line::styled(self.empty_message(), theme().dim).render(inner, buf);
+            return Ok(());
+        }
+
+        // Scrolling logic, every reference takes two rows.
+        let visible = (inner.height as usize / 2).max(1);
+        if self.selected < self.scrolled_offset {
+            self.scrolled_offset = self.selected;
+        }
+        if self.selected >= self.scrolled_offset + visible {
+            self.scrolled_offset = self.selected + 1 - visible;
+        }
+
+        let lines: Vec<Line> = self
+            .items
+            .iter()
+            .enumerate()
+            .skip(self.scrolled_offset)
+            .take(visible)
+            .flat_map(|(i, item)| Self::build_lines(item, i == self.selected && self.focused))
+            .collect();
+        Paragraph::new(lines).render(inner, buf);
         Ok(())
     }
 }
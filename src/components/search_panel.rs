@@ -0,0 +1,174 @@
+use crate::app::events::{AppEvent, UserAction};
+use crate::bible::Bible;
+use crate::components::Component;
+use crate::prelude::*;
+use crate::reference::Reference;
+use crate::search::{SearchHit, SearchIndex, SearchRequest, SearchResults};
+use ratatui::widgets::{Block, Borders};
+
+/// Characters of context shown before the first match in a snippet.
+const SNIPPET_CONTEXT: usize = 24;
+
+pub struct SearchPanel {
+    bible: Arc<Bible>,
+    // Built on the first search, it is not needed otherwise.
+    index: Option<SearchIndex>,
+    results: Option<SearchResults>,
+    selected: usize,
+    scrolled_offset: usize,
+    focused: bool,
+    open: bool,
+    selected_reference: Option<Reference>,
+}
+
+impl SearchPanel {
+    pub fn new(bible: Arc<Bible>) -> Self {
+        Self {
+            bible,
+            index: None,
+            results: None,
+            selected: 0,
+            scrolled_offset: 0,
+            focused: false,
+            open: false,
+            selected_reference: None,
+        }
+    }
+
+    pub fn search(&mut self, input: &str) -> Result<()> {
+        let request = SearchRequest::parse(input, &self.bible)?;
+        let index = self
+            .index
+            .get_or_insert_with(|| SearchIndex::build(&self.bible));
+
+        self.results = Some(index.search(&self.bible, &request));
+        self.selected = 0;
+        self.scrolled_offset = 0;
+        self.open = true;
+        Ok(())
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// What was searched for, if the panel is open.
+    pub fn query(&self) -> Option<&str> {
+        let results = self.results.as_ref().filter(|_| self.open)?;
+        Some(&results.request.input)
+    }
+
+    /// The reference of the hit that was selected with enter, if any.
+    pub fn take_selected(&mut self) -> Option<Reference> {
+        self.selected_reference.take()
+    }
+
+    fn hits(&self) -> &[SearchHit] {
+        self.results.as_ref().map_or(&[], |r| &r.hits)
+    }
+
+    fn build_line(hit: &SearchHit, selected: bool) -> Line<'_> {
+        let reference = format!("{:<22}", hit.reference.to_string());
+        let mut spans = vec![match selected {
+            true => Span::styled(reference, theme().selected),
+            false => Span::styled(reference, theme().dim),
+        }];
+
+        // Start a bit before the first match, so that it is visible on narrow panels.
+        let text = hit.text.as_str();
+        let mut cursor = match hit.highlights.first() {
+            Some(m) if m.start > SNIPPET_CONTEXT => {
+                let start = text[..m.start - SNIPPET_CONTEXT]
+                    .rfind(' ')
+                    .map_or(0, |i| i + 1);
+                spans.push(Span::raw(icon(Icon::Ellipsis)));
+                start
+            }
+            _ => 0,
+        };
+
+        for m in &hit.highlights {
+            spans.push(Span::raw(&text[cursor..m.start]));
+            spans.push(Span::styled(&text[m.start..m.end], theme().search_hit));
+            cursor = m.end;
+        }
+        spans.push(Span::raw(&text[cursor..]));
+
+        Line::from(spans)
+    }
+}
+
+impl Component for SearchPanel {
+    fn update(&mut self, event: &AppEvent) -> Result<()> {
+        match event {
+            AppEvent::Focus => self.focused = true,
+            AppEvent::Defocus => self.focused = false,
+            AppEvent::UserAction(action) if self.focused => match action {
+                UserAction::MoveDown if self.selected + 1 < self.hits().len() => {
+                    self.selected += 1;
+                }
+                UserAction::MoveUp => self.selected = self.selected.saturating_sub(1),
+                UserAction::Select => {
+                    self.selected_reference =
+                        self.hits().get(self.selected).map(|h| h.reference.clone());
+                }
+                UserAction::Close => self.open = false,
+                _ => {}
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        let title = match &self.results {
+            Some(r) => format!(
+                " [5] Search: {} in {} ({} verses, {} matches) ",
+                r.request.input,
+                r.request.scope.name(),
+                r.hits.len(),
+                r.matches
+            ),
+            None => " [5] Search ".to_string(),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(border_set())
+            .title(Span::styled(title, theme().title))
+            .border_style(theme().border(self.focused));
+
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        // Scrolling logic.
+        let visible = inner.height as usize;
+        if self.selected < self.scrolled_offset {
+            self.scrolled_offset = self.selected;
+        }
+        if visible > 0 && self.selected >= self.scrolled_offset + visible {
+            self.scrolled_offset = self.selected + 1 - visible;
+        }
+
+        let hits = self.hits();
+        if hits.is_empty() {
+            Line::from(Span::styled("No matches", theme().dim)).render(inner, buf);
+            return Ok(());
+        }
+
+        for (row, (i, hit)) in hits
+            .iter()
+            .enumerate()
+            .skip(self.scrolled_offset)
+            .take(visible)
+            .enumerate()
+        {
+            let line = Self::build_line(hit, i == self.selected);
+            buf.set_line(inner.x, inner.y + row as u16, &line, inner.width);
+        }
+        Ok(())
+    }
+}
@@ -9,7 +9,24 @@ use tui_big_text::{BigText, PixelSize};
 
 // TODO: Revisit and rename components here.
 // Should probably move menu out.
-pub struct SplashScreen;
+pub struct SplashScreen {
+    /// Name of the loaded translation.
+    pub translation: Option<String>,
+    /// Where reading stopped the last time, e.g. "John 3:16 (KJV)".
+    pub continue_reading: Option<String>,
+    /// Shown below the menu, e.g. when a translation failed to load.
+    pub error: Option<String>,
+}
+
+impl SplashScreen {
+    pub fn new() -> Self {
+        Self {
+            translation: None,
+            continue_reading: None,
+            error: None,
+        }
+    }
+}
 
 impl Component for SplashScreen {
     fn update(&mut self, _event: &AppEvent) -> Result<()> {
@@ -20,19 +37,43 @@ impl Component for SplashScreen {
         let big_text = BigText::builder()
             .pixel_size(PixelSize::Full)
             .centered()
-            .lines(vec!["LOGOS".yellow().into()])
+            .lines(vec![Span::styled("LOGOS", theme().title).into()])
             .build();
 
         let version = Paragraph::new(format!("v{VERSION}"))
             .alignment(Alignment::Center)
-            .italic()
-            .dark_gray();
+            .style(theme().dim)
+            .italic();
 
-        let menu = Paragraph::new(Text::from(vec![
-            Line::from(vec!["[r]".cyan().bold(), " Reader".white()]),
-            Line::from(vec!["[q]".red().bold(), " Quit  ".white()]),
-        ]))
-        .alignment(Alignment::Center);
+        let translation = self.translation.as_deref().unwrap_or(icon(Icon::Ellipsis));
+        let mut menu = Vec::new();
+        if let Some(location) = &self.continue_reading {
+            menu.push(Line::from(vec![
+                Span::styled("[enter]", theme().selected),
+                Span::styled(" Continue reading ", theme().text),
+                Span::styled(location.as_str(), theme().dim),
+            ]));
+        }
+        menu.extend([
+            Line::from(vec![
+                Span::styled("[r]", theme().selected),
+                Span::styled(" Reader", theme().text),
+            ]),
+            Line::from(vec![
+                Span::styled("[t]", theme().selected),
+                Span::styled(" Translation ", theme().text),
+                Span::styled(format!("({translation})"), theme().dim),
+            ]),
+            Line::from(vec![
+                Span::styled("[q]", theme().hint),
+                Span::styled(" Quit  ", theme().text),
+            ]),
+        ]);
+        if let Some(error) = &self.error {
+            menu.push(Line::raw(""));
+            menu.push(Line::styled(error.as_str(), theme().error));
+        }
+        let menu = Paragraph::new(Text::from(menu)).alignment(Alignment::Center);
 
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -41,12 +82,21 @@ impl Component for SplashScreen {
                 Constraint::Length(8),
                 Constraint::Length(1),
                 Constraint::Length(1),
-                Constraint::Length(2),
+                Constraint::Length(6),
                 Constraint::Fill(1),
             ])
             .split(rect);
 
         big_text.render(chunks[1], buf);
+        // The big text is always drawn with full blocks.
+        let pixel = icon(Icon::Pixel);
+        if pixel != "█" {
+            for position in chunks[1].positions() {
+                if buf[position].symbol() == "█" {
+                    buf[position].set_symbol(pixel);
+                }
+            }
+        }
         version.render(chunks[2], buf);
         menu.render(chunks[4], buf);
         Ok(())
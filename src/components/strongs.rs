@@ -1,15 +1,128 @@
-use crate::app::events::AppEvent;
+use crate::app::events::{AppEvent, UserAction};
+use crate::bible::WordTag;
 use crate::components::Component;
+use crate::components::book_column::Word;
+use crate::lexicon::{Lexicon, LexiconEntry};
 use crate::prelude::*;
-use ratatui::widgets::{Block, BorderType, Borders};
+use ratatui::widgets::{Block, Borders, Wrap};
 
+/// The lexicon entries of the word under the word cursor of the reader.
 pub struct Strongs {
     focused: bool,
+    lexicon: Arc<Lexicon>,
+    /// The text and tag of the word, `None` without a word cursor.
+    word: Option<(String, Option<WordTag>)>,
+    /// Entries of the Strong's numbers of the word, looked up when the word changes.
+    entries: Vec<(String, Option<LexiconEntry>)>,
+    scrolled_offset: usize,
 }
 
 impl Strongs {
-    pub fn new() -> Self {
-        Self { focused: false }
+    pub fn new(lexicon: Arc<Lexicon>) -> Self {
+        Self {
+            focused: false,
+            lexicon,
+            word: None,
+            entries: Vec::new(),
+            scrolled_offset: 0,
+        }
+    }
+
+    /// Shows the entries of the word, if it is not the one that is shown already.
+    pub fn set_word(&mut self, word: Option<Word>) {
+        let word = word.map(|w| {
+            let text: String = w.parts.iter().map(|(text, _)| text.as_str()).collect();
+            (text, w.tag)
+        });
+        if word == self.word {
+            return;
+        }
+
+        let tag = word.as_ref().and_then(|(_, tag)| tag.as_ref());
+        self.entries = tag
+            .into_iter()
+            .flat_map(|tag| tag.strongs())
+            .map(|number| {
+                let entry = self.lexicon.get(&number);
+                (number, entry)
+            })
+            .collect();
+        self.word = word;
+        self.scrolled_offset = 0;
+    }
+
+    fn build_lines(&self) -> Vec<Line<'_>> {
+        let Some((text, tag)) = &self.word else {
+            return vec![Line::styled(
+                "Move the word cursor to a word to look it up",
+                theme().dim,
+            )];
+        };
+
+        let mut lines = vec![Line::from(vec![
+            Span::styled(text.as_str(), theme().selected),
+            Span::raw(" "),
+            Span::styled(
+                tag.as_ref()
+                    .and_then(|t| t.morph.as_deref())
+                    .unwrap_or_default(),
+                theme().dim,
+            ),
+        ])];
+        let Some(tag) = tag else {
+            lines.push(Line::styled("The word is not tagged", theme().dim));
+            return lines;
+        };
+        if self.entries.is_empty() {
+            lines.push(Line::styled(format!("Lemma: {}", tag.lemma), theme().text));
+        }
+
+        for (number, entry) in &self.entries {
+            lines.push(Line::raw(""));
+            let Some(entry) = entry else {
+                lines.push(Line::from(vec![
+                    Span::styled(number.as_str(), theme().title),
+                    Span::styled(
+                        " is not in the lexicon, see `logos lexicon import`",
+                        theme().dim,
+                    ),
+                ]));
+                continue;
+            };
+
+            let mut heading = vec![Span::styled(number.as_str(), theme().title)];
+            if !entry.lemma.is_empty() {
+                heading.push(Span::raw(" "));
+                heading.push(Span::styled(entry.lemma.as_str(), theme().text));
+            }
+            if !entry.transliteration.is_empty() {
+                heading.push(Span::raw(" "));
+                heading.push(Span::styled(
+                    entry.transliteration.as_str(),
+                    theme().selected,
+                ));
+            }
+            if !entry.pronunciation.is_empty() {
+                heading.push(Span::styled(
+                    format!(" ({})", entry.pronunciation),
+                    theme().dim,
+                ));
+            }
+            lines.push(Line::from(heading));
+            lines.extend(
+                entry
+                    .definition
+                    .lines()
+                    .map(|line| Line::styled(line, theme().text)),
+            );
+            if !entry.kjv_usage.is_empty() {
+                lines.push(Line::from(vec![
+                    Span::styled("KJV: ", theme().dim),
+                    Span::styled(entry.kjv_usage.as_str(), theme().text),
+                ]));
+            }
+        }
+        lines
     }
 }
 
@@ -18,6 +131,11 @@ impl Component for Strongs {
         match event {
             AppEvent::Focus => self.focused = true,
             AppEvent::Defocus => self.focused = false,
+            AppEvent::UserAction(action) if self.focused => match action {
+                UserAction::MoveDown => self.scrolled_offset += 1,
+                UserAction::MoveUp => self.scrolled_offset = self.scrolled_offset.saturating_sub(1),
+                _ => {}
+            },
             _ => {}
         }
         Ok(())
@@ -26,15 +144,20 @@ impl Component for Strongs {
     fn render(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
         let block = Block::default()
             .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
-            .title(" [4] Strong's ".yellow().bold())
-            .border_style(if self.focused {
-                Style::default().blue()
-            } else {
-                Style::default()
-            });
+            .border_set(border_set())
+            .title(Span::styled(" [4] Strong's ", theme().title))
+            .border_style(theme().border(self.focused));
 
+        let inner = block.inner(area);
         block.render(area, buf);
+
+        let lines = self.build_lines();
+        let offset = self.scrolled_offset.min(lines.len().saturating_sub(1));
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .scroll((offset as u16, 0))
+            .render(inner, buf);
+        self.scrolled_offset = offset;
         Ok(())
     }
 }
@@ -0,0 +1,138 @@
+use crate::app::events::{AppEvent, UserAction};
+use crate::components::Component;
+use crate::prelude::*;
+use crate::translations::{TranslationInfo, TranslationRegistry};
+use ratatui::widgets::{Block, Borders, Clear};
+
+const MAX_WIDTH: u16 = 100;
+
+/// Popup listing the installed translations.  It is modal, so while it is open it should get the
+/// user actions instead of the windows behind it.
+pub struct TranslationPicker {
+    translations: Arc<TranslationRegistry>,
+    /// Names of the translations that are loaded.
+    loaded: Vec<String>,
+    selected: usize,
+    open: bool,
+    picked: Option<TranslationInfo>,
+}
+
+impl TranslationPicker {
+    pub fn new(translations: Arc<TranslationRegistry>) -> Self {
+        Self {
+            translations,
+            loaded: Vec::new(),
+            selected: 0,
+            open: false,
+            picked: None,
+        }
+    }
+
+    /// Opens the picker with the `loaded` translations marked, the first one is selected.
+    pub fn open(&mut self, loaded: &[&str]) {
+        self.loaded = loaded.iter().map(|name| name.to_string()).collect();
+        self.selected = self
+            .translations
+            .iter()
+            .position(|t| loaded.first() == Some(&t.name.as_str()))
+            .unwrap_or(0);
+        self.open = true;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// The translation that was picked with enter, if any.
+    pub fn take_picked(&mut self) -> Option<TranslationInfo> {
+        self.picked.take()
+    }
+
+    fn build_line(&self, info: &TranslationInfo, selected: bool, width: usize) -> Line<'_> {
+        let marker = match self.loaded.contains(&info.name) {
+            true => icon(Icon::Loaded),
+            false => " ",
+        };
+        let name = format!("{marker} {:<8}", info.name);
+        let details = format!(" {} ({})", info.language, info.rights);
+        let title_width = width.saturating_sub(name.chars().count() + details.chars().count());
+        let title: String = info.title.chars().take(title_width).collect();
+
+        let style = match selected {
+            true => theme().selected,
+            false => Style::default(),
+        };
+        Line::from(vec![
+            Span::styled(name, theme().title.patch(style)),
+            Span::styled(format!("{title:<title_width$}"), style),
+            Span::styled(details, theme().dim),
+        ])
+    }
+}
+
+impl Component for TranslationPicker {
+    fn update(&mut self, event: &AppEvent) -> Result<()> {
+        match event {
+            AppEvent::UserAction(action) if self.open => match action {
+                UserAction::MoveDown if self.selected + 1 < self.translations.len() => {
+                    self.selected += 1;
+                }
+                UserAction::MoveUp => self.selected = self.selected.saturating_sub(1),
+                UserAction::Select => {
+                    self.picked = self.translations.iter().nth(self.selected).cloned();
+                    self.open = false;
+                }
+                UserAction::Close | UserAction::OpenTranslations => self.open = false,
+                _ => {}
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) -> Result<()> {
+        if !self.open {
+            return Ok(());
+        }
+
+        // Centered popup that fits the list.
+        let width = area.width.min(MAX_WIDTH);
+        let height = area.height.min(self.translations.len().max(1) as u16 + 2);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_set(border_set())
+            .title(Span::styled(" Translations ", theme().title))
+            .border_style(theme().border(true));
+        let inner = block.inner(popup);
+        Clear.render(popup, buf);
+        block.render(popup, buf);
+
+        if self.translations.is_empty() {
+            Line::from(Span::styled("No translations installed", theme().dim)).render(inner, buf);
+            return Ok(());
+        }
+
+        // Keep the selection in view.
+        let visible = inner.height as usize;
+        let offset = (self.selected + 1).saturating_sub(visible);
+        for (row, (i, info)) in self
+            .translations
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(visible)
+            .enumerate()
+        {
+            let line = self.build_line(info, i == self.selected, inner.width as usize);
+            buf.set_line(inner.x, inner.y + row as u16, &line, inner.width);
+        }
+        Ok(())
+    }
+}
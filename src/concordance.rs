@@ -0,0 +1,423 @@
+use crate::bible::{Bible, Inline, VerseView, strongs_number, text_at};
+use crate::filesystem::write_atomic;
+use crate::prelude::*;
+use crate::reference::Reference;
+use crate::search::tokenize;
+use serde::{Deserialize, Serialize};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::Range;
+
+/// Version of the cached index, bumped when the format or the way it is built changes.
+const CONCORDANCE_VERSION: u32 = 1;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// What a concordance is of.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    /// Lowercase word, as it is tokenized for search.
+    Word(String),
+    /// Normalized Strong's number of the tagged words, e.g. "H7225".
+    Strongs(String),
+}
+
+impl Term {
+    /// Parses a single word or a Strong's number like "G3056".
+    pub fn parse(input: &str) -> Result<Term> {
+        let input = input.trim();
+        let is_number = input.len() > 1
+            && input.starts_with(['H', 'h', 'G', 'g'])
+            && input[1..].starts_with(|c: char| c.is_ascii_digit());
+        if is_number && let Some(number) = strongs_number(input) {
+            return Ok(Term::Strongs(number));
+        }
+
+        let mut words = tokenize(input).map(|t| t.word);
+        match (words.next(), words.next()) {
+            (Some(word), None) => Ok(Term::Word(word)),
+            _ => Err(Error::InvalidQuery(format!(
+                "{input:?} is not a single word or Strong's number"
+            ))),
+        }
+    }
+
+    /// The key of the term in the index, Strong's numbers are upper case unlike words.
+    pub fn key(&self) -> &str {
+        match self {
+            Term::Word(word) | Term::Strongs(word) => word,
+        }
+    }
+}
+
+/// An occurrence of the term, with the verse as context.
+pub struct ConcordanceHit {
+    pub reference: Reference,
+    pub text: String,
+    /// Byte range of the occurrence in `text`.
+    pub range: Range<usize>,
+}
+
+pub struct ConcordanceResults {
+    pub term: Term,
+    /// In the order of the bible.
+    pub hits: Vec<ConcordanceHit>,
+    /// (OSIS ID, occurrences) of the books with hits, in the order of the bible.
+    pub books: Vec<(String, usize)>,
+    /// Number of verses with hits.
+    pub verses: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ConcordanceFile {
+    version: u32,
+    /// Hash of the text of the bible, the index is rebuilt when it changes.
+    source_hash: u64,
+    verses: usize,
+    occurrences: HashMap<String, Vec<u32>>,
+}
+
+/// Inverted index of every occurrence of the words and Strong's numbers of a bible.
+pub struct Concordance {
+    /// (book, chapter, verse) indices into the bible index, verse ids are indices into this.
+    verses: Vec<(usize, usize, usize)>,
+    /// Word or Strong's number -> ids of the verses, once for every occurrence.
+    occurrences: HashMap<String, Vec<u32>>,
+}
+
+impl Concordance {
+    /// Loads the index of the bible from the cache, or builds and caches it.
+    pub fn load(bible: &Bible) -> Concordance {
+        let verses = verse_locations(bible);
+        let source_hash = source_hash(bible);
+        let path = cache_file(bible);
+
+        if let Some(path) = &path {
+            match read_cache(path, source_hash, verses.len()) {
+                Ok(Some(occurrences)) => {
+                    return Concordance {
+                        verses,
+                        occurrences,
+                    };
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Failed to read the concordance cache {path:?}: {e}"),
+            }
+        }
+
+        let concordance = Self::build(bible, verses);
+        if let Some(path) = &path
+            && let Err(e) = concordance.save(path, source_hash)
+        {
+            warn!("Failed to cache the concordance in {path:?}: {e}");
+        }
+        concordance
+    }
+
+    fn build(bible: &Bible, verses: Vec<(usize, usize, usize)>) -> Concordance {
+        info!("Building concordance");
+        let start = Instant::now();
+
+        let raw = bible.get_raw_data();
+        let mut occurrences: HashMap<String, Vec<u32>> = HashMap::new();
+        for (id, location) in verses.iter().enumerate() {
+            let verse = resolve_verse(bible, *location);
+            for token in tokenize(&verse.collect_string(raw)) {
+                occurrences.entry(token.word).or_default().push(id as u32);
+            }
+            for inline in &verse.inlines {
+                if let Inline::WordStart(tag) = inline {
+                    for number in tag.strongs() {
+                        occurrences.entry(number).or_default().push(id as u32);
+                    }
+                }
+            }
+        }
+
+        info!(
+            "Built concordance of {} words in {:?}",
+            occurrences.len(),
+            start.elapsed()
+        );
+        Concordance {
+            verses,
+            occurrences,
+        }
+    }
+
+    fn save(&self, path: &Path, source_hash: u64) -> Result<()> {
+        let file = ConcordanceFile {
+            version: CONCORDANCE_VERSION,
+            source_hash,
+            verses: self.verses.len(),
+            occurrences: self.occurrences.clone(),
+        };
+        write_atomic(path, &serde_json::to_string(&file)?)?;
+        debug!("Cached concordance in {path:?}");
+        Ok(())
+    }
+
+    /// Every occurrence of the term, with the counts per book.
+    pub fn lookup(&self, bible: &Bible, term: Term) -> ConcordanceResults {
+        let start = Instant::now();
+        let mut ids: Vec<u32> = self
+            .occurrences
+            .get(term.key())
+            .cloned()
+            .unwrap_or_default();
+        ids.dedup();
+
+        let raw = bible.get_raw_data();
+        let mut hits = Vec::new();
+        let mut books: Vec<(String, usize)> = Vec::new();
+        for id in &ids {
+            let location = self.verses[*id as usize];
+            let (book, _) = bible
+                .get_book_at(location.0)
+                .expect("Index was built from this bible");
+            let verse = resolve_verse(bible, location);
+            let (text, ranges) = match &term {
+                Term::Word(word) => {
+                    let text = verse.collect_string(raw);
+                    let ranges = tokenize(&text)
+                        .filter(|t| &t.word == word)
+                        .map(|t| t.range)
+                        .collect();
+                    (text, ranges)
+                }
+                Term::Strongs(number) => tagged_text(verse, raw, number),
+            };
+
+            match books.last_mut() {
+                Some((last, count)) if last == book => *count += ranges.len(),
+                _ => books.push((book.to_string(), ranges.len())),
+            }
+            let reference = Reference {
+                book: book.to_string(),
+                chapter: resolve_chapter(bible, location),
+                verses: Some(verse.number..=verse.number),
+            };
+            hits.extend(ranges.into_iter().map(|range| ConcordanceHit {
+                reference: reference.clone(),
+                text: text.clone(),
+                range,
+            }));
+        }
+
+        debug!(
+            "Concordance of {term:?} found {} occurrences in {:?}",
+            hits.len(),
+            start.elapsed()
+        );
+        ConcordanceResults {
+            term,
+            hits,
+            books,
+            verses: ids.len(),
+        }
+    }
+}
+
+fn verse_locations(bible: &Bible) -> Vec<(usize, usize, usize)> {
+    let mut verses = Vec::new();
+    for (b, (_, book)) in bible.iter_books().enumerate() {
+        for (c, chapter) in book.chapters.iter().enumerate() {
+            verses.extend((0..chapter.verses.len()).map(|v| (b, c, v)));
+        }
+    }
+    verses
+}
+
+fn resolve_verse(bible: &Bible, (book, chapter, verse): (usize, usize, usize)) -> &VerseView {
+    let (_, book) = bible
+        .get_book_at(book)
+        .expect("Index was built from this bible");
+    &book.chapters[chapter].verses[verse]
+}
+
+fn resolve_chapter(bible: &Bible, (book, chapter, _): (usize, usize, usize)) -> usize {
+    let (_, book) = bible
+        .get_book_at(book)
+        .expect("Index was built from this bible");
+    book.chapters[chapter].number
+}
+
+/// The text of the verse like `VerseView::collect_string`, with the byte ranges of the words
+/// that are tagged with the Strong's number.
+fn tagged_text(verse: &VerseView, raw: &str, number: &str) -> (String, Vec<Range<usize>>) {
+    let mut text = String::new();
+    let mut ranges = Vec::new();
+    // Whether the open words are tagged with the number.
+    let mut words: Vec<bool> = Vec::new();
+    let mut start = None;
+    for inline in &verse.inlines {
+        match inline {
+            Inline::Text(s, e) | Inline::Added(s, e) => {
+                for c in text_at(raw, *s, *e).chars() {
+                    match c.is_whitespace() {
+                        true if text.is_empty() || text.ends_with(' ') => {}
+                        true => text.push(' '),
+                        false => {
+                            if start.is_none() && words.iter().any(|tagged| *tagged) {
+                                start = Some(text.len());
+                            }
+                            text.push(c);
+                        }
+                    }
+                }
+            }
+            Inline::WordStart(tag) => words.push(tag.strongs().any(|n| n == number)),
+            Inline::WordEnd => {
+                words.pop();
+                if !words.iter().any(|tagged| *tagged)
+                    && let Some(start) = start.take()
+                {
+                    ranges.push(start..text.trim_end().len());
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = start {
+        ranges.push(start..text.trim_end().len());
+    }
+    let len = text.trim_end().len();
+    text.truncate(len);
+    (text, ranges)
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Where the index of the bible is cached, by the name of the translation.
+fn cache_file(bible: &Bible) -> Option<PathBuf> {
+    let name: String = bible
+        .get_translation()
+        .name
+        .chars()
+        .map(|c| match c.is_alphanumeric() || c == '-' {
+            true => c,
+            false => '_',
+        })
+        .collect();
+    concordance_dir().map(|dir| dir.join(format!("{name}.json")))
+}
+
+fn source_hash(bible: &Bible) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bible.get_raw_data().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The cached occurrences, `None` if there are none or they are of another version of the text.
+fn read_cache(
+    path: &Path,
+    source_hash: u64,
+    verses: usize,
+) -> Result<Option<HashMap<String, Vec<u32>>>> {
+    let start = Instant::now();
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let file: ConcordanceFile = serde_json::from_str(&contents)?;
+    if file.version != CONCORDANCE_VERSION
+        || file.source_hash != source_hash
+        || file.verses != verses
+    {
+        info!("The concordance cache {path:?} is out of date");
+        return Ok(None);
+    }
+    info!("Loaded concordance from {path:?} in {:?}", start.elapsed());
+    Ok(Some(file.occurrences))
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bible::tests::bible_from;
+
+    fn bible() -> Bible {
+        bible_from(
+            "concordance.xml",
+            r#"<osis><osisText>
+<div type="book" osisID="Gen"><chapter osisID="Gen.1">
+<verse osisID="Gen.1.1"><w lemma="strong:H7225">In the beginning</w> <w lemma="strong:H430">God</w> created.</verse>
+<verse osisID="Gen.1.2">And the Spirit of <w lemma="strong:H430">God</w> moved, God said.</verse>
+</chapter></div>
+<div type="book" osisID="John"><chapter osisID="John.1">
+<verse osisID="John.1.1">In the beginning was the Word, and the Word was with God.</verse>
+</chapter><chapter osisID="John.3">
+<verse osisID="John.3.16">For God so loved the world.</verse>
+</chapter></div>
+</osisText></osis>"#,
+        )
+    }
+
+    fn concordance(bible: &Bible) -> Concordance {
+        Concordance::build(bible, verse_locations(bible))
+    }
+
+    #[test]
+    fn counts_the_occurrences_per_book() {
+        let bible = bible();
+        let concordance = concordance(&bible);
+
+        let results = concordance.lookup(&bible, Term::parse("God").unwrap());
+        assert_eq!(
+            results.books,
+            [("Gen".to_string(), 3), ("John".to_string(), 2)]
+        );
+        assert_eq!(results.verses, 4);
+        let locations: Vec<String> = results
+            .hits
+            .iter()
+            .map(|hit| format!("{} {}", hit.reference, &hit.text[hit.range.clone()]))
+            .collect();
+        assert_eq!(
+            locations,
+            [
+                "Genesis 1:1 God",
+                "Genesis 1:2 God",
+                "Genesis 1:2 God",
+                "John 1:1 God",
+                "John 3:16 God"
+            ]
+        );
+
+        // Only the tagged words have the Strong's number.
+        let results = concordance.lookup(&bible, Term::parse("h0430").unwrap());
+        assert_eq!(results.books, [("Gen".to_string(), 2)]);
+        let results = concordance.lookup(&bible, Term::parse("H7225").unwrap());
+        let hit = &results.hits[0];
+        assert_eq!(&hit.text[hit.range.clone()], "In the beginning");
+        assert!(
+            concordance
+                .lookup(&bible, Term::parse("unknown").unwrap())
+                .hits
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn caches_the_index() {
+        let bible = bible();
+        let concordance = concordance(&bible);
+        let path =
+            std::env::temp_dir().join(format!("logos-{}-concordance.json", std::process::id()));
+        let hash = source_hash(&bible);
+        concordance.save(&path, hash).unwrap();
+
+        let cached = read_cache(&path, hash, concordance.verses.len());
+        // The cache is out of date when the text or its verses change.
+        let changed = read_cache(&path, hash + 1, concordance.verses.len());
+        let moved = read_cache(&path, hash, concordance.verses.len() + 1);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(cached.unwrap(), Some(concordance.occurrences));
+        assert_eq!(changed.unwrap(), None);
+        assert_eq!(moved.unwrap(), None);
+        let missing = read_cache(&path, hash, 4).unwrap();
+        assert_eq!(missing, None);
+    }
+}
@@ -1,18 +1,73 @@
 use crate::prelude::*;
-use std::{sync::OnceLock, time::Duration};
+use std::time::Duration;
 
-type Translations = HashMap<&'static str, std::path::PathBuf>;
+/// Where translations are installed, `$XDG_DATA_HOME/logos/translations` on Linux.
+pub fn translations_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("logos").join("translations"))
+}
 
-// TODO: Correctly locate assets.
-static TRANSLATIONS: OnceLock<Translations> = OnceLock::new();
+/// Where imported Strong's dictionaries are kept, next to the translations.
+pub fn lexicon_file() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("logos").join("lexicon.json"))
+}
+
+/// Where the imported cross reference dataset is kept, next to the translations.
+pub fn cross_references_file() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("logos").join("cross_references.json"))
+}
+
+/// Where the notes of the user are kept, in the local data directory like the bookmarks.
+pub fn notes_file() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("logos").join("notes.json"))
+}
+
+/// Where the highlights of the user are kept, in the local data directory like the bookmarks.
+pub fn highlights_file() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("logos").join("highlights.json"))
+}
+
+/// Where the concordance of each translation is cached, `$XDG_CACHE_HOME/logos/concordance` on
+/// Linux.
+pub fn concordance_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("logos").join("concordance"))
+}
 
-pub fn get_translations() -> &'static Translations {
-    TRANSLATIONS
-        .get_or_init(|| HashMap::from([("KJV", PathBuf::from("assets/eng-kjv.osis.xml.xz"))]))
+/// The TOML config file, `$XDG_CONFIG_HOME/logos/config.toml` on Linux.
+pub fn config_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("logos").join("config.toml"))
 }
 
+/// Where bookmarks are saved, in the local data directory like `logos.log`.
+pub fn bookmarks_file() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("logos").join("bookmarks.json"))
+}
+
+/// Where the session is saved when quitting, in the local data directory like `logos.log`.
+pub fn session_file() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("logos").join("session.json"))
+}
+
+// TODO: Correctly locate assets.
+pub const BUNDLED_TRANSLATIONS_DIR: &str = "assets";
+/// Used when the config file does not set `default_translation`.
+pub const DEFAULT_TRANSLATION: &str = "KJV";
+
+/// Environment variable that overrides the detected icon set, see `IconSet::detect`.
+pub const ICONS_ENV: &str = "LOGOS_ICONS";
+
+/// Environment variable with a directory of SWORD modules, like for the SWORD library.
+pub const SWORD_PATH_ENV: &str = "SWORD_PATH";
+
+/// Environment variable that selects where yanked text goes, see `ClipboardSink`.
+pub const CLIPBOARD_ENV: &str = "LOGOS_CLIPBOARD";
+
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 // TODO: Get from screen rate or config file.
 pub const TARGET_FRAMERATE: f64 = 120.0;
 pub const TARGET_FRAMETIME: Duration = Duration::from_micros((1000000.0 / TARGET_FRAMERATE) as u64);
+
+/// Narrowest a reader column is allowed to get before dropping a column.
+pub const READER_MIN_COLUMN_WIDTH: usize = 50;
+pub const READER_MAX_COLUMNS: usize = 4;
+pub const READER_COLUMN_GAP: usize = 4;
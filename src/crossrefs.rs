@@ -0,0 +1,361 @@
+use crate::canon;
+use crate::filesystem::{read_text, write_atomic};
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Version of the cross reference file, bumped when the format changes.
+const CROSS_REFERENCES_VERSION: u32 = 1;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A passage as (book, (chapter, verse), (chapter, verse)).
+pub type Passage<'a> = (&'a str, (usize, usize), (usize, usize));
+
+/// A verse or passage that is related to a verse.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrossReference {
+    /// OSIS reference in the numbering of the KJV, e.g. "John.1.1" or "John.1.1-John.1.3".
+    pub target: String,
+    /// Votes of the users of OpenBible.info, `None` for datasets without them.
+    pub votes: Option<i32>,
+}
+
+impl CrossReference {
+    /// The (book, (chapter, verse), (chapter, verse)) of the target, see `osis_range`.
+    pub fn range(&self) -> Option<Passage<'_>> {
+        osis_range(&self.target)
+    }
+}
+
+/// "John.1.1-John.1.3" -> ("John", (1, 1), (1, 3)).  A range that ends in another book ends with
+/// the first verse.
+pub fn osis_range(id: &str) -> Option<Passage<'_>> {
+    let (start, end) = id.split_once('-').unwrap_or((id, id));
+    let (book, chapter, verse) = osis_verse(start)?;
+    let end = match osis_verse(end) {
+        Some((end_book, c, v)) if end_book == book && (c, v) >= (chapter, verse) => (c, v),
+        _ => (chapter, verse),
+    };
+    Some((book, (chapter, verse), end))
+}
+
+/// "John.3.16" -> ("John", 3, 16).
+pub fn osis_verse(id: &str) -> Option<(&str, usize, usize)> {
+    let mut parts = id.trim().split('.');
+    let book = parts.next()?;
+    let chapter = parts.next()?.parse().ok()?;
+    let verse = parts.next()?.parse().ok()?;
+    parts.next().is_none().then_some((book, chapter, verse))
+}
+
+#[derive(Serialize, Deserialize)]
+struct CrossReferencesFile {
+    version: u32,
+    /// Where the dataset was imported from.
+    source: String,
+    references: HashMap<String, Vec<CrossReference>>,
+}
+
+/// The cross references of an imported dataset, keyed by the OSIS ID of the verse in the
+/// numbering of the KJV, e.g. "Gen.1.1".
+pub struct CrossReferences {
+    /// Where the references are saved, `None` keeps them in memory only.
+    path: Option<PathBuf>,
+    source: String,
+    references: HashMap<String, Vec<CrossReference>>,
+}
+
+impl CrossReferences {
+    /// Loads the imported dataset from the data directory.
+    pub fn load() -> Result<CrossReferences> {
+        match cross_references_file() {
+            Some(path) => Self::from_file(path),
+            None => {
+                warn!("No data directory, imported cross references will not be saved");
+                Ok(Self::in_memory())
+            }
+        }
+    }
+
+    /// Cross references that are not saved, used when the file can not be read so it does not
+    /// get overwritten.
+    pub fn in_memory() -> CrossReferences {
+        CrossReferences {
+            path: None,
+            source: String::new(),
+            references: HashMap::new(),
+        }
+    }
+
+    /// Loads the dataset from `path`, there is none when it does not exist yet.
+    pub fn from_file(path: PathBuf) -> Result<CrossReferences> {
+        let start = Instant::now();
+        let (source, references) = match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                let file: CrossReferencesFile = serde_json::from_str(&contents)?;
+                if file.version > CROSS_REFERENCES_VERSION {
+                    let name = path.display().to_string();
+                    return Err(Error::UnsupportedVersion(name, file.version));
+                }
+                (file.source, file.references)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (String::new(), HashMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+        info!(
+            "Loaded cross references of {} verses from {path:?} in {:?}",
+            references.len(),
+            start.elapsed()
+        );
+
+        Ok(CrossReferences {
+            path: Some(path),
+            source,
+            references,
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let file = CrossReferencesFile {
+            version: CROSS_REFERENCES_VERSION,
+            source: self.source.clone(),
+            references: self.references.clone(),
+        };
+        write_atomic(path, &serde_json::to_string(&file)?)?;
+        debug!("Saved cross references to {path:?}");
+        Ok(())
+    }
+
+    /// Reads a dataset and saves it, replacing the one that was imported before.  Returns the
+    /// number of cross references that were read.
+    pub fn import(&mut self, path: &Path) -> Result<usize> {
+        let references = read_dataset(&read_text(path)?);
+        let count: usize = references.values().map(Vec::len).sum();
+        if count == 0 {
+            return Err(Error::UnsupportedFormat(format!(
+                "{path:?} does not have any cross references"
+            )));
+        }
+        self.references = references;
+        self.source = path.display().to_string();
+        self.save()?;
+        info!("Imported {count} cross references from {path:?}");
+        Ok(count)
+    }
+
+    /// The cross references of a verse in the numbering of the KJV, in the order of the dataset.
+    pub fn get(&self, book: &str, chapter: usize, verse: usize) -> &[CrossReference] {
+        self.references
+            .get(&format!("{book}.{chapter}.{verse}"))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.references.is_empty()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Reads the cross references of either format, line by line:
+/// - The TSV of OpenBible.info: "Gen.1.1<TAB>John.1.1-John.1.3<TAB>279", with a header.
+/// - The Treasury of Scripture Knowledge as "Ge 1:1<TAB>Pr 8:22-24; 16:4; Joh 1:1-3", where the
+///   verse can also be three columns of book number, chapter and verse.  Any columns between
+///   the verse and the list, e.g. the words the references are about, are ignored.
+fn read_dataset(text: &str) -> HashMap<String, Vec<CrossReference>> {
+    let mut references: HashMap<String, Vec<CrossReference>> = HashMap::new();
+    let mut skipped = 0;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("From Verse") {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        let parsed = read_openbible(&fields).or_else(|| read_tsk(&fields));
+        let Some((verse, targets)) = parsed else {
+            skipped += 1;
+            continue;
+        };
+        let list = references.entry(verse).or_default();
+        for target in targets {
+            if !list.iter().any(|r| r.target == target.target) {
+                list.push(target);
+            }
+        }
+    }
+    if skipped > 0 {
+        warn!("Skipped {skipped} lines that are not cross references");
+    }
+    references
+}
+
+fn read_openbible(fields: &[&str]) -> Option<(String, Vec<CrossReference>)> {
+    let [from, to, votes, ..] = fields else {
+        return None;
+    };
+    let (book, chapter, verse) = osis_verse(from)?;
+    let reference = CrossReference {
+        target: to.trim().to_string(),
+        votes: Some(votes.trim().parse().ok()?),
+    };
+    reference.range()?;
+    canon::book_info(book)?;
+    Some((format!("{book}.{chapter}.{verse}"), vec![reference]))
+}
+
+fn read_tsk(fields: &[&str]) -> Option<(String, Vec<CrossReference>)> {
+    let (list, rest) = fields.split_last()?;
+    let numbers: Vec<usize> = rest
+        .iter()
+        .take(3)
+        .map_while(|f| f.trim().parse().ok())
+        .collect();
+    let (book, chapter, verse) = match numbers[..] {
+        [book, chapter, verse] => (canon::osis_id_from_number(book)?, chapter, verse),
+        _ => {
+            let targets = parse_references(rest.first()?, None);
+            let (book, (chapter, verse), _) = *targets.first()?;
+            (book, chapter, verse)
+        }
+    };
+
+    let targets: Vec<CrossReference> = parse_references(list, Some(book))
+        .into_iter()
+        .map(|(book, start, end)| CrossReference {
+            target: match start == end {
+                true => format!("{book}.{}.{}", start.0, start.1),
+                false => format!("{book}.{}.{}-{book}.{}.{}", start.0, start.1, end.0, end.1),
+            },
+            votes: None,
+        })
+        .collect();
+    (!targets.is_empty()).then(|| (format!("{book}.{chapter}.{verse}"), targets))
+}
+
+/// Parses a list like "Pr 8:22-24; 16:4; Mr 13:19, 21; Jude 1:6", where the book and chapter
+/// carry over to the references that leave them out.
+fn parse_references(list: &str, mut book: Option<&'static str>) -> Vec<Passage<'static>> {
+    let mut passages = Vec::new();
+    let mut chapter = None;
+    for part in list.split([';', ',']) {
+        let part = part.trim().trim_end_matches('.');
+        let location = match part.rsplit_once(char::is_whitespace) {
+            Some((name, location)) => {
+                let Some(info) = canon::find_book(name) else {
+                    warn!("Unknown book in cross reference {part:?}");
+                    continue;
+                };
+                book = Some(info.osis_id);
+                chapter = None;
+                location
+            }
+            None => part,
+        };
+        let (Some(book), Some((start, end))) = (book, parse_location(location, chapter)) else {
+            continue;
+        };
+        chapter = Some(end.0);
+        passages.push((book, start, end));
+    }
+    passages
+}
+
+/// Parses "3:16", "3:16-18", "3:16-4:2" or "18", a verse in `chapter`.
+fn parse_location(
+    location: &str,
+    chapter: Option<usize>,
+) -> Option<((usize, usize), (usize, usize))> {
+    let verse = |s: &str, chapter: Option<usize>| match s.split_once(':') {
+        Some((c, v)) => Some((c.parse().ok()?, v.parse().ok()?)),
+        None => Some((chapter?, s.parse().ok()?)),
+    };
+    let (start, end) = location.split_once('-').unwrap_or((location, location));
+    let start = verse(start, chapter)?;
+    let end = verse(end, Some(start.0))?;
+    (end >= start).then_some((start, end))
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Imports the dataset into an empty file, and loads it again from there.
+    fn import(dataset: &str) -> (CrossReferences, CrossReferences) {
+        let dir = std::env::temp_dir().join(format!(
+            "logos-crossrefs-{}-{}",
+            std::process::id(),
+            dataset.len()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("dataset.txt"), dataset).unwrap();
+        let mut references = CrossReferences::from_file(dir.join("crossrefs.json")).unwrap();
+        let imported = references.import(&dir.join("dataset.txt"));
+        let reloaded = CrossReferences::from_file(dir.join("crossrefs.json"));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(imported.unwrap() > 0);
+        (references, reloaded.unwrap())
+    }
+
+    fn targets(references: &[CrossReference]) -> Vec<(&str, Option<i32>)> {
+        references
+            .iter()
+            .map(|r| (r.target.as_str(), r.votes))
+            .collect()
+    }
+
+    #[test]
+    fn imports_openbible() {
+        let (references, reloaded) = import(
+            "From Verse\tTo Verse\tVotes\t#www.openbible.info CC-BY 2019-10-01\n\
+             Gen.1.1\tJohn.1.1-John.1.3\t279\n\
+             Gen.1.1\tHeb.11.3\t198\n\
+             Gen.1.1\tHeb.11.3\t198\n\
+             John.3.16\tRom.5.8\t60\n\
+             Nope.1.1\tRom.5.8\t60\n",
+        );
+        for references in [&references, &reloaded] {
+            assert_eq!(
+                targets(references.get("Gen", 1, 1)),
+                [("John.1.1-John.1.3", Some(279)), ("Heb.11.3", Some(198))]
+            );
+            assert_eq!(
+                targets(references.get("John", 3, 16)),
+                [("Rom.5.8", Some(60))]
+            );
+            assert!(references.get("Gen", 1, 2).is_empty());
+        }
+        assert_eq!(
+            references.get("Gen", 1, 1)[0].range(),
+            Some(("John", (1, 1), (1, 3)))
+        );
+    }
+
+    #[test]
+    fn imports_the_treasury_of_scripture_knowledge() {
+        let (references, _) = import(
+            "Ge 1:1\tPr 8:22-24; 16:4; Joh 1:1-3, 10; Heb 11:3\n\
+             1\t1\t2\twithout form\tJer 4:23\n\
+             43\t3\t16\tso\tRo 5:8; 1Jo 4:9, 10\n",
+        );
+        assert_eq!(
+            targets(references.get("Gen", 1, 1)),
+            [
+                ("Prov.8.22-Prov.8.24", None),
+                ("Prov.16.4", None),
+                ("John.1.1-John.1.3", None),
+                ("John.1.10", None),
+                ("Heb.11.3", None),
+            ]
+        );
+        assert_eq!(targets(references.get("Gen", 1, 2)), [("Jer.4.23", None)]);
+        assert_eq!(
+            targets(references.get("John", 3, 16)),
+            [("Rom.5.8", None), ("1John.4.9", None), ("1John.4.10", None)]
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use crate::prelude::*;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::process::Command;
+
+/// Text for the user to edit in their own editor, while the terminal interface is suspended.
+pub struct EditRequest {
+    /// Shown in the name of the file, e.g. "John.3.16".
+    pub name: String,
+    pub text: String,
+}
+
+/// Opens the text of the request in `$VISUAL` or `$EDITOR`, falling back to vi, and returns the
+/// edited text.  The terminal has to be restored to normal mode before.
+pub fn edit(request: &EditRequest) -> Result<String> {
+    let command = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    // The file name is only a hint for the editor, e.g. for markdown highlighting.
+    let name: String = request
+        .name
+        .chars()
+        .map(|c| match c.is_alphanumeric() || c == '.' || c == '-' {
+            true => c,
+            false => '_',
+        })
+        .collect();
+    let (path, mut file) = create_temp_file(&std::env::temp_dir(), &name)?;
+    file.write_all(request.text.as_bytes())?;
+    drop(file);
+
+    // The editor can have arguments, e.g. "code --wait".
+    let mut args = command.split_whitespace();
+    let program = args.next().unwrap_or("vi");
+    info!("Editing {path:?} with {command:?}");
+    let status = Command::new(program).args(args).arg(&path).status();
+    let text = std::fs::read_to_string(&path);
+    if let Err(e) = std::fs::remove_file(&path) {
+        warn!("Failed to remove {path:?}: {e}");
+    }
+
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => return Err(Error::EditorFailed(command, status.to_string())),
+        Err(e) => return Err(Error::EditorFailed(command, e.to_string())),
+    }
+    Ok(text?)
+}
+
+/// Creates a new file for `name` in the shared temporary directory, readable only by the user.
+/// A file that is already there, e.g. a symlink planted by another user, is never opened, another
+/// name is tried instead.
+fn create_temp_file(dir: &Path, name: &str) -> Result<(PathBuf, File)> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut attempt = 0;
+    loop {
+        let path = dir.join(format!("logos-{}-{attempt}-{name}.md", std::process::id()));
+        match options.open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && attempt < 100 => {
+                warn!("{path:?} already exists, trying another name");
+                attempt += 1;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn does_not_open_files_that_are_already_there() {
+        let dir = std::env::temp_dir().join(format!("logos-editor-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let planted = |attempt| dir.join(format!("logos-{}-{attempt}-John.md", std::process::id()));
+        std::fs::write(planted(0), "planted").unwrap();
+        let target = dir.join("target");
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&target, planted(1)).unwrap();
+
+        let (path, mut file) = create_temp_file(&dir, "John").unwrap();
+        file.write_all(b"note").unwrap();
+        let planted_text = std::fs::read_to_string(planted(0)).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        let followed = target.exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(planted_text, "planted");
+        assert!(!followed);
+        assert_eq!(text, "note");
+    }
+}
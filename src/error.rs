@@ -1,26 +1,49 @@
+use std::path::PathBuf;
 use std::string::FromUtf8Error;
 
 use color_eyre::eyre;
 
 #[derive(thiserror::Error, Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
-    #[error("Invalid file for bible")]
-    InvalidBibleFile,
+    #[error("Unsupported format: {0}")]
+    UnsupportedFormat(String),
+    #[error("{0:?} has {1} errors")]
+    InvalidTranslation(PathBuf, usize),
     #[error("Could not create bible index")]
     BibleIndex(String),
-    #[error("No matching book")]
+    #[error("No matching book: {0}")]
     BookNotFound(String),
-    #[error("No matching chapter")]
+    #[error("No matching chapter: {0} {1}")]
     ChapterNotFound(String, usize),
-    #[error("No matching chapter")]
+    #[error("No matching verse: {0} {1}:{2}")]
     VerseNotFound(String, usize, usize),
-    #[error("Translation not supported")]
+    #[error("Invalid reference: {0}")]
+    InvalidReference(String),
+    #[error("Invalid search: {0}")]
+    InvalidQuery(String),
+    #[error("Translation not supported: {0}")]
     UnsupprtedTranslation(String),
+    #[error("{path}:{line}:{column}: {message}")]
+    InvalidConfig {
+        path: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    #[error("{0} is already installed at {1:?}")]
+    TranslationInstalled(String, PathBuf),
+    #[error("Unsupported version {1} of {0}")]
+    UnsupportedVersion(String, u32),
+    #[error("Editor {0:?} failed: {1}")]
+    EditorFailed(String, String),
 
     #[error(transparent)]
     IoError(#[from] std::io::Error),
     #[error(transparent)]
     ParseError(#[from] FromUtf8Error),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
 
     #[error(transparent)]
     Eyre(#[from] eyre::Report),
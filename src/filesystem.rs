@@ -1,28 +1,139 @@
 use crate::prelude::*;
-use std::io::Read;
+use std::io::{BufRead, BufReader, Read};
 
-pub fn decompress_xz(path: &std::path::Path) -> Result<String> {
-    let compressed = std::fs::read(path)?;
+/// Reads a (possibly compressed) text file, detecting the compression from the content.  A
+/// directory is read as the book files in it one after the other, e.g. a USFM file per book.
+pub fn read_text(path: &Path) -> Result<String> {
+    if path.is_dir() {
+        let texts: Vec<String> = book_files(path)?
+            .iter()
+            .map(|file| read_text(file))
+            .collect::<Result<_>>()?;
+        return Ok(texts.join("\n"));
+    }
 
-    let mut decompressor = xz2::read::XzDecoder::new(compressed.as_slice());
-    let mut decompressed = Vec::new();
-    decompressor.read_to_end(&mut decompressed)?;
+    let contents = std::fs::read(path)?;
+    let text = match Compression::sniff(&contents)? {
+        Compression::Xz => {
+            let mut decompressed = Vec::new();
+            xz2::read::XzDecoder::new(contents.as_slice()).read_to_end(&mut decompressed)?;
+            String::from_utf8(decompressed)?
+        }
+        Compression::None => String::from_utf8(contents)?,
+    };
+    Ok(match text.strip_prefix('\u{feff}') {
+        Some(text) => text.to_string(),
+        None => text,
+    })
+}
 
-    return Ok(String::from_utf8(decompressed)?);
+/// Opens a (possibly compressed) text file for streaming, so that only the part that is read
+/// gets decompressed.  For a directory it is the first book file.
+pub fn open_text(path: &Path) -> Result<Box<dyn BufRead>> {
+    let mut file = BufReader::new(std::fs::File::open(first_file(path)?)?);
+    match Compression::sniff(file.fill_buf()?)? {
+        Compression::Xz => Ok(Box::new(BufReader::new(xz2::read::XzDecoder::new(file)))),
+        Compression::None => Ok(Box::new(file)),
+    }
 }
 
-// TODO: Is there a better way to check this other than just checking the extension?
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// How a file is compressed, from its magic bytes rather than the extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Xz,
+}
+
+impl Compression {
+    /// Looks at the first bytes of a file.  Formats that are recognized but can not be read are
+    /// an error.
+    pub fn sniff(head: &[u8]) -> Result<Compression> {
+        const XZ: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+        const UNSUPPORTED: [(&[u8], &str); 5] = [
+            (&[0x1f, 0x8b], "gzip compressed"),
+            (b"BZh", "bzip2 compressed"),
+            (b"PK\x03\x04", "a zip archive"),
+            (&[0xff, 0xfe], "UTF-16 text"),
+            (&[0xfe, 0xff], "UTF-16 text"),
+        ];
+
+        if head.starts_with(XZ) {
+            return Ok(Compression::Xz);
+        }
+        match UNSUPPORTED
+            .iter()
+            .find(|(magic, _)| head.starts_with(magic))
+        {
+            Some((_, kind)) => Err(Error::UnsupportedFormat(kind.to_string())),
+            None => Ok(Compression::None),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The 1-based line and column of the byte `offset` in `contents`.
+pub fn line_column(contents: &str, offset: usize) -> (usize, usize) {
+    let before = &contents[..offset.min(contents.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+    (line, column)
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Extensions of the files a bible is read from, also when compressed with xz.  Only used to find
+/// the translations, the content decides how a file is read.
+const BIBLE_EXTENSIONS: [&str; 6] = ["xml", "usx", "usfm", "sfm", "vpl", "txt"];
+/// Formats that have a file per book, a directory of these is read as a single bible.
+const BOOK_EXTENSIONS: [&str; 3] = ["usx", "usfm", "sfm"];
+
+/// "kjv.osis.xml.xz" -> "xml"
+fn extension(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?.to_lowercase();
+    let name = name.strip_suffix(".xz").unwrap_or(&name);
+    Some(name.rsplit_once('.')?.1.to_string())
+}
+
+pub fn is_bible_file(path: &Path) -> bool {
+    match path.is_dir() {
+        true => book_files(path).is_ok_and(|files| !files.is_empty()),
+        false => extension(path).is_some_and(|e| BIBLE_EXTENSIONS.contains(&e.as_str())),
+    }
+}
+
+/// The book files in a directory, sorted by name.
+pub fn book_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter(|p| extension(p).is_some_and(|e| BOOK_EXTENSIONS.contains(&e.as_str())))
+        .collect();
+    files.sort();
+    Ok(files)
+}
 
-pub fn is_xml_file(path: &std::path::Path) -> bool {
-    path.extension().and_then(|p| p.to_str()) == Some("xml")
+/// The file to look at to tell the format, which is the first book file for a directory.
+pub fn first_file(path: &Path) -> Result<PathBuf> {
+    if !path.is_dir() {
+        return Ok(path.to_path_buf());
+    }
+    book_files(path)?.into_iter().next().ok_or_else(|| {
+        Error::UnsupportedFormat("a directory without USFM or USX files".to_string())
+    })
 }
 
-pub fn is_xz_compressed_xml(path: &std::path::Path) -> bool {
-    path.extension()
-        .and_then(|p| p.to_str())
-        .map_or(false, |ext| ext == "xz")
-        && path
-            .file_stem()
-            .and_then(|p| p.to_str())
-            .map_or(false, |stem| stem.ends_with("xml"))
+/// Writes to a temporary file next to `path` first, so a failed write can not lose what was there.
+pub fn write_atomic(path: &std::path::Path, contents: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    std::fs::write(&temp, contents)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
 }
@@ -0,0 +1,413 @@
+pub mod osis;
+pub mod sword;
+pub mod usfm;
+pub mod usx;
+pub mod vpl;
+pub mod zefania;
+
+use crate::bible::{Book, Chapter, Inline, VerseView};
+use crate::canon;
+use crate::filesystem::{Compression, first_file, open_text, read_text};
+use crate::prelude::*;
+use crate::translations::TranslationInfo;
+use indexmap::IndexMap;
+use quick_xml::Reader;
+use quick_xml::events::{BytesRef, BytesStart, Event};
+use std::io::{BufRead, Read};
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Reads a source format into the index of a `Bible`.  Text is referred to by byte ranges in the
+/// raw source, so the text has to be used as it is in the source.
+pub trait BibleParser {
+    /// Reads the metadata, without reading more of the source than needed.
+    fn info(&self, source: &mut dyn BufRead, path: &Path) -> Result<TranslationInfo>;
+
+    /// Reads the raw source of the bible at `path`, the text as it is for most formats.
+    fn load(&self, path: &Path) -> Result<String> {
+        read_text(path)
+    }
+
+    /// Builds the books of the raw source.
+    fn parse(&self, raw: &str) -> Result<IndexMap<String, Book>>;
+}
+
+/// Format of a bible file, from its content rather than the extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Osis,
+    Usfm,
+    Usx,
+    Zefania,
+    /// Verse per line, e.g. "Gen 1:1 In the beginning...".
+    Vpl,
+    /// The conf file of a SWORD module.
+    Sword,
+}
+
+/// How much of a file is read to find the root element.
+const SNIFF_BYTES: u64 = 64 * 1024;
+
+impl Format {
+    /// Detects the format from the start of the (decompressed) text.
+    pub fn sniff(text: &str) -> Result<Format> {
+        if text.trim_start().starts_with('\\') {
+            return Ok(Format::Usfm);
+        }
+        if crate::sword::is_conf(text) {
+            return Ok(Format::Sword);
+        }
+        if vpl::is_vpl(text) {
+            return Ok(Format::Vpl);
+        }
+
+        let mut reader = Reader::from_str(text);
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                    let root = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                    return match root.as_str() {
+                        "osis" => Ok(Format::Osis),
+                        "usx" => Ok(Format::Usx),
+                        "XMLBIBLE" => Ok(Format::Zefania),
+                        _ => Err(Error::UnsupportedFormat(format!(
+                            "XML with the root element <{root}>"
+                        ))),
+                    };
+                }
+                Ok(Event::Text(e)) if !e.iter().all(u8::is_ascii_whitespace) => break,
+                Ok(Event::Eof) | Err(_) => break,
+                _ => {}
+            }
+        }
+        Err(Error::UnsupportedFormat(
+            "neither XML, USFM nor a verse per line".to_string(),
+        ))
+    }
+
+    /// Sniffs the format of a file or directory, only reading the start of it.
+    pub fn sniff_file(path: &Path) -> Result<(Format, Compression)> {
+        let mut magic = Vec::new();
+        std::fs::File::open(first_file(path)?)?
+            .take(8)
+            .read_to_end(&mut magic)?;
+        let compression = Compression::sniff(&magic)?;
+
+        let mut head = Vec::new();
+        open_text(path)?.take(SNIFF_BYTES).read_to_end(&mut head)?;
+        // The root element might be cut off, but everything before it is complete.
+        let text = String::from_utf8_lossy(&head);
+        let text = text.strip_prefix('\u{feff}').unwrap_or(&text);
+        Ok((Self::sniff(text)?, compression))
+    }
+
+    pub fn parser(&self) -> &'static dyn BibleParser {
+        match self {
+            Format::Osis => &osis::OsisParser,
+            Format::Usfm => &usfm::UsfmParser,
+            Format::Usx => &usx::UsxParser,
+            Format::Zefania => &zefania::ZefaniaParser,
+            Format::Vpl => &vpl::VplParser,
+            Format::Sword => &sword::SwordParser,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Format::Osis => "OSIS",
+            Format::Usfm => "USFM",
+            Format::Usx => "USX",
+            Format::Zefania => "Zefania",
+            Format::Vpl => "VPL",
+            Format::Sword => "SWORD",
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Builds the index for formats without milestones, like USFM and USX.  Verses end where the
+/// next one starts, unless they are ended before.
+///
+/// Markup that starts something is held back until the next text, so that a poetry line or quote
+/// that starts right before a verse ends up in that verse instead of the previous one.
+#[derive(Default)]
+pub struct IndexBuilder {
+    index: IndexMap<String, Book>,
+    /// OSIS ID of the current book, `None` for front and back matter.
+    book: Option<String>,
+    in_verse: bool,
+    /// Markup that starts something, for the next text or verse.
+    held: Vec<Inline>,
+    /// Speakers of the quotes that are open.
+    quotes: Vec<Option<String>>,
+    /// Quotes that were open at the end of the last verse, reopened in the next one.
+    reopen: Vec<Option<String>>,
+    /// Whether a poetry line is open.
+    in_line: bool,
+}
+
+impl IndexBuilder {
+    /// Starts a book, `None` skips everything up to the next book.
+    pub fn book(&mut self, osis_id: Option<&str>) {
+        self.end_verse();
+        self.end_line();
+        self.held.clear();
+        self.quotes.clear();
+        self.reopen.clear();
+        self.book = osis_id.map(str::to_string);
+        if let Some(id) = osis_id {
+            self.index
+                .entry(id.to_string())
+                .or_insert_with(|| Book::new(id, None));
+        }
+    }
+
+    /// Sets the name the translation uses for the current book.
+    pub fn book_name(&mut self, name: &str) {
+        let name = name.trim();
+        if let Some(book) = self.current_book()
+            && !name.is_empty()
+        {
+            book.name = name.to_string();
+        }
+    }
+
+    pub fn chapter(&mut self, number: usize) {
+        self.end_verse();
+        self.end_line();
+        if let Some(book) = self.current_book() {
+            book.chapters.push(Chapter::new(number));
+        }
+    }
+
+    pub fn verse(&mut self, number: usize) {
+        self.end_verse();
+        let reopened = self.reopen.drain(..).map(|who| Inline::QuoteStart { who });
+        let inlines: Vec<Inline> = reopened.chain(self.held.drain(..)).collect();
+        if let Some(chapter) = self.current_book().and_then(|b| b.chapters.last_mut()) {
+            let mut verse = VerseView::new(number);
+            verse.inlines = inlines;
+            chapter.verses.push(verse);
+            self.in_verse = true;
+        }
+    }
+
+    /// Ends the verse, e.g. before a heading.  Open quotes are closed and reopened in the next
+    /// verse.
+    pub fn end_verse(&mut self) {
+        if !self.in_verse {
+            return;
+        }
+        for _ in 0..self.quotes.len() {
+            self.push(Inline::QuoteEnd);
+        }
+        self.reopen = self.quotes.clone();
+        self.in_verse = false;
+    }
+
+    /// Text at the byte range of the raw source.  Outside of verses, e.g. in headings, it is
+    /// ignored.
+    pub fn text(&mut self, raw: &str, start: usize, end: usize, added: bool) {
+        if !self.in_verse || start >= end {
+            return;
+        }
+        // Whitespace does not start anything, e.g. the newline before the next verse.
+        if !raw[start..end].trim().is_empty() {
+            for inline in std::mem::take(&mut self.held) {
+                self.push(inline);
+            }
+        }
+        self.push(match added {
+            true => Inline::Added(start, end),
+            false => Inline::Text(start, end),
+        });
+    }
+
+    /// A line break within the verse.
+    pub fn line_break(&mut self) {
+        if self.in_verse {
+            self.push(Inline::LineBreak);
+        }
+    }
+
+    /// Starts a poetry line, ending the one before.
+    pub fn line(&mut self, level: usize) {
+        self.end_line();
+        self.held.push(Inline::LineStart { level });
+        self.in_line = true;
+    }
+
+    /// Ends the poetry line, if there is one.
+    pub fn end_line(&mut self) {
+        if !std::mem::take(&mut self.in_line) {
+            return;
+        }
+        // A line that was never started in a verse has nothing to end.
+        match self
+            .held
+            .iter()
+            .rposition(|i| matches!(i, Inline::LineStart { .. }))
+        {
+            Some(i) => _ = self.held.remove(i),
+            None => self.push(Inline::LineEnd),
+        }
+    }
+
+    pub fn open_quote(&mut self, who: Option<String>) {
+        self.quotes.push(who.clone());
+        self.held.push(Inline::QuoteStart { who });
+    }
+
+    pub fn close_quote(&mut self) {
+        self.quotes.pop();
+        match self
+            .held
+            .iter()
+            .rposition(|i| matches!(i, Inline::QuoteStart { .. }))
+        {
+            Some(i) => _ = self.held.remove(i),
+            None if self.in_verse => self.push(Inline::QuoteEnd),
+            // Already closed at the end of the verse.
+            None => _ = self.reopen.pop(),
+        }
+    }
+
+    /// The books in canonical order, empty ones left out.
+    pub fn finish(mut self) -> IndexMap<String, Book> {
+        self.end_verse();
+        self.end_line();
+        // Chapters without verses, e.g. a draft ending in `\c 2`, are dropped like in OSIS.
+        for book in self.index.values_mut() {
+            book.chapters.retain(|c| !c.verses.is_empty());
+        }
+        self.index.retain(|_, book| !book.chapters.is_empty());
+        self.index
+            .sort_by_cached_key(|id, _| canon::canonical_position(id));
+        self.index
+    }
+
+    fn current_book(&mut self) -> Option<&mut Book> {
+        self.index.get_mut(self.book.as_deref()?)
+    }
+
+    /// Adds to the current verse, or the last one between verses.
+    fn push(&mut self, inline: Inline) {
+        if let Some(verse) = self
+            .current_book()
+            .and_then(|b| b.chapters.last_mut())
+            .and_then(|c| c.verses.last_mut())
+        {
+            verse.inlines.push(inline);
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The byte range of an entity reference like `&amp;` in the raw source, from the position of the
+/// reader after it.  It is resolved when the text is shown, see `bible::text_at`.
+pub fn entity_range(e: &BytesRef, end: u64) -> (usize, usize) {
+    let end = end as usize;
+    (end - e.len() - 2, end)
+}
+
+/// The text of an entity reference, e.g. "&" for `&amp;`.  Unknown entities are left out.
+pub fn resolve_entity(e: &BytesRef) -> String {
+    let entity = format!("&{};", String::from_utf8_lossy(e));
+    quick_xml::escape::unescape(&entity)
+        .unwrap_or_default()
+        .into_owned()
+}
+
+/// The value of an attribute of an XML element.
+pub fn attr(e: &BytesStart, key: &[u8]) -> Option<String> {
+    e.attributes()
+        .filter_map(|a| a.ok())
+        .find(|a| a.key.as_ref() == key)
+        .map(|a| String::from_utf8_lossy(&a.value).to_string())
+}
+
+pub fn has_attr(e: &BytesStart, key: &[u8]) -> bool {
+    e.attributes()
+        .filter_map(|a| a.ok())
+        .any(|a| a.key.as_ref() == key)
+}
+
+pub fn has_attr_val(e: &BytesStart, key: &[u8], val: &[u8]) -> bool {
+    e.attributes()
+        .filter_map(|a| a.ok())
+        .any(|a| a.key.as_ref() == key && a.value.as_ref() == val)
+}
+
+/// Paragraph styles of USFM and USX whose text is not part of the verses, e.g. headings and
+/// introductions.
+pub fn is_skipped_style(style: &str) -> bool {
+    let base = style.trim_end_matches(|c: char| c.is_ascii_digit());
+    matches!(
+        base,
+        "id" | "ide"
+            | "h"
+            | "toc"
+            | "toca"
+            | "rem"
+            | "sts"
+            | "usfm"
+            | "mt"
+            | "mte"
+            | "ms"
+            | "mr"
+            | "s"
+            | "sr"
+            | "r"
+            | "d"
+            | "sp"
+            | "sd"
+            | "cl"
+            | "cd"
+            | "lit"
+            | "imt"
+            | "imte"
+            | "is"
+            | "ip"
+            | "ipi"
+            | "im"
+            | "imi"
+            | "ipq"
+            | "imq"
+            | "ipr"
+            | "iq"
+            | "ib"
+            | "ili"
+            | "iot"
+            | "io"
+            | "iex"
+            | "ie"
+    )
+}
+
+/// The poetry level of a paragraph style, e.g. 2 for "q2".
+pub fn poetry_level(style: &str) -> Option<usize> {
+    let level = style
+        .strip_prefix("qm")
+        .or_else(|| style.strip_prefix('q'))?;
+    match level {
+        "" => Some(1),
+        level => level.parse().ok(),
+    }
+}
+
+/// The first number of a verse or chapter number, e.g. 1 for "1-2" or "1a".
+pub fn leading_number(s: &str) -> Option<usize> {
+    let digits: &str = &s[..s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len())];
+    digits.parse().ok()
+}
+
+/// "GEN" and the description of an `\id` line or USX `<book>`, e.g. "GEN - King James Version".
+pub fn description(id: &str) -> &str {
+    id.trim()
+        .split_once(char::is_whitespace)
+        .map_or("", |(_, rest)| rest.trim().trim_start_matches(['-', ' ']))
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
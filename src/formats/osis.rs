@@ -0,0 +1,322 @@
+use crate::bible::{Book, Chapter, Inline, Testament, VerseView, WordTag};
+use crate::formats::{BibleParser, attr, entity_range, has_attr, has_attr_val};
+use crate::prelude::*;
+use crate::translations::TranslationInfo;
+use indexmap::IndexMap;
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use std::io::BufRead;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// OSIS XML, with milestoned or container chapters and verses.
+pub struct OsisParser;
+
+impl BibleParser for OsisParser {
+    fn info(&self, source: &mut dyn BufRead, path: &Path) -> Result<TranslationInfo> {
+        TranslationInfo::from_osis_header(source, path)
+    }
+
+    fn parse(&self, raw: &str) -> Result<IndexMap<String, Book>> {
+        build_index(raw)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn build_index(raw: &str) -> Result<IndexMap<String, Book>> {
+    info!("Building bible index");
+
+    let start = Instant::now();
+    let mut index: IndexMap<String, Book> = IndexMap::new();
+    let mut reader = Reader::from_str(raw);
+
+    let mut buf = Vec::new();
+    let mut book = String::new();
+    let mut awaiting_title = false;
+    let mut awaiting_group_title = false;
+    let mut in_group_title = false;
+    let mut testament = None;
+    let mut in_verse = false;
+    let mut in_added = false;
+    // Markup found between verses that starts something, it is added to the next verse.
+    let mut pending: Vec<Inline> = Vec::new();
+    // (sID, who) of the quotes that are open.  Milestoned quotes can span verses, so they
+    // are closed at the end of each verse and reopened in the next one.
+    let mut open_quotes: Vec<(Option<String>, Option<String>)> = Vec::new();
+    let mut current_chapter = 1;
+    let mut current_verse = 1;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e))
+                if e.name().as_ref() == b"div" && has_attr_val(e, b"type", b"bookGroup") =>
+            {
+                awaiting_group_title = true;
+                testament = None;
+            }
+            Ok(Event::Start(ref e))
+                if e.name().as_ref() == b"div" && has_attr_val(e, b"type", b"book") =>
+            {
+                awaiting_title = true;
+                awaiting_group_title = false;
+                pending.clear();
+                open_quotes.clear();
+                current_chapter = 1;
+                book = attr(e, b"osisID").unwrap_or_default();
+                if !book.is_empty() {
+                    index.insert(book.clone(), Book::new(&book, testament));
+                }
+            }
+            Ok(Event::Start(ref e)) if e.name().as_ref() == b"title" && awaiting_group_title => {
+                in_group_title = true;
+                awaiting_group_title = false;
+            }
+            Ok(Event::Text(ref e)) if in_group_title => {
+                testament = Testament::from_title(&String::from_utf8_lossy(e));
+                in_group_title = false;
+            }
+            Ok(Event::Start(ref e)) if e.name().as_ref() == b"title" && awaiting_title => {
+                if let Some(name) = attr(e, b"short") {
+                    // Fall back to the title when there is no ID.
+                    if book.is_empty() {
+                        book = name.clone();
+                        index.insert(book.clone(), Book::new(&book, testament));
+                    }
+                    index.entry(book.clone()).or_default().name = name;
+                }
+                awaiting_title = false;
+            }
+            // Milestones (`<chapter sID="..."/>`) or containers (`<chapter osisID="...">`).
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e))
+                if e.name().as_ref() == b"chapter" && !has_attr(e, b"eID") =>
+            {
+                current_chapter = osis_number(e).unwrap_or(current_chapter);
+                index
+                    .entry(book.clone())
+                    .or_default()
+                    .chapters
+                    .push(Chapter::new(current_chapter));
+                current_chapter += 1;
+                current_verse = 1;
+            }
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e))
+                if e.name().as_ref() == b"verse" && !has_attr(e, b"eID") =>
+            {
+                in_verse = true;
+                current_verse = osis_number(e).unwrap_or(current_verse);
+                // The ID says which chapter the verse is in, even where it is not the one that
+                // was opened last, e.g. Malachi 4 in a text with the verses numbered 3:19-24.
+                if let Some(chapter) = osis_chapter(e) {
+                    select_chapter(&mut index, &book, chapter);
+                }
+                if let Some(ch) = index.get_mut(&book).and_then(|b| b.chapters.last_mut()) {
+                    let mut verse = VerseView::new(current_verse);
+                    verse.inlines.append(&mut pending);
+                    verse.inlines.extend(
+                        open_quotes
+                            .iter()
+                            .map(|(_, who)| Inline::QuoteStart { who: who.clone() }),
+                    );
+                    ch.verses.push(verse);
+                    current_verse += 1;
+                }
+            }
+            Ok(Event::Empty(ref e)) if e.name().as_ref() == b"verse" && has_attr(e, b"eID") => {
+                for _ in &open_quotes {
+                    push_inline(&mut index, &book, true, &mut pending, Inline::QuoteEnd);
+                }
+                in_verse = false;
+            }
+            // The end of a container verse.
+            Ok(Event::End(ref e)) if e.name().as_ref() == b"verse" => {
+                for _ in &open_quotes {
+                    push_inline(&mut index, &book, true, &mut pending, Inline::QuoteEnd);
+                }
+                in_verse = false;
+            }
+            Ok(Event::Start(ref e)) if e.name().as_ref() == b"w" && in_verse => {
+                let inline = Inline::WordStart(WordTag {
+                    lemma: attr(e, b"lemma").unwrap_or_default(),
+                    morph: attr(e, b"morph"),
+                });
+                push_inline(&mut index, &book, true, &mut pending, inline);
+            }
+            Ok(Event::End(ref e)) if e.name().as_ref() == b"w" && in_verse => {
+                push_inline(&mut index, &book, true, &mut pending, Inline::WordEnd);
+            }
+            Ok(Event::Start(ref e)) if e.name().as_ref() == b"transChange" => {
+                in_added = has_attr_val(e, b"type", b"added");
+            }
+            Ok(Event::End(ref e)) if e.name().as_ref() == b"transChange" => in_added = false,
+            Ok(Event::Text(ref e)) if in_verse => {
+                let len = e.len();
+                if len > 0 {
+                    let end = reader.buffer_position() as usize;
+                    let start = end - len;
+                    let inline = match in_added {
+                        true => Inline::Added(start, end),
+                        false => Inline::Text(start, end),
+                    };
+                    push_inline(&mut index, &book, in_verse, &mut pending, inline);
+                }
+            }
+            Ok(Event::GeneralRef(ref e)) if in_verse => {
+                let (start, end) = entity_range(e, reader.buffer_position());
+                let inline = match in_added {
+                    true => Inline::Added(start, end),
+                    false => Inline::Text(start, end),
+                };
+                push_inline(&mut index, &book, in_verse, &mut pending, inline);
+            }
+            // Quotes between verses only change what is open, see `open_quotes`.
+            Ok(Event::Empty(ref e)) if e.name().as_ref() == b"q" && has_attr(e, b"eID") => {
+                let id = attr(e, b"eID");
+                match open_quotes.iter().rposition(|(sid, _)| *sid == id) {
+                    Some(i) => _ = open_quotes.remove(i),
+                    None => _ = open_quotes.pop(),
+                }
+                if in_verse {
+                    push_inline(&mut index, &book, true, &mut pending, Inline::QuoteEnd);
+                }
+            }
+            Ok(Event::Empty(ref e)) if e.name().as_ref() == b"q" && !has_attr(e, b"sID") => {
+                // An empty quote, nothing to open.
+            }
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) if e.name().as_ref() == b"q" => {
+                let who = attr(e, b"who");
+                open_quotes.push((attr(e, b"sID"), who.clone()));
+                if in_verse {
+                    let inline = Inline::QuoteStart { who };
+                    push_inline(&mut index, &book, true, &mut pending, inline);
+                }
+            }
+            Ok(Event::End(ref e)) if e.name().as_ref() == b"q" => {
+                open_quotes.pop();
+                if in_verse {
+                    push_inline(&mut index, &book, true, &mut pending, Inline::QuoteEnd);
+                }
+            }
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e))
+                if matches!(e.name().as_ref(), b"l" | b"lb") =>
+            {
+                let milestone_end = has_attr(e, b"eID");
+                let inline = match e.name().as_ref() {
+                    b"l" if milestone_end => Inline::LineEnd,
+                    b"l" => Inline::LineStart {
+                        level: attr(e, b"level").and_then(|l| l.parse().ok()).unwrap_or(1),
+                    },
+                    _ => Inline::LineBreak,
+                };
+                push_inline(&mut index, &book, in_verse, &mut pending, inline);
+            }
+            Ok(Event::End(ref e)) if e.name().as_ref() == b"l" => {
+                push_inline(&mut index, &book, in_verse, &mut pending, Inline::LineEnd);
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(Error::BibleIndex(e.to_string())),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    // Chapters whose verses all turned out to be in other chapters are dropped.
+    for book in index.values_mut() {
+        book.chapters.retain(|c| !c.verses.is_empty());
+        book.chapters.sort_by_key(|c| c.number);
+    }
+    info!("Built index in {:?}", start.elapsed());
+    Ok(index)
+}
+
+/// Adds the inline to the current verse.  Between verses, markup that ends something is
+/// added to the previous verse and markup that starts something to the next one.
+fn push_inline(
+    index: &mut IndexMap<String, Book>,
+    book: &str,
+    in_verse: bool,
+    pending: &mut Vec<Inline>,
+    inline: Inline,
+) {
+    if !in_verse && !inline.is_end() {
+        pending.push(inline);
+        return;
+    }
+    if let Some(verse) = index
+        .get_mut(book)
+        .and_then(|b| b.chapters.last_mut())
+        .and_then(|c| c.verses.last_mut())
+    {
+        verse.inlines.push(inline);
+    }
+}
+
+/// Makes the chapter the one verses are added to, i.e. the last one of the book.  It is added if
+/// the book does not have it yet.
+fn select_chapter(index: &mut IndexMap<String, Book>, book: &str, number: usize) {
+    let Some(book) = index.get_mut(book) else {
+        return;
+    };
+    if book.chapters.last().is_some_and(|c| c.number == number) {
+        return;
+    }
+    let chapter = match book.chapters.iter().position(|c| c.number == number) {
+        Some(i) => book.chapters.remove(i),
+        None => Chapter::new(number),
+    };
+    book.chapters.push(chapter);
+}
+
+/// The number at the end of the OSIS ID, e.g. 16 for "Gen.1.16", falling back to `n`.
+/// Merged verses list multiple IDs ("Rom.16.25 Rom.16.26"), the first one is used.
+fn osis_number(e: &quick_xml::events::BytesStart) -> Option<usize> {
+    attr(e, b"osisID")
+        .or_else(|| attr(e, b"osisRef"))
+        .and_then(|id| {
+            id.split_whitespace()
+                .next()?
+                .rsplit('.')
+                .next()?
+                .parse()
+                .ok()
+        })
+        .or_else(|| attr(e, b"n")?.parse().ok())
+}
+
+/// The chapter of a verse from its OSIS ID, e.g. 1 for "Gen.1.16".
+fn osis_chapter(e: &quick_xml::events::BytesStart) -> Option<usize> {
+    let id = attr(e, b"osisID")?;
+    let mut parts = id.split_whitespace().next()?.split('.');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(_), Some(chapter), Some(_)) => chapter.parse().ok(),
+        _ => None,
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use crate::bible::tests::bible_from;
+
+    #[test]
+    fn keeps_entity_references_in_verse_text() {
+        let bible = bible_from(
+            "entities.xml",
+            r#"<osis><osisText><div type="bookGroup"><title>New Testament</title>
+<div type="book" osisID="John"><chapter osisID="John.1">
+<verse osisID="John.1.1">Salt &amp; light, not &lt;x&gt; <transChange type="added">&#x2019;s</transChange></verse>
+</chapter></div></div></osisText></osis>"#,
+        );
+        let verse = bible
+            .get_chapter("John", 1)
+            .unwrap()
+            .get_verses()
+            .next()
+            .unwrap();
+        assert_eq!(
+            verse.collect_string(bible.get_raw_data()),
+            "Salt & light, not <x> \u{2019}s"
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use crate::bible::Book;
+use crate::formats::{BibleParser, osis::OsisParser};
+use crate::prelude::*;
+use crate::sword::{Conf, SwordModule, to_osis};
+use crate::translations::TranslationInfo;
+use indexmap::IndexMap;
+use std::io::BufRead;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A SWORD bible module, from its conf file in `mods.d`.  The verses are read into an OSIS text,
+/// with the books and chapters of the versification of the module.
+pub struct SwordParser;
+
+impl BibleParser for SwordParser {
+    fn info(&self, source: &mut dyn BufRead, path: &Path) -> Result<TranslationInfo> {
+        let mut text = String::new();
+        source.read_to_string(&mut text)?;
+        let conf = Conf::parse(&text)?;
+        Ok(TranslationInfo {
+            name: conf.name.clone(),
+            title: conf.description().to_string(),
+            language: conf.language().to_string(),
+            rights: conf.rights().to_string(),
+            versification: Some(conf.versification().to_string()),
+            path: path.to_path_buf(),
+        })
+    }
+
+    fn load(&self, path: &Path) -> Result<String> {
+        let module = SwordModule::open(path)?;
+        let start = Instant::now();
+        let mut verses = module.verses()?;
+        let mut osis = format!("<osis><osisText osisIDWork=\"{}\">\n", module.name);
+
+        let versification = module.versification();
+        if !versification.missing_books().is_empty() {
+            warn!(
+                "{} uses the {} versification, whose books {} can not be read",
+                module.name,
+                versification.name,
+                versification.missing_books().join(", ")
+            );
+        }
+        for (book, chapters) in versification.books() {
+            let mut book_text = String::new();
+            for (chapter, count) in chapters.iter().enumerate().map(|(i, c)| (i + 1, *c)) {
+                let mut chapter_text = String::new();
+                for verse in 1..=count as usize {
+                    if let Some(entry) = verses.entry(book, chapter, verse)? {
+                        let id = format!("{book}.{chapter}.{verse}");
+                        let text = to_osis(module.markup, &entry);
+                        chapter_text.push_str(&format!("<verse osisID=\"{id}\">{text}</verse>\n"));
+                    }
+                }
+                if !chapter_text.is_empty() {
+                    book_text.push_str(&format!("<chapter osisID=\"{book}.{chapter}\">\n"));
+                    book_text.push_str(&chapter_text);
+                    book_text.push_str("</chapter>\n");
+                }
+            }
+            if !book_text.is_empty() {
+                osis.push_str(&format!("<div type=\"book\" osisID=\"{book}\">\n"));
+                osis.push_str(&book_text);
+                osis.push_str("</div>\n");
+            }
+        }
+
+        osis.push_str("</osisText></osis>\n");
+        info!("Read SWORD module {} in {:?}", module.name, start.elapsed());
+        Ok(osis)
+    }
+
+    fn parse(&self, raw: &str) -> Result<IndexMap<String, Book>> {
+        OsisParser.parse(raw)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,315 @@
+use crate::bible::Book;
+use crate::canon::osis_id_from_usfm;
+use crate::formats::{
+    BibleParser, IndexBuilder, description, is_skipped_style, leading_number, poetry_level,
+};
+use crate::prelude::*;
+use crate::translations::{TranslationInfo, file_stem};
+use indexmap::IndexMap;
+use std::io::BufRead;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// USFM, either a single file or a directory with a file per book.
+pub struct UsfmParser;
+
+/// Markers whose content is not part of the text, up to their closing marker.
+const SKIPPED_NOTES: [&str; 10] = ["f", "fe", "ef", "x", "ex", "fig", "rq", "va", "vp", "ca"];
+
+impl BibleParser for UsfmParser {
+    /// USFM has no metadata besides the description on the `\id` line, so the name is the one
+    /// of the file.
+    fn info(&self, source: &mut dyn BufRead, path: &Path) -> Result<TranslationInfo> {
+        let name = file_stem(path).to_uppercase();
+        let mut title = String::new();
+        for line in source.lines().take(10) {
+            if let Some(id) = line?.trim_start().strip_prefix("\\id ") {
+                title = description(id).to_string();
+                break;
+            }
+        }
+
+        Ok(TranslationInfo {
+            title: match title.is_empty() {
+                true => name.clone(),
+                false => title,
+            },
+            language: String::new(),
+            rights: String::new(),
+            name,
+            versification: None,
+            path: path.to_path_buf(),
+        })
+    }
+
+    fn parse(&self, raw: &str) -> Result<IndexMap<String, Book>> {
+        info!("Building bible index");
+        let start = Instant::now();
+
+        let mut builder = IndexBuilder::default();
+        let mut pos = 0;
+        let mut text_start = 0;
+        let mut added = false;
+        // Whether the book has a `\h`, which is preferred over `\toc2`.
+        let mut has_name = false;
+        // Character markers that are open, text after a `|` in them is attributes.
+        let mut depth = 0usize;
+        let mut in_attributes = false;
+
+        while let Some(found) = raw[pos..].find('\\') {
+            let marker_start = pos + found;
+            if !in_attributes {
+                let mut end = marker_start;
+                if depth > 0
+                    && let Some(bar) = raw[text_start..marker_start].find('|')
+                {
+                    end = text_start + bar;
+                    in_attributes = true;
+                }
+                builder.text(raw, text_start, end, added);
+            }
+
+            let marker = Marker::read(raw, marker_start);
+            pos = marker.end;
+            match (marker.name, marker.closing) {
+                (_, true) => {
+                    match marker.name {
+                        "add" => added = false,
+                        "wj" => builder.close_quote(),
+                        _ => {}
+                    }
+                    depth = depth.saturating_sub(1);
+                    in_attributes = false;
+                }
+                ("id", _) => {
+                    let line = rest_of_line(raw, pos);
+                    let code = line.split_whitespace().next().unwrap_or_default();
+                    builder.book(osis_id_from_usfm(code));
+                    has_name = false;
+                    added = false;
+                    depth = 0;
+                    pos += line.len();
+                }
+                ("h", _) | ("toc2", _) => {
+                    let line = rest_of_line(raw, pos);
+                    if marker.name == "h" || !has_name {
+                        builder.book_name(line);
+                        has_name = marker.name == "h";
+                    }
+                    pos += line.len();
+                }
+                ("c", _) => {
+                    let line = rest_of_line(raw, pos);
+                    if let Some(number) = leading_number(line.trim_start()) {
+                        builder.chapter(number);
+                    }
+                    pos += line.len();
+                }
+                ("v", _) => {
+                    let rest = &raw[pos..];
+                    let number = rest.trim_start();
+                    let len = number.find(char::is_whitespace).unwrap_or(number.len());
+                    if let Some(number) = leading_number(&number[..len]) {
+                        builder.verse(number);
+                    }
+                    pos += rest.len() - number.len() + len;
+                    pos += whitespace_len(&raw[pos..]);
+                }
+                (name, _) if SKIPPED_NOTES.contains(&name) => {
+                    let closing = format!("\\{name}*");
+                    pos = raw[pos..]
+                        .find(&closing)
+                        .map_or(raw.len(), |i| pos + i + closing.len());
+                }
+                (name, _) if is_skipped_style(name) => {
+                    builder.end_line();
+                    pos += rest_of_line(raw, pos).len();
+                }
+                (name, _) => match poetry_level(name) {
+                    Some(level) => builder.line(level),
+                    None if is_paragraph(name) => builder.end_line(),
+                    None => {
+                        match name {
+                            "add" => added = true,
+                            "wj" => builder.open_quote(Some("Jesus".to_string())),
+                            _ => {}
+                        }
+                        depth += 1;
+                    }
+                },
+            }
+            text_start = pos;
+        }
+        if !in_attributes {
+            builder.text(raw, text_start, raw.len(), added);
+        }
+
+        let index = builder.finish();
+        info!("Built index in {:?}", start.elapsed());
+        Ok(index)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A marker like `\v`, `\+add` or `\wj*`.
+struct Marker<'a> {
+    /// Without the backslash, the `+` of nested markers and the `*`.
+    name: &'a str,
+    closing: bool,
+    /// Where the text after the marker starts.
+    end: usize,
+}
+
+impl<'a> Marker<'a> {
+    fn read(raw: &'a str, start: usize) -> Marker<'a> {
+        let after = start + 1;
+        let name_start = after + usize::from(raw[after..].starts_with('+'));
+        let len = raw[name_start..]
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+            .unwrap_or(raw.len() - name_start);
+        let name = &raw[name_start..name_start + len];
+        let mut end = name_start + len;
+
+        let closing = raw[end..].starts_with('*');
+        match closing {
+            true => end += 1,
+            // The whitespace after an opening marker belongs to the marker.
+            false => end += usize::from(raw[end..].starts_with([' ', '\t', '\r', '\n'])),
+        }
+        Marker { name, closing, end }
+    }
+}
+
+/// Paragraph markers that are not poetry, they end the poetry line.
+fn is_paragraph(name: &str) -> bool {
+    let base = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    matches!(
+        base,
+        "p" | "m"
+            | "po"
+            | "pr"
+            | "cls"
+            | "pmo"
+            | "pm"
+            | "pmc"
+            | "pmr"
+            | "pi"
+            | "mi"
+            | "nb"
+            | "pc"
+            | "ph"
+            | "b"
+            | "li"
+            | "lh"
+            | "lf"
+            | "lim"
+            | "qr"
+            | "qc"
+            | "qa"
+            | "qd"
+            | "tr"
+            | "periph"
+    )
+}
+
+/// The text up to the end of the line, without the line break.
+fn rest_of_line(raw: &str, start: usize) -> &str {
+    let rest = &raw[start..];
+    &rest[..rest.find(['\r', '\n']).unwrap_or(rest.len())]
+}
+
+/// The length of the whitespace at the start, in bytes.
+fn whitespace_len(s: &str) -> usize {
+    s.len() - s.trim_start().len()
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bible::{Inline, VerseView};
+
+    const GENESIS: &str = r#"\id GEN Draft
+\h Genesis
+\c 1
+\p
+\v 1 In the beginning God created\f + \fr 1:1 \ft Or "made".\f* the heaven.
+\v 2 And the earth was \add without\add* form.
+\q1
+\v 3 \w Light|strong="H216"\w* be,
+\q2 and it was.
+\p
+\v 4 \wj I am the light.\wj*
+\c 2
+"#;
+
+    fn verse(index: &IndexMap<String, Book>, chapter: usize, number: usize) -> &VerseView {
+        index["Gen"].chapters[chapter - 1]
+            .verses
+            .iter()
+            .find(|v| v.number == number)
+            .unwrap()
+    }
+
+    #[test]
+    fn reads_the_verse_text() {
+        let index = UsfmParser.parse(GENESIS).unwrap();
+        assert_eq!(index["Gen"].name, "Genesis");
+        // Footnotes and the attributes of `\w` are not part of the text.
+        assert_eq!(
+            verse(&index, 1, 1).collect_string(GENESIS),
+            "In the beginning God created the heaven."
+        );
+        assert_eq!(
+            verse(&index, 1, 3).collect_string(GENESIS),
+            "Light be, and it was."
+        );
+    }
+
+    #[test]
+    fn keeps_added_words_quotes_and_poetry_lines() {
+        let index = UsfmParser.parse(GENESIS).unwrap();
+        let added: Vec<&str> = verse(&index, 1, 2)
+            .inlines
+            .iter()
+            .filter_map(|inline| match inline {
+                Inline::Added(s, e) => Some(&GENESIS[*s..*e]),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(added, ["without"]);
+
+        let lines: Vec<&Inline> = verse(&index, 1, 3)
+            .inlines
+            .iter()
+            .filter(|inline| matches!(inline, Inline::LineStart { .. } | Inline::LineEnd))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                &Inline::LineStart { level: 1 },
+                &Inline::LineEnd,
+                &Inline::LineStart { level: 2 },
+                &Inline::LineEnd
+            ]
+        );
+
+        let inlines = &verse(&index, 1, 4).inlines;
+        assert_eq!(
+            inlines.first(),
+            Some(&Inline::QuoteStart {
+                who: Some("Jesus".to_string())
+            })
+        );
+        assert!(inlines.contains(&Inline::QuoteEnd));
+    }
+
+    #[test]
+    fn drops_chapters_without_verses() {
+        let index = UsfmParser.parse(GENESIS).unwrap();
+        let chapters: Vec<usize> = index["Gen"].chapters.iter().map(|c| c.number).collect();
+        assert_eq!(chapters, [1]);
+    }
+}
@@ -0,0 +1,280 @@
+use crate::bible::Book;
+use crate::canon::osis_id_from_usfm;
+use crate::formats::{
+    BibleParser, IndexBuilder, attr, entity_range, has_attr, is_skipped_style, leading_number,
+    poetry_level,
+};
+use crate::prelude::*;
+use crate::translations::{TranslationInfo, file_stem};
+use indexmap::IndexMap;
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use std::io::BufRead;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// USX, the XML form of USFM.  A directory with a file per book is read as one text with a
+/// document after the other.
+pub struct UsxParser;
+
+impl BibleParser for UsxParser {
+    /// Like USFM, the only metadata is the description of the `<book>`.
+    fn info(&self, source: &mut dyn BufRead, path: &Path) -> Result<TranslationInfo> {
+        let name = file_stem(path).to_uppercase();
+        let mut reader = Reader::from_reader(source);
+        let mut buf = Vec::new();
+        let mut in_book = false;
+        let mut title = String::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(ref e)) if e.name().as_ref() == b"book" => in_book = true,
+                Ok(Event::Text(ref e)) if in_book => {
+                    title.push_str(&String::from_utf8_lossy(e));
+                }
+                Ok(Event::End(ref e)) if e.name().as_ref() == b"book" => break,
+                // The book is the first thing, no need to read further.
+                Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e))
+                    if matches!(e.name().as_ref(), b"chapter" | b"para") =>
+                {
+                    break;
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(Error::BibleIndex(e.to_string())),
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        let title = title.trim().trim_start_matches(['-', ' ']).to_string();
+        Ok(TranslationInfo {
+            title: match title.is_empty() {
+                true => name.clone(),
+                false => title,
+            },
+            language: String::new(),
+            rights: String::new(),
+            name,
+            versification: None,
+            path: path.to_path_buf(),
+        })
+    }
+
+    fn parse(&self, raw: &str) -> Result<IndexMap<String, Book>> {
+        info!("Building bible index");
+        let start = Instant::now();
+
+        let mut builder = IndexBuilder::default();
+        let mut reader = Reader::from_str(raw);
+        let mut buf = Vec::new();
+        // Elements whose text is not part of the verses, e.g. headings and footnotes.
+        let mut skipped = 0usize;
+        // Whether the text is the name of the book.
+        let mut in_name = false;
+        // Styles of the open `<char>` elements.
+        let mut chars: Vec<String> = Vec::new();
+        // Whether the `<para>` that is open is a poetry line.
+        let mut in_line = false;
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                // The text of the book is its description.
+                Ok(Event::Start(ref e)) if e.name().as_ref() == b"book" => {
+                    builder.book(osis_id_from_usfm(&attr(e, b"code").unwrap_or_default()));
+                    skipped += 1;
+                }
+                Ok(Event::Empty(ref e)) if e.name().as_ref() == b"book" => {
+                    builder.book(osis_id_from_usfm(&attr(e, b"code").unwrap_or_default()));
+                }
+                Ok(Event::End(ref e)) if e.name().as_ref() == b"book" => skipped -= 1,
+                Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e))
+                    if e.name().as_ref() == b"chapter" =>
+                {
+                    if let Some(number) = attr(e, b"number").and_then(|n| leading_number(&n)) {
+                        builder.chapter(number);
+                    }
+                }
+                Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e))
+                    if e.name().as_ref() == b"verse" =>
+                {
+                    match attr(e, b"number").and_then(|n| leading_number(&n)) {
+                        Some(number) => builder.verse(number),
+                        None if has_attr(e, b"eid") => builder.end_verse(),
+                        None => {}
+                    }
+                }
+                Ok(Event::Start(ref e)) if e.name().as_ref() == b"para" => {
+                    let style = attr(e, b"style").unwrap_or_default();
+                    match poetry_level(&style) {
+                        Some(level) => {
+                            builder.line(level);
+                            in_line = true;
+                        }
+                        None if is_skipped_style(&style) => {
+                            builder.end_line();
+                            in_name = style == "h";
+                            skipped += 1;
+                        }
+                        None => builder.end_line(),
+                    }
+                }
+                Ok(Event::End(ref e)) if e.name().as_ref() == b"para" => {
+                    if std::mem::take(&mut in_line) {
+                        builder.end_line();
+                    }
+                    if skipped > 0 {
+                        skipped -= 1;
+                        in_name = false;
+                    }
+                }
+                Ok(Event::Start(ref e)) if matches!(e.name().as_ref(), b"note" | b"figure") => {
+                    skipped += 1;
+                }
+                Ok(Event::End(ref e)) if matches!(e.name().as_ref(), b"note" | b"figure") => {
+                    skipped -= 1;
+                }
+                Ok(Event::Start(ref e)) if e.name().as_ref() == b"char" => {
+                    let style = attr(e, b"style").unwrap_or_default();
+                    if style == "wj" {
+                        builder.open_quote(Some("Jesus".to_string()));
+                    }
+                    chars.push(style);
+                }
+                Ok(Event::End(ref e)) if e.name().as_ref() == b"char" => {
+                    let style = chars.pop();
+                    if style.as_deref() == Some("wj") {
+                        builder.close_quote();
+                    }
+                }
+                Ok(Event::Text(ref e)) if in_name => {
+                    builder.book_name(&String::from_utf8_lossy(e));
+                }
+                Ok(Event::Text(ref e)) if skipped == 0 => {
+                    let end = reader.buffer_position() as usize;
+                    let added = chars.iter().any(|style| style == "add");
+                    builder.text(raw, end - e.len(), end, added);
+                }
+                Ok(Event::GeneralRef(ref e)) if skipped == 0 => {
+                    let (start, end) = entity_range(e, reader.buffer_position());
+                    let added = chars.iter().any(|style| style == "add");
+                    builder.text(raw, start, end, added);
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(Error::BibleIndex(e.to_string())),
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        let index = builder.finish();
+        info!("Built index in {:?}", start.elapsed());
+        Ok(index)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bible::tests::bible_from;
+    use crate::bible::{Inline, VerseView};
+
+    const GENESIS: &str = r#"<usx version="3.0"><book code="GEN" style="id">- Draft</book>
+<para style="h">Genesis</para>
+<chapter number="1" style="c" sid="GEN 1"/>
+<para style="p"><verse number="1" style="v" sid="GEN 1:1"/>In the beginning God created<note caller="+" style="f"><char style="ft">Or "made".</char></note> the heaven.<verse eid="GEN 1:1"/>
+<verse number="2" style="v" sid="GEN 1:2"/>And the earth was <char style="add">without</char> form.<verse eid="GEN 1:2"/></para>
+<para style="q1"><verse number="3" style="v" sid="GEN 1:3"/><char style="w" strong="H216">Light</char> be,</para>
+<para style="q2">and it was.<verse eid="GEN 1:3"/></para>
+<para style="p"><verse number="4" style="v" sid="GEN 1:4"/><char style="wj">I am the light.</char><verse eid="GEN 1:4"/></para>
+<chapter eid="GEN 1"/>
+<chapter number="2" style="c" sid="GEN 2"/>
+</usx>"#;
+
+    fn verse(index: &IndexMap<String, Book>, chapter: usize, number: usize) -> &VerseView {
+        index["Gen"].chapters[chapter - 1]
+            .verses
+            .iter()
+            .find(|v| v.number == number)
+            .unwrap()
+    }
+
+    #[test]
+    fn reads_the_verse_text() {
+        let index = UsxParser.parse(GENESIS).unwrap();
+        assert_eq!(index["Gen"].name, "Genesis");
+        // Footnotes and the attributes of `<char style="w">` are not part of the text.
+        assert_eq!(
+            verse(&index, 1, 1).collect_string(GENESIS),
+            "In the beginning God created the heaven."
+        );
+        assert_eq!(
+            verse(&index, 1, 3).collect_string(GENESIS),
+            "Light be, and it was."
+        );
+    }
+
+    #[test]
+    fn keeps_added_words_quotes_and_poetry_lines() {
+        let index = UsxParser.parse(GENESIS).unwrap();
+        let added: Vec<&str> = verse(&index, 1, 2)
+            .inlines
+            .iter()
+            .filter_map(|inline| match inline {
+                Inline::Added(s, e) => Some(&GENESIS[*s..*e]),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(added, ["without"]);
+
+        let lines: Vec<&Inline> = verse(&index, 1, 3)
+            .inlines
+            .iter()
+            .filter(|inline| matches!(inline, Inline::LineStart { .. } | Inline::LineEnd))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                &Inline::LineStart { level: 1 },
+                &Inline::LineEnd,
+                &Inline::LineStart { level: 2 },
+                &Inline::LineEnd
+            ]
+        );
+
+        let inlines = &verse(&index, 1, 4).inlines;
+        assert!(inlines.contains(&Inline::QuoteStart {
+            who: Some("Jesus".to_string())
+        }));
+        assert!(inlines.contains(&Inline::QuoteEnd));
+    }
+
+    #[test]
+    fn drops_chapters_without_verses() {
+        let index = UsxParser.parse(GENESIS).unwrap();
+        let chapters: Vec<usize> = index["Gen"].chapters.iter().map(|c| c.number).collect();
+        assert_eq!(chapters, [1]);
+    }
+
+    #[test]
+    fn keeps_entity_references_in_verse_text() {
+        let bible = bible_from(
+            "entities.usx",
+            r#"<usx version="3.0"><book code="JHN" style="id">- Test</book>
+<chapter number="1" style="c" sid="JHN 1"/>
+<para style="p"><verse number="1" style="v" sid="JHN 1:1"/>Salt &amp; light, not &lt;x&gt; <char style="add">&#x2019;s</char><verse eid="JHN 1:1"/></para>
+<chapter eid="JHN 1"/></usx>"#,
+        );
+        let verse = bible
+            .get_chapter("John", 1)
+            .unwrap()
+            .get_verses()
+            .next()
+            .unwrap();
+        assert_eq!(
+            verse.collect_string(bible.get_raw_data()),
+            "Salt & light, not <x> \u{2019}s"
+        );
+    }
+}
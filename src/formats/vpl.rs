@@ -0,0 +1,211 @@
+use crate::bible::Book;
+use crate::canon;
+use crate::formats::{BibleParser, IndexBuilder};
+use crate::prelude::*;
+use crate::translations::{TranslationInfo, file_stem};
+use indexmap::IndexMap;
+use std::io::BufRead;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Plain text with a verse per line, e.g. "Gen 1:1 In the beginning...".  Books can be named
+/// like in references, including USFM codes ("GEN 1:1").  Lines starting with `#` are comments.
+pub struct VplParser;
+
+impl BibleParser for VplParser {
+    /// There is no metadata, apart from a comment on the first line that is taken as the title.
+    fn info(&self, source: &mut dyn BufRead, path: &Path) -> Result<TranslationInfo> {
+        let name = file_stem(path).to_uppercase();
+        let mut first = String::new();
+        source.read_line(&mut first)?;
+        let title = first
+            .strip_prefix('#')
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .map_or_else(|| name.clone(), str::to_string);
+
+        Ok(TranslationInfo {
+            title,
+            language: String::new(),
+            rights: String::new(),
+            name,
+            versification: None,
+            path: path.to_path_buf(),
+        })
+    }
+
+    fn parse(&self, raw: &str) -> Result<IndexMap<String, Book>> {
+        info!("Building bible index");
+        let start = Instant::now();
+
+        let mut builder = IndexBuilder::default();
+        let mut book = "";
+        let mut chapter = 0;
+        let mut offset = 0;
+        for line in raw.split_inclusive('\n') {
+            let line_start = offset;
+            offset += line.len();
+            let Some(verse) = Line::parse(line) else {
+                if !is_comment(line) {
+                    warn!("Skipping line {line:?}");
+                }
+                continue;
+            };
+
+            if verse.book != book {
+                builder.book(Some(verse.book));
+                (book, chapter) = (verse.book, 0);
+            }
+            if verse.chapter != chapter {
+                builder.chapter(verse.chapter);
+                chapter = verse.chapter;
+            }
+            builder.verse(verse.verse);
+            let text = line_start + verse.text_start;
+            builder.text(raw, text, line_start + line.trim_end().len(), false);
+        }
+
+        let index = builder.finish();
+        info!("Built index in {:?}", start.elapsed());
+        Ok(index)
+    }
+}
+
+/// Whether the first line that is not a comment is a verse.
+pub fn is_vpl(text: &str) -> bool {
+    text.lines()
+        .find(|line| !is_comment(line))
+        .and_then(Line::parse)
+        .is_some()
+}
+
+fn is_comment(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A line of the file.
+struct Line {
+    /// OSIS ID of the book.
+    book: &'static str,
+    chapter: usize,
+    verse: usize,
+    /// Where the text starts in the line.
+    text_start: usize,
+}
+
+impl Line {
+    /// "1 John 3:16 For..." -> the book, chapter and verse.  The book is everything before the
+    /// first word that looks like "3:16".
+    fn parse(line: &str) -> Option<Line> {
+        let mut words = line
+            .split_whitespace()
+            .map(|word| (word.as_ptr() as usize - line.as_ptr() as usize, word));
+        let (position, reference) = words.find(|(_, word)| word.contains(':'))?;
+        let (chapter, verse) = reference.split_once(':')?;
+
+        let name = &line[..position];
+        let book = canon::find_book(name)
+            .map(|b| b.osis_id)
+            .or_else(|| canon::osis_id_from_usfm(name.trim()))?;
+        let text = &line[position + reference.len()..];
+        Some(Line {
+            book,
+            chapter: chapter.parse().ok()?,
+            verse: verse.parse().ok()?,
+            text_start: line.len() - text.trim_start().len(),
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::Format;
+
+    const VPL: &str = "# Test Bible\n\
+        \n\
+        Gen 1:1 In the beginning God created the heaven and the earth.\n\
+        GEN 1:2 And the earth was without form.\n\
+        Song of Solomon 2:1 I am the rose of Sharon.\n\
+        SNG 2:2 As the lily among thorns.\n\
+        1 John 3:16 Hereby perceive we the love of God.\r\n\
+        not a verse\n";
+
+    fn location(line: &str) -> Option<(&'static str, usize, usize, &str)> {
+        let parsed = Line::parse(line)?;
+        Some((
+            parsed.book,
+            parsed.chapter,
+            parsed.verse,
+            &line[parsed.text_start..],
+        ))
+    }
+
+    #[test]
+    fn sniffs_a_verse_per_line() {
+        assert!(is_vpl(VPL));
+        assert!(matches!(Format::sniff(VPL), Ok(Format::Vpl)));
+        assert!(!is_vpl("# Comment\nIn the beginning\n"));
+        assert!(!is_vpl(r#"<?xml version="1.0"?><XMLBIBLE>"#));
+        assert!(matches!(
+            Format::sniff("\\id GEN\n\\c 1\n"),
+            Ok(Format::Usfm)
+        ));
+    }
+
+    #[test]
+    fn parses_book_names_and_usfm_codes() {
+        assert_eq!(
+            location("Gen 1:1 In the beginning"),
+            Some(("Gen", 1, 1, "In the beginning"))
+        );
+        assert_eq!(location("GEN 1:2 And"), Some(("Gen", 1, 2, "And")));
+        assert_eq!(
+            location("Song of Solomon 2:1  I am"),
+            Some(("Song", 2, 1, "I am"))
+        );
+        assert_eq!(location("SNG 2:2 As"), Some(("Song", 2, 2, "As")));
+        assert_eq!(
+            location("1 John 3:16 Hereby"),
+            Some(("1John", 3, 16, "Hereby"))
+        );
+        assert_eq!(location("Unknown 1:1 text"), None);
+        assert_eq!(location("Gen one:1 text"), None);
+    }
+
+    #[test]
+    fn reads_a_verse_per_line() {
+        let index = VplParser.parse(VPL).unwrap();
+        let books: Vec<&str> = index.keys().map(String::as_str).collect();
+        assert_eq!(books, ["Gen", "Song", "1John"]);
+
+        let verses = |book: &str, chapter: usize| -> Vec<(usize, String)> {
+            let chapter = index[book]
+                .chapters
+                .iter()
+                .find(|c| c.number == chapter)
+                .unwrap();
+            chapter
+                .get_verses()
+                .map(|v| (v.number, v.text_ranges().map(|(s, e)| &VPL[s..e]).collect()))
+                .collect()
+        };
+        assert_eq!(
+            verses("Song", 2),
+            [
+                (1, "I am the rose of Sharon.".to_string()),
+                (2, "As the lily among thorns.".to_string())
+            ]
+        );
+        // The line break is not part of the text.
+        assert_eq!(
+            verses("1John", 3),
+            [(16, "Hereby perceive we the love of God.".to_string())]
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use crate::bible::Book;
+use crate::canon;
+use crate::formats::{BibleParser, IndexBuilder, attr, entity_range, leading_number};
+use crate::prelude::*;
+use crate::translations::{TranslationInfo, file_stem};
+use indexmap::IndexMap;
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+use std::io::BufRead;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Zefania XML, with a `<BIBLEBOOK>`, `<CHAPTER>` and `<VERS>` container for everything.
+pub struct ZefaniaParser;
+
+/// Elements whose text is not part of the verses.
+const SKIPPED: [&[u8]; 6] = [b"CAPTION", b"NOTE", b"REMARK", b"XREF", b"MEDIA", b"PROLOG"];
+
+impl BibleParser for ZefaniaParser {
+    /// Reads the `<INFORMATION>`, which comes before the first book.
+    fn info(&self, source: &mut dyn BufRead, path: &Path) -> Result<TranslationInfo> {
+        let mut reader = Reader::from_reader(source);
+        let mut buf = Vec::new();
+        let mut bible_name = None;
+        let mut in_information = false;
+        let mut field: Option<Vec<u8>> = None;
+        let mut fields: Vec<(Vec<u8>, String)> = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(ref e)) if is(e, b"XMLBIBLE") => {
+                    bible_name = attr(e, b"biblename");
+                }
+                Ok(Event::Start(ref e)) if is(e, b"INFORMATION") => in_information = true,
+                Ok(Event::End(ref e)) if e.name().as_ref().eq_ignore_ascii_case(b"INFORMATION") => {
+                    break;
+                }
+                Ok(Event::Start(ref e)) if in_information => {
+                    field = Some(e.name().as_ref().to_ascii_lowercase());
+                }
+                Ok(Event::Text(ref e)) => {
+                    if let Some(name) = field.take() {
+                        let value = String::from_utf8_lossy(e).trim().to_string();
+                        fields.push((name, value));
+                    }
+                }
+                Ok(Event::End(_)) => field = None,
+                // Not every file has the information, don't read the whole text looking for it.
+                Ok(Event::Start(ref e)) if is(e, b"BIBLEBOOK") => break,
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(Error::BibleIndex(e.to_string())),
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        let find = |name: &[u8]| {
+            fields
+                .iter()
+                .find(|(n, value)| n == name && !value.is_empty())
+                .map(|(_, value)| value.clone())
+        };
+        let name = find(b"identifier")
+            .unwrap_or_else(|| file_stem(path))
+            .to_uppercase();
+
+        Ok(TranslationInfo {
+            title: find(b"title")
+                .or(bible_name)
+                .unwrap_or_else(|| name.clone()),
+            language: find(b"language").unwrap_or_default(),
+            rights: find(b"rights").unwrap_or_default(),
+            name,
+            versification: None,
+            path: path.to_path_buf(),
+        })
+    }
+
+    fn parse(&self, raw: &str) -> Result<IndexMap<String, Book>> {
+        info!("Building bible index");
+        let start = Instant::now();
+
+        let mut builder = IndexBuilder::default();
+        let mut reader = Reader::from_str(raw);
+        let mut buf = Vec::new();
+        let mut skipped = 0usize;
+        // Styles that are open, italics are the added words.
+        let mut styles: Vec<bool> = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(ref e)) if is(e, b"BIBLEBOOK") => {
+                    let id = attr(e, b"bnumber")
+                        .and_then(|n| canon::osis_id_from_number(leading_number(&n)?))
+                        .or_else(|| {
+                            let name = attr(e, b"bsname").or_else(|| attr(e, b"bname"))?;
+                            Some(canon::find_book(&name)?.osis_id)
+                        });
+                    if id.is_none() {
+                        warn!("Skipping unknown book {:?}", attr(e, b"bname"));
+                    }
+                    builder.book(id);
+                    if let Some(name) = attr(e, b"bname") {
+                        builder.book_name(&name);
+                    }
+                }
+                Ok(Event::Start(ref e)) if is(e, b"CHAPTER") => {
+                    if let Some(number) = attr(e, b"cnumber").and_then(|n| leading_number(&n)) {
+                        builder.chapter(number);
+                    }
+                }
+                Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) if is(e, b"VERS") => {
+                    if let Some(number) = attr(e, b"vnumber").and_then(|n| leading_number(&n)) {
+                        builder.verse(number);
+                    }
+                }
+                Ok(Event::End(ref e)) if e.name().as_ref().eq_ignore_ascii_case(b"VERS") => {
+                    builder.end_verse();
+                }
+                Ok(Event::Start(ref e)) if SKIPPED.iter().any(|name| is(e, name)) => {
+                    skipped += 1;
+                }
+                Ok(Event::End(ref e))
+                    if SKIPPED
+                        .iter()
+                        .any(|name| e.name().as_ref().eq_ignore_ascii_case(name)) =>
+                {
+                    skipped = skipped.saturating_sub(1);
+                }
+                Ok(Event::Start(ref e)) if is(e, b"STYLE") => {
+                    let italic = [b"fs".as_slice(), b"css"]
+                        .iter()
+                        .filter_map(|key| attr(e, key))
+                        .any(|style| style.contains("italic"));
+                    styles.push(italic);
+                }
+                Ok(Event::End(ref e)) if e.name().as_ref().eq_ignore_ascii_case(b"STYLE") => {
+                    styles.pop();
+                }
+                Ok(Event::Empty(ref e)) if is(e, b"BR") => builder.line_break(),
+                Ok(Event::Text(ref e)) if skipped == 0 => {
+                    let end = reader.buffer_position() as usize;
+                    let added = styles.iter().any(|italic| *italic);
+                    builder.text(raw, end - e.len(), end, added);
+                }
+                Ok(Event::GeneralRef(ref e)) if skipped == 0 => {
+                    let (start, end) = entity_range(e, reader.buffer_position());
+                    let added = styles.iter().any(|italic| *italic);
+                    builder.text(raw, start, end, added);
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(Error::BibleIndex(e.to_string())),
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        let index = builder.finish();
+        info!("Built index in {:?}", start.elapsed());
+        Ok(index)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Element names are upper case, but not every file sticks to that.
+fn is(e: &BytesStart, name: &[u8]) -> bool {
+    e.name().as_ref().eq_ignore_ascii_case(name)
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bible::Inline;
+    use crate::bible::tests::bible_from;
+    use crate::formats::Format;
+
+    const ZEFANIA: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<XMLBIBLE biblename="Test Bible" type="x-bible">
+<INFORMATION><title>Test Bible</title><identifier>TST</identifier><language>ENG</language></INFORMATION>
+<BIBLEBOOK bnumber="1" bname="Genesis"><CHAPTER cnumber="1">
+<CAPTION>The creation</CAPTION>
+<VERS vnumber="1">In the beginning God created the heaven<NOTE>Or, the heavens</NOTE> and the earth.</VERS>
+<VERS vnumber="2">And the earth was <STYLE fs="italic">without</STYLE> form.</VERS>
+</CHAPTER></BIBLEBOOK>
+<BIBLEBOOK bname="Song of Solomon"><CHAPTER cnumber="2">
+<VERS vnumber="1">I am the rose of Sharon.</VERS>
+</CHAPTER></BIBLEBOOK>
+<biblebook bsname="1Jn" bname="Erste Johannes"><chapter cnumber="3">
+<vers vnumber="16">Hereby perceive we the love of God.</vers>
+</chapter></biblebook>
+</XMLBIBLE>"#;
+
+    #[test]
+    fn sniffs_zefania() {
+        assert!(matches!(Format::sniff(ZEFANIA), Ok(Format::Zefania)));
+        let info = ZefaniaParser
+            .info(&mut ZEFANIA.as_bytes(), Path::new("test.xml"))
+            .unwrap();
+        assert_eq!(info.name, "TST");
+        assert_eq!(info.title, "Test Bible");
+        assert_eq!(info.language, "ENG");
+    }
+
+    #[test]
+    fn reads_books_by_number_and_by_name() {
+        let index = ZefaniaParser.parse(ZEFANIA).unwrap();
+        let books: Vec<&str> = index.keys().map(String::as_str).collect();
+        assert_eq!(books, ["Gen", "Song", "1John"]);
+        // The name in the file is kept, whatever the book was found by.
+        assert_eq!(index["1John"].name, "Erste Johannes");
+
+        let text = |book: &str, chapter: usize, verse: usize| {
+            index[book]
+                .chapters
+                .iter()
+                .find(|c| c.number == chapter)
+                .and_then(|c| c.get_verses().find(|v| v.number == verse))
+                .map(|v| v.collect_string(ZEFANIA))
+        };
+        // Captions and notes are not part of the text.
+        assert_eq!(
+            text("Gen", 1, 1).as_deref(),
+            Some("In the beginning God created the heaven and the earth.")
+        );
+        assert_eq!(
+            text("Song", 2, 1).as_deref(),
+            Some("I am the rose of Sharon.")
+        );
+        assert_eq!(
+            text("1John", 3, 16).as_deref(),
+            Some("Hereby perceive we the love of God.")
+        );
+
+        let added: Vec<&str> = index["Gen"].chapters[0].verses[1]
+            .inlines
+            .iter()
+            .filter_map(|inline| match inline {
+                Inline::Added(s, e) => Some(&ZEFANIA[*s..*e]),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(added, ["without"]);
+    }
+
+    #[test]
+    fn keeps_entity_references_in_verse_text() {
+        let bible = bible_from(
+            "entities.xml",
+            r#"<XMLBIBLE biblename="Test"><BIBLEBOOK bnumber="43"><CHAPTER cnumber="1">
+<VERS vnumber="1">Salt &amp; light, not &lt;x&gt; <STYLE fs="italic">&#x2019;s</STYLE></VERS>
+</CHAPTER></BIBLEBOOK></XMLBIBLE>"#,
+        );
+        let verse = bible
+            .get_chapter("John", 1)
+            .unwrap()
+            .get_verses()
+            .next()
+            .unwrap();
+        assert_eq!(
+            verse.collect_string(bible.get_raw_data()),
+            "Salt & light, not <x> \u{2019}s"
+        );
+    }
+
+    #[test]
+    fn reads_the_additions_to_daniel() {
+        let bible = bible_from(
+            "apocrypha.xml",
+            r#"<XMLBIBLE biblename="Test"><BIBLEBOOK bnumber="74"><CHAPTER cnumber="1">
+<VERS vnumber="1">There dwelt a man in Babylon.</VERS>
+</CHAPTER></BIBLEBOOK></XMLBIBLE>"#,
+        );
+        assert!(bible.get_chapter("AddDan", 1).is_ok());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::canon;
+use crate::crossrefs::{Passage, osis_range};
+use crate::prelude::*;
+use crate::store::{Store, Stored};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Verses, or words of a verse, highlighted in one of the categories of the settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Highlight {
+    /// OSIS reference in the numbering of the KJV, e.g. "John.3.16" or "John.3.16-John.3.18".
+    pub reference: String,
+    /// Name of the category, see `HighlightCategory`.
+    pub category: String,
+    /// Only these words of the verse.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub words: Option<WordRange>,
+}
+
+/// Words of a verse, which are only the same words in the translation they were highlighted in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WordRange {
+    pub translation: String,
+    /// Indices of the words in the verse, the end is exclusive.
+    pub start: usize,
+    pub end: usize,
+}
+
+impl WordRange {
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+impl Highlight {
+    /// The (book, (chapter, verse), (chapter, verse)) of the highlight.
+    pub fn range(&self) -> Option<Passage<'_>> {
+        osis_range(&self.reference)
+    }
+
+    /// Whether the highlight is of any of the verses, in the numbering of the KJV.
+    fn overlaps(&self, book: &str, start: (usize, usize), end: (usize, usize)) -> bool {
+        self.range()
+            .is_some_and(|(b, s, e)| b == book && s <= end && start <= e)
+    }
+
+    fn position(&self) -> (usize, (usize, usize), (usize, usize)) {
+        match self.range() {
+            Some((book, start, end)) => (canon::canonical_position(book), start, end),
+            None => (usize::MAX, (0, 0), (0, 0)),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl Stored for Highlight {
+    const NAME: &'static str = "highlights";
+    const VERSION: u32 = 1;
+
+    fn file() -> Option<PathBuf> {
+        highlights_file()
+    }
+}
+
+/// The highlights of the user in canon order, saved after every change.
+pub type Highlights = Store<Highlight>;
+
+impl Store<Highlight> {
+    /// Adds the highlight, replacing the one of the same verses or words so that highlighting
+    /// again changes the category.
+    pub fn add(&mut self, highlight: Highlight) -> Result<()> {
+        self.change(|highlights| {
+            highlights
+                .retain(|h| (&h.reference, &h.words) != (&highlight.reference, &highlight.words));
+            highlights.push(highlight);
+            // Stable, so the later of overlapping highlights stays on top.
+            highlights.sort_by_key(Highlight::position);
+        })
+    }
+
+    /// Removes every highlight of the verses, including the ones of their words.  Returns how
+    /// many were removed.
+    pub fn clear(
+        &mut self,
+        book: &str,
+        start: (usize, usize),
+        end: (usize, usize),
+    ) -> Result<usize> {
+        if !self.iter().any(|h| h.overlaps(book, start, end)) {
+            return Ok(0);
+        }
+        self.change(|highlights| {
+            let count = highlights.len();
+            highlights.retain(|h| !h.overlaps(book, start, end));
+            count - highlights.len()
+        })
+    }
+
+    pub fn remove(&mut self, highlight: &Highlight) -> Result<()> {
+        self.change(|highlights| highlights.retain(|h| h != highlight))
+    }
+
+    /// The highlights of the verses of a book.
+    pub fn in_book<'a>(&'a self, book: &'a str) -> impl Iterator<Item = &'a Highlight> {
+        self.iter()
+            .filter(move |h| h.range().is_some_and(|(b, _, _)| b == book))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlight(reference: &str, category: &str) -> Highlight {
+        Highlight {
+            reference: reference.to_string(),
+            category: category.to_string(),
+            words: None,
+        }
+    }
+
+    #[test]
+    fn keeps_the_highlights_in_canon_order() {
+        let mut highlights = Highlights::in_memory();
+        highlights.add(highlight("John.3.16", "Promise")).unwrap();
+        highlights.add(highlight("Gen.1.1", "Promise")).unwrap();
+        // Highlighting again changes the category.
+        highlights.add(highlight("John.3.16", "Love")).unwrap();
+        let all: Vec<_> = highlights
+            .iter()
+            .map(|h| (h.reference.as_str(), h.category.as_str()))
+            .collect();
+        assert_eq!(all, [("Gen.1.1", "Promise"), ("John.3.16", "Love")]);
+
+        assert_eq!(highlights.clear("John", (3, 1), (3, 36)).unwrap(), 1);
+        assert_eq!(highlights.clear("John", (3, 1), (3, 36)).unwrap(), 0);
+        assert_eq!(highlights.len(), 1);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::prelude::*;
+use ratatui::symbols::border;
+use serde::{Deserialize, Deserializer, de};
+
+/// The glyph of `icon` in the icon set of the settings.
+pub fn icon(icon: Icon) -> &'static str {
+    icon.glyph(settings().icon_set)
+}
+
+/// Borders of the panels, rounded unless only ASCII can be shown.
+pub fn border_set() -> border::Set<'static> {
+    match settings().icon_set {
+        IconSet::NerdFont | IconSet::Unicode => border::ROUNDED,
+        IconSet::Ascii => ASCII_BORDER,
+    }
+}
+
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Which glyphs the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum IconSet {
+    /// Icons of a patched Nerd Font.
+    NerdFont,
+    /// Plain Unicode symbols that most fonts have.
+    Unicode,
+    /// For terminals without Unicode, e.g. with the C locale.
+    Ascii,
+}
+
+impl IconSet {
+    /// Guesses the icon set from the environment.  Nerd Fonts can not be detected, so they are
+    /// only used in terminals that bundle the symbols.
+    pub fn detect() -> IconSet {
+        if let Ok(set) = std::env::var(ICONS_ENV) {
+            match set.parse() {
+                Ok(set) => return set,
+                Err(e) => warn!("Ignoring {ICONS_ENV}: {e}"),
+            }
+        }
+
+        let term = std::env::var("TERM").unwrap_or_default();
+        if matches!(term.as_str(), "dumb" | "ansi") || term.starts_with("vt") {
+            return IconSet::Ascii;
+        }
+
+        // The first locale variable that is set wins, like for `setlocale`.
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty());
+        if let Some(locale) = locale {
+            let locale = locale.to_lowercase();
+            if !locale.contains("utf-8") && !locale.contains("utf8") {
+                return IconSet::Ascii;
+            }
+        } else if cfg!(unix) {
+            return IconSet::Ascii;
+        }
+
+        let program = std::env::var("TERM_PROGRAM").unwrap_or_default();
+        if matches!(program.as_str(), "WezTerm" | "ghostty")
+            || matches!(term.as_str(), "xterm-kitty" | "xterm-ghostty" | "wezterm")
+        {
+            return IconSet::NerdFont;
+        }
+        IconSet::Unicode
+    }
+}
+
+impl std::str::FromStr for IconSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        <IconSet as clap::ValueEnum>::from_str(s, true)
+            .map_err(|_| format!("unknown icon set {s:?}, expected nerd-font, unicode or ascii"))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The `icons` setting, an icon set or "auto".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IconsSetting {
+    /// Detected from the environment.
+    #[default]
+    Auto,
+    Set(IconSet),
+}
+
+impl IconsSetting {
+    pub fn icon_set(self) -> IconSet {
+        match self {
+            IconsSetting::Auto => IconSet::detect(),
+            IconsSetting::Set(set) => set,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for IconsSetting {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        match s.as_str() {
+            "auto" => Ok(IconsSetting::Auto),
+            _ => s.parse().map(IconsSetting::Set).map_err(de::Error::custom),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Every icon of the interface, see `icon`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Icon {
+    /// In front of the name in the footer.
+    Logo,
+    /// In front of the selected item of a list.
+    Selected,
+    /// Marks the translations that are open.
+    Loaded,
+    /// Text that was cut off.
+    Ellipsis,
+    /// Between the translations of a parallel verse.
+    Separator,
+    /// A pixel of the big logo on the dashboard.
+    Pixel,
+    /// A unit of the bars of a histogram.
+    Bar,
+    /// After the number of verses with a note, in place of a whitespace.
+    Note,
+    /// In front of the items of a list in a note.
+    Bullet,
+}
+
+impl Icon {
+    /// The one table of glyphs, in the order nerd font, unicode, ascii.  An empty glyph leaves
+    /// the icon out.
+    fn glyphs(self) -> [&'static str; 3] {
+        match self {
+            Icon::Logo => ["\u{eebe}", "✝", ""],
+            Icon::Selected => ["\u{f0da}", "▸", ">"],
+            Icon::Loaded => ["\u{f00c}", "•", "*"],
+            Icon::Ellipsis => ["…", "…", "..."],
+            Icon::Separator => ["—", "—", "-"],
+            Icon::Pixel => ["█", "█", "#"],
+            Icon::Bar => ["■", "■", "="],
+            Icon::Note => ["\u{f040}", "✎", "*"],
+            Icon::Bullet => ["•", "•", "-"],
+        }
+    }
+
+    pub fn glyph(self, set: IconSet) -> &'static str {
+        let [nerd_font, unicode, ascii] = self.glyphs();
+        match set {
+            IconSet::NerdFont => nerd_font,
+            IconSet::Unicode => unicode,
+            IconSet::Ascii => ascii,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Settings {
+        icons: IconsSetting,
+    }
+
+    fn parse(toml: &str) -> std::result::Result<IconsSetting, toml::de::Error> {
+        toml::from_str::<Settings>(toml).map(|s| s.icons)
+    }
+
+    #[test]
+    fn parses_icon_sets() {
+        assert_eq!(parse(r#"icons = "auto""#).unwrap(), IconsSetting::Auto);
+        assert_eq!(
+            parse(r#"icons = "nerd-font""#).unwrap(),
+            IconsSetting::Set(IconSet::NerdFont)
+        );
+        assert_eq!(
+            parse(r#"icons = "ASCII""#).unwrap(),
+            IconsSetting::Set(IconSet::Ascii)
+        );
+        assert!(parse(r#"icons = "emoji""#).is_err());
+        assert!(parse("icons = true").is_err());
+    }
+
+    #[test]
+    fn looks_up_glyphs_in_the_icon_set() {
+        assert_eq!(Icon::Selected.glyph(IconSet::NerdFont), "\u{f0da}");
+        assert_eq!(Icon::Selected.glyph(IconSet::Unicode), "▸");
+        assert_eq!(Icon::Ellipsis.glyph(IconSet::Ascii), "...");
+        for icon in [
+            Icon::Logo,
+            Icon::Selected,
+            Icon::Loaded,
+            Icon::Separator,
+            Icon::Pixel,
+        ] {
+            assert!(icon.glyph(IconSet::Ascii).is_ascii(), "{icon:?}");
+        }
+    }
+}
@@ -0,0 +1,559 @@
+use crate::filesystem::{Compression, book_files};
+use crate::formats::{Format, attr, has_attr_val};
+use crate::prelude::*;
+use crate::translations::{TranslationInfo, TranslationRegistry, file_stem};
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+use std::fmt;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The file can be read, but something might be missing or misplaced.
+    Warning,
+    /// The file would not be read correctly.
+    Error,
+}
+
+/// A problem in a file, at a 1-based line and column.
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub severity: Severity,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(
+            f,
+            "{}:{}: {severity}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+/// The outcome of validating a file before importing it.
+#[derive(Debug)]
+pub struct ImportReport {
+    pub path: PathBuf,
+    pub format: Format,
+    pub compression: Compression,
+    pub books: usize,
+    pub chapters: usize,
+    pub verses: usize,
+    pub issues: Vec<Issue>,
+}
+
+impl ImportReport {
+    pub fn errors(&self) -> usize {
+        self.count(Severity::Error)
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.count(Severity::Warning)
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == severity)
+            .count()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Reads and validates the bible file or directory at `path`, which can be anywhere.  Only fails
+/// when the file can not be read or the format is not supported, problems in the content are in
+/// the report.
+pub fn validate(path: &Path) -> Result<ImportReport> {
+    let (format, compression) = Format::sniff_file(path)?;
+    let raw = format.parser().load(path)?;
+    let mut report = ImportReport {
+        path: path.to_path_buf(),
+        format,
+        compression,
+        books: 0,
+        chapters: 0,
+        verses: 0,
+        issues: Vec::new(),
+    };
+
+    match format {
+        Format::Osis => {
+            let mut validator = OsisValidator::new(&raw);
+            validator.run();
+            report.books = validator.books;
+            report.chapters = validator.chapters;
+            report.verses = validator.verses;
+            report.issues = validator.issues;
+        }
+        // Verses end where the next one starts, there are no milestones to get wrong.
+        Format::Usfm | Format::Usx | Format::Zefania | Format::Vpl | Format::Sword => {
+            let index = format.parser().parse(&raw)?;
+            report.books = index.len();
+            report.chapters = index.values().map(|b| b.chapters.len()).sum();
+            report.verses = index
+                .values()
+                .flat_map(|b| &b.chapters)
+                .map(|c| c.verses.len())
+                .sum();
+            if report.verses == 0 {
+                report.issues.push(Issue {
+                    severity: Severity::Error,
+                    line: 1,
+                    column: 1,
+                    message: "no verses".to_string(),
+                });
+            }
+        }
+    }
+
+    info!(
+        "Validated {path:?}: {} books, {} verses, {} issues",
+        report.books,
+        report.verses,
+        report.issues.len()
+    );
+    Ok(report)
+}
+
+/// Copies a validated file into the translations directory.  A translation of the same name is
+/// only replaced when `force` is set.
+pub fn install(report: &ImportReport, force: bool) -> Result<TranslationInfo> {
+    if report.errors() > 0 {
+        return Err(Error::InvalidTranslation(
+            report.path.clone(),
+            report.errors(),
+        ));
+    }
+    let dir = translations_dir()
+        .ok_or_else(|| Error::IoError(std::io::Error::other("no data directory")))?;
+    let info = TranslationInfo::from_file(&report.path)?;
+
+    let installed = TranslationRegistry::scan(std::slice::from_ref(&dir));
+    if let Ok(existing) = installed.get(&info.name) {
+        let same_file = existing.path.canonicalize().ok() == report.path.canonicalize().ok();
+        if !force || same_file {
+            return Err(Error::TranslationInstalled(
+                info.name.clone(),
+                existing.path.clone(),
+            ));
+        }
+        match existing.path.is_dir() {
+            true => std::fs::remove_dir_all(&existing.path)?,
+            false => std::fs::remove_file(&existing.path)?,
+        }
+    }
+
+    // The extension is what the translations are found by, whatever the file was called.
+    let stem = match file_stem(&report.path) {
+        stem if stem.is_empty() => info.name.to_lowercase(),
+        stem => stem,
+    };
+    let extension = match report.format {
+        Format::Osis => "osis.xml",
+        Format::Usfm => "usfm",
+        Format::Usx => "usx",
+        Format::Zefania => "zefania.xml",
+        Format::Vpl => "vpl",
+        // The data is next to the conf file, in the layout of the SWORD library.
+        Format::Sword => {
+            return Err(Error::UnsupportedFormat(
+                "SWORD modules, they are read where they are installed".to_string(),
+            ));
+        }
+    };
+
+    std::fs::create_dir_all(&dir)?;
+    let target = match report.path.is_dir() {
+        // A file per book, they are copied as they are.
+        true => {
+            let target = dir.join(stem);
+            std::fs::create_dir_all(&target)?;
+            for file in book_files(&report.path)? {
+                let name = file.file_name().expect("Listed from the directory");
+                std::fs::copy(&file, target.join(name))?;
+            }
+            target
+        }
+        false => {
+            let target = match report.compression {
+                Compression::None => dir.join(format!("{stem}.{extension}")),
+                Compression::Xz => dir.join(format!("{stem}.{extension}.xz")),
+            };
+            std::fs::copy(&report.path, &target)?;
+            target
+        }
+    };
+    info!(
+        "Installed {} from {:?} to {target:?}",
+        info.name, report.path
+    );
+    TranslationInfo::from_file(&target)
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Checks the structure of an OSIS file the way `Bible` reads it.
+struct OsisValidator<'a> {
+    raw: &'a str,
+    /// Offsets where the lines start, for the line and column of the issues.
+    line_starts: Vec<usize>,
+    issues: Vec<Issue>,
+    books: usize,
+    chapters: usize,
+    verses: usize,
+
+    /// (element, sID, offset) of the milestones that have not been closed yet.
+    milestones: Vec<(String, String, usize)>,
+    /// osisID -> offset where it was first used.
+    ids: HashMap<String, usize>,
+    /// Whether each open div is a book.
+    divs: Vec<bool>,
+    /// (osisID, offset, verses) of the current book.
+    book: Option<(String, usize, usize)>,
+    /// osisID of the current chapter.
+    chapter: Option<String>,
+    /// osisID of the current verse.
+    verse: Option<String>,
+    seen_work: bool,
+}
+
+impl<'a> OsisValidator<'a> {
+    fn new(raw: &'a str) -> Self {
+        Self {
+            raw,
+            line_starts: std::iter::once(0)
+                .chain(raw.match_indices('\n').map(|(i, _)| i + 1))
+                .collect(),
+            issues: Vec::new(),
+            books: 0,
+            chapters: 0,
+            verses: 0,
+            milestones: Vec::new(),
+            ids: HashMap::new(),
+            divs: Vec::new(),
+            book: None,
+            chapter: None,
+            verse: None,
+            seen_work: false,
+        }
+    }
+
+    fn run(&mut self) {
+        let mut reader = Reader::from_str(self.raw);
+        loop {
+            let offset = reader.buffer_position() as usize;
+            match reader.read_event() {
+                Ok(Event::Start(e)) => self.start(&e, offset, false),
+                Ok(Event::Empty(e)) => self.start(&e, offset, true),
+                Ok(Event::End(e)) => self.end(e.name().as_ref()),
+                Ok(Event::Eof) => break,
+                Err(e) => {
+                    let offset = reader.error_position() as usize;
+                    self.issue(Severity::Error, offset, format!("invalid XML, {e}"));
+                    return;
+                }
+                _ => {}
+            }
+        }
+
+        self.end_book();
+        for (name, id, offset) in std::mem::take(&mut self.milestones) {
+            let message = format!("<{name} sID=\"{id}\"> has no matching eID");
+            self.issue(Severity::Error, offset, message);
+        }
+        if !self.seen_work {
+            let message = "no <work> in the header, the name comes from the file name";
+            self.issue(Severity::Warning, 0, message.to_string());
+        }
+        if self.verses == 0 {
+            self.issue(Severity::Error, 0, "no verses".to_string());
+        }
+        self.issues.sort_by_key(|i| (i.line, i.column));
+    }
+
+    fn start(&mut self, e: &BytesStart, offset: usize, empty: bool) {
+        let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+        let sid = attr(e, b"sID");
+        let eid = attr(e, b"eID");
+
+        if let Some(eid) = &eid {
+            self.close_milestone(&name, eid, offset);
+            match name.as_str() {
+                "chapter" => self.chapter = None,
+                "verse" => self.verse = None,
+                _ => {}
+            }
+            return;
+        }
+        if let Some(sid) = sid {
+            if self
+                .milestones
+                .iter()
+                .any(|(n, s, _)| *n == name && *s == sid)
+            {
+                let message = format!("<{name} sID=\"{sid}\"> is already open");
+                self.issue(Severity::Error, offset, message);
+            }
+            self.milestones.push((name.clone(), sid, offset));
+        }
+
+        let osis_id = attr(e, b"osisID");
+        for id in osis_id.iter().flat_map(|ids| ids.split_whitespace()) {
+            if let Some(first) = self.ids.get(id) {
+                let (line, column) = self.line_column(*first);
+                let message = format!("duplicate osisID \"{id}\", first used at {line}:{column}");
+                self.issue(Severity::Error, offset, message);
+            } else {
+                self.ids.insert(id.to_string(), offset);
+            }
+        }
+        // Chapters are sometimes only named by an osisRef, which `Bible` also reads.
+        let osis_id = osis_id.or_else(|| attr(e, b"osisRef")).unwrap_or_default();
+
+        match name.as_str() {
+            "work" => self.seen_work = true,
+            "div" => {
+                let is_book = has_attr_val(e, b"type", b"book");
+                if is_book {
+                    self.end_book();
+                    self.books += 1;
+                    self.book = Some((osis_id, offset, 0));
+                }
+                if !empty {
+                    self.divs.push(is_book);
+                }
+            }
+            "chapter" => {
+                self.chapters += 1;
+                match &self.book {
+                    None => {
+                        let message = format!("chapter \"{osis_id}\" outside of a book");
+                        self.issue(Severity::Warning, offset, message);
+                    }
+                    Some((book, _, _))
+                        if !osis_id.is_empty() && !osis_id.starts_with(&format!("{book}.")) =>
+                    {
+                        let message = format!("chapter \"{osis_id}\" in the book \"{book}\"");
+                        self.issue(Severity::Warning, offset, message);
+                    }
+                    Some(_) => {}
+                }
+                self.chapter = Some(osis_id);
+            }
+            "verse" => {
+                self.verses += 1;
+                if let Some(open) = &self.verse {
+                    let message = format!("verse \"{osis_id}\" starts inside of \"{open}\"");
+                    self.issue(Severity::Error, offset, message);
+                }
+                match &self.chapter {
+                    None => {
+                        let message = format!("verse \"{osis_id}\" outside of a chapter");
+                        self.issue(Severity::Error, offset, message);
+                    }
+                    Some(chapter)
+                        if !chapter.is_empty() && !osis_id.starts_with(&format!("{chapter}.")) =>
+                    {
+                        let message = format!("verse \"{osis_id}\" in the chapter \"{chapter}\"");
+                        self.issue(Severity::Warning, offset, message);
+                    }
+                    Some(_) => {}
+                }
+                if let Some((_, _, verses)) = &mut self.book {
+                    *verses += 1;
+                }
+                // A milestone verse stays open until its eID.
+                if !empty || self.milestones.last().is_some_and(|(n, _, _)| n == "verse") {
+                    self.verse = Some(osis_id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, name: &[u8]) {
+        match name {
+            b"div" if self.divs.pop() == Some(true) => self.end_book(),
+            b"chapter" => self.chapter = None,
+            b"verse" => self.verse = None,
+            _ => {}
+        }
+    }
+
+    fn close_milestone(&mut self, name: &str, eid: &str, offset: usize) {
+        match self
+            .milestones
+            .iter()
+            .rposition(|(n, sid, _)| n == name && sid == eid)
+        {
+            Some(i) => _ = self.milestones.remove(i),
+            None => {
+                let message = format!("<{name} eID=\"{eid}\"> has no matching sID");
+                self.issue(Severity::Error, offset, message);
+            }
+        }
+    }
+
+    fn end_book(&mut self) {
+        if let Some((book, offset, 0)) = self.book.take() {
+            let message = format!("the book \"{book}\" has no verses");
+            self.issue(Severity::Warning, offset, message);
+        }
+        self.chapter = None;
+    }
+
+    fn issue(&mut self, severity: Severity, offset: usize, message: String) {
+        let (line, column) = self.line_column(offset);
+        self.issues.push(Issue {
+            severity,
+            line,
+            column,
+            message,
+        });
+    }
+
+    /// The 1-based line and column of the byte `offset`, like `filesystem::line_column` but
+    /// without going through the file up to it every time.
+    fn line_column(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.raw.len());
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        let start = self.line_starts[line - 1];
+        (line, self.raw[start..offset].chars().count() + 1)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The (line, column, message) of the issues in the `books` of an OSIS file with a header.
+    fn issues(books: &str) -> Vec<(usize, usize, String)> {
+        let raw = format!(
+            "<osis><osisText><header><work osisWork=\"Test\"/></header>\n{books}\n</osisText></osis>"
+        );
+        let mut validator = OsisValidator::new(&raw);
+        validator.run();
+        validator
+            .issues
+            .into_iter()
+            .map(|i| (i.line, i.column, i.message))
+            .collect()
+    }
+
+    #[test]
+    fn reports_milestones_without_a_match() {
+        assert_eq!(
+            issues(
+                r#"<div type="book" osisID="Gen"><chapter osisID="Gen.1">
+<verse sID="Gen.1.1" osisID="Gen.1.1"/>In the beginning.
+</chapter></div>"#
+            ),
+            [(
+                3,
+                1,
+                "<verse sID=\"Gen.1.1\"> has no matching eID".to_string()
+            )]
+        );
+        assert_eq!(
+            issues(
+                r#"<div type="book" osisID="Gen"><chapter osisID="Gen.1">
+<verse osisID="Gen.1.1">In the beginning.</verse>  <verse eID="Gen.1.1"/>
+</chapter></div>"#
+            ),
+            [(
+                3,
+                52,
+                "<verse eID=\"Gen.1.1\"> has no matching sID".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn reports_duplicate_ids() {
+        assert_eq!(
+            issues(
+                r#"<div type="book" osisID="Gen"><chapter osisID="Gen.1">
+<verse osisID="Gen.1.1">In the beginning.</verse>
+<verse osisID="Gen.1.1">In the beginning.</verse>
+</chapter></div>"#
+            ),
+            [(
+                4,
+                1,
+                "duplicate osisID \"Gen.1.1\", first used at 3:1".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn reports_verses_outside_of_a_chapter() {
+        assert_eq!(
+            issues(
+                r#"<div type="book" osisID="Gen">
+  <verse osisID="Gen.1.1">In the beginning.</verse>
+</div>"#
+            ),
+            [(3, 3, "verse \"Gen.1.1\" outside of a chapter".to_string())]
+        );
+    }
+
+    #[test]
+    fn reports_books_without_verses() {
+        assert_eq!(
+            issues(
+                r#"<div type="book" osisID="Gen"><chapter osisID="Gen.1">
+<verse osisID="Gen.1.1">In the beginning.</verse>
+</chapter></div>
+<div type="book" osisID="Exod"><chapter osisID="Exod.1"></chapter></div>"#
+            ),
+            [(5, 1, "the book \"Exod\" has no verses".to_string())]
+        );
+    }
+
+    #[test]
+    fn counts_columns_in_characters() {
+        assert_eq!(
+            issues(
+                r#"<div type="book" osisID="Gen"><chapter osisID="Gen.1">
+<verse osisID="Gen.1.1">Im Anfang schuf Gott.</verse>
+</chapter></div><!-- ü --><verse eID="Gen.1.2"/>"#
+            ),
+            [(
+                4,
+                27,
+                "<verse eID=\"Gen.1.2\"> has no matching sID".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn sniffs_the_format_from_the_content() {
+        let osis = "<?xml version=\"1.0\"?>\n<!-- KJV --><osis xmlns=\"x\"><osisText/></osis>";
+        assert_eq!(Format::sniff(osis).unwrap(), Format::Osis);
+        assert!(matches!(
+            Format::sniff("<html><body/></html>"),
+            Err(Error::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            Format::sniff("In the beginning God created."),
+            Err(Error::UnsupportedFormat(_))
+        ));
+
+        let xz = [0xfd, b'7', b'z', b'X', b'Z', 0x00, 0x00, 0x04];
+        assert_eq!(Compression::sniff(&xz).unwrap(), Compression::Xz);
+        assert_eq!(Compression::sniff(b"<osis>").unwrap(), Compression::None);
+        assert!(Compression::sniff(&[0x1f, 0x8b, 0x08]).is_err());
+    }
+}
@@ -0,0 +1,401 @@
+use crate::bible::strongs_number;
+use crate::filesystem::{read_text, write_atomic};
+use crate::formats::{attr, has_attr, resolve_entity};
+use crate::prelude::*;
+use crate::sword::{self, ModuleKind, SwordModule};
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+use serde::{Deserialize, Serialize};
+
+/// Version of the lexicon file, bumped when the format changes.
+const LEXICON_VERSION: u32 = 1;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An entry of a Strong's dictionary.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LexiconEntry {
+    /// Strong's number, e.g. "H7225".
+    pub number: String,
+    /// The word in Hebrew or Greek.
+    pub lemma: String,
+    pub transliteration: String,
+    pub pronunciation: String,
+    /// Where the word comes from and what it means.
+    pub definition: String,
+    /// How the word is translated in the KJV.
+    pub kjv_usage: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LexiconFile {
+    version: u32,
+    entries: Vec<LexiconEntry>,
+}
+
+/// The Strong's dictionaries that were imported, with the lexicon modules of SWORD for the
+/// numbers that are not in them.
+pub struct Lexicon {
+    /// Where the entries are saved, `None` keeps them in memory only.
+    path: Option<PathBuf>,
+    entries: HashMap<String, LexiconEntry>,
+    /// `StrongsHebrew`, `StrongsGreek` and the like.
+    modules: Vec<SwordModule>,
+}
+
+impl Lexicon {
+    /// Loads the imported entries from the data directory.
+    pub fn load() -> Result<Lexicon> {
+        match lexicon_file() {
+            Some(path) => Self::from_file(path),
+            None => {
+                warn!("No data directory, imported lexicons will not be saved");
+                Ok(Self::in_memory())
+            }
+        }
+    }
+
+    /// A lexicon that is not saved, used when the file can not be read so it does not get
+    /// overwritten.
+    pub fn in_memory() -> Lexicon {
+        Lexicon {
+            path: None,
+            entries: HashMap::new(),
+            modules: Self::sword_modules(),
+        }
+    }
+
+    /// Loads the entries from `path`, there are none when it does not exist yet.
+    pub fn from_file(path: PathBuf) -> Result<Lexicon> {
+        let start = Instant::now();
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                let file: LexiconFile = serde_json::from_str(&contents)?;
+                if file.version > LEXICON_VERSION {
+                    let name = path.display().to_string();
+                    return Err(Error::UnsupportedVersion(name, file.version));
+                }
+                file.entries
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        info!(
+            "Loaded {} lexicon entries from {path:?} in {:?}",
+            entries.len(),
+            start.elapsed()
+        );
+
+        Ok(Lexicon {
+            path: Some(path),
+            entries: entries
+                .into_iter()
+                .map(|entry| (entry.number.clone(), entry))
+                .collect(),
+            modules: Self::sword_modules(),
+        })
+    }
+
+    /// The SWORD lexicons that are keyed by Strong's number.
+    fn sword_modules() -> Vec<SwordModule> {
+        sword::discover()
+            .into_iter()
+            .filter(|m| m.kind == ModuleKind::Lexicon)
+            .filter(|m| {
+                m.conf
+                    .entries
+                    .iter()
+                    .any(|(k, v)| k == "Feature" && matches!(v.as_str(), "HebrewDef" | "GreekDef"))
+            })
+            .collect()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut entries: Vec<LexiconEntry> = self.entries.values().cloned().collect();
+        entries.sort_by_key(|e| (e.number[..1].to_string(), e.number[1..].parse().unwrap_or(0)));
+        let file = LexiconFile {
+            version: LEXICON_VERSION,
+            entries,
+        };
+        write_atomic(path, &serde_json::to_string(&file)?)?;
+        debug!("Saved {} lexicon entries to {path:?}", self.entries.len());
+        Ok(())
+    }
+
+    /// Reads a Strong's dictionary and saves its entries, replacing the ones of the same numbers.
+    /// Returns the number of entries that were read.
+    pub fn import(&mut self, path: &Path) -> Result<usize> {
+        let entries = read_dictionary(&read_text(path)?)?;
+        if entries.is_empty() {
+            return Err(Error::UnsupportedFormat(format!(
+                "{path:?} does not have any Strong's entries"
+            )));
+        }
+        let count = entries.len();
+        for entry in entries {
+            self.entries.insert(entry.number.clone(), entry);
+        }
+        self.save()?;
+        info!("Imported {count} lexicon entries from {path:?}");
+        Ok(count)
+    }
+
+    /// Looks up a Strong's number like "H7225" or "G3056", in the imported entries first.
+    pub fn get(&self, number: &str) -> Option<LexiconEntry> {
+        let number = strongs_number(number)?;
+        if let Some(entry) = self.entries.get(&number) {
+            return Some(entry.clone());
+        }
+
+        // The entries of the modules are only text, without the parts.
+        let feature = match number.starts_with('H') {
+            true => "HebrewDef",
+            false => "GreekDef",
+        };
+        self.modules
+            .iter()
+            .filter(|m| m.conf.entries.iter().any(|(k, v)| k == "Feature" && v == feature))
+            .find_map(|module| match module.lexicon_entry(&number) {
+                Ok(entry) => entry.map(|text| LexiconEntry {
+                    number: number.clone(),
+                    definition: sword::plain_text(module.markup, &text),
+                    ..LexiconEntry::default()
+                }),
+                Err(e) => {
+                    warn!("Failed to look up {number} in {}: {e}", module.name);
+                    None
+                }
+            })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Where an entry is being read from.
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Derivation,
+    Definition,
+    Usage,
+}
+
+/// Reads the entries of a Strong's dictionary in XML, either the OSIS dictionary of the Open
+/// Scriptures Hebrew Bible (`<div type="entry">` with `<note>`s) or the `<entry>` files of the
+/// Greek dictionary (`<strongs_def>`, `<kjv_def>`).
+pub fn read_dictionary(text: &str) -> Result<Vec<LexiconEntry>> {
+    let mut reader = Reader::from_str(text);
+    let mut entries = Vec::new();
+    let mut entry: Option<LexiconEntry> = None;
+    // The derivation is put before the definition, once the entry is complete.
+    let mut derivation = String::new();
+    let mut field: Option<Field> = None;
+    // Text of references like `<w src="H7218">7218</w>` is replaced by the number.
+    let mut in_reference = false;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| Error::BibleIndex(e.to_string()))?;
+        match event {
+            Event::Start(ref e) if is_entry(e) => {
+                entry = Some(LexiconEntry::default());
+                derivation.clear();
+                if let Some(number) = attr(e, b"n").or_else(|| attr(e, b"strongs")) {
+                    entry.as_mut().expect("Just set").number = number;
+                }
+            }
+            Event::End(ref e) if matches!(e.name().as_ref(), b"div" | b"entry") => {
+                let Some(mut done) = entry.take() else {
+                    continue;
+                };
+                let definition = std::mem::take(&mut done.definition);
+                done.definition = [derivation.trim(), definition.trim()]
+                    .iter()
+                    .filter(|part| !part.is_empty())
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                done.kjv_usage = done.kjv_usage.trim().trim_start_matches(":--").to_string();
+                match strongs_number(&done.number) {
+                    Some(number) => entries.push(LexiconEntry { number, ..done }),
+                    None => debug!("Skipping lexicon entry {:?}", done.number),
+                }
+                field = None;
+            }
+            Event::Start(ref e) | Event::Empty(ref e) if entry.is_some() => {
+                let current = entry.as_mut().expect("Checked by guard");
+                match e.name().as_ref() {
+                    // The headword of the OSIS dictionary.
+                    b"w" if has_attr(e, b"ID") => {
+                        current.number = attr(e, b"ID").unwrap_or_default();
+                        current.lemma = attr(e, b"lemma").unwrap_or_default();
+                        current.transliteration = attr(e, b"xlit").unwrap_or_default();
+                        current.pronunciation = attr(e, b"pron").unwrap_or_default();
+                        in_reference = matches!(event, Event::Start(_));
+                    }
+                    b"w" if field.is_some() => {
+                        if let Some(src) = attr(e, b"src") {
+                            push_text(current, &mut derivation, field, &src);
+                            in_reference = matches!(event, Event::Start(_));
+                        }
+                    }
+                    b"note" => {
+                        field = match attr(e, b"type").as_deref() {
+                            Some("exegesis") => Some(Field::Derivation),
+                            Some("explanation") => Some(Field::Definition),
+                            Some("translation") => Some(Field::Usage),
+                            _ => None,
+                        };
+                    }
+                    // Words quoted in the definition.
+                    b"greek" | b"hebrew" if field.is_some() => {
+                        let word = attr(e, b"unicode").unwrap_or_default();
+                        push_text(current, &mut derivation, field, &format!(" {word} "));
+                    }
+                    // The headword of the Greek dictionary, its number has no prefix.
+                    name @ (b"greek" | b"hebrew") => {
+                        let prefix = if name == b"greek" { "G" } else { "H" };
+                        let digits = current.number.trim_start_matches(['G', 'H', 'g', 'h']);
+                        current.number = format!("{prefix}{digits}");
+                        current.lemma = attr(e, b"unicode").unwrap_or_default();
+                        current.transliteration = attr(e, b"translit").unwrap_or_default();
+                    }
+                    b"pronunciation" => {
+                        current.pronunciation = attr(e, b"strongs").unwrap_or_default();
+                    }
+                    b"strongs_derivation" => field = Some(Field::Derivation),
+                    b"strongs_def" => field = Some(Field::Definition),
+                    b"kjv_def" => field = Some(Field::Usage),
+                    b"strongsref" if field.is_some() => {
+                        let prefix = match attr(e, b"language").as_deref() {
+                            Some("HEBREW") => "H",
+                            _ => "G",
+                        };
+                        let number = attr(e, b"strongs").unwrap_or_default();
+                        if let Some(number) = strongs_number(&format!("{prefix}{number}")) {
+                            push_text(current, &mut derivation, field, &number);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(ref e) if e.name().as_ref() == b"w" => in_reference = false,
+            Event::End(ref e)
+                if matches!(
+                    e.name().as_ref(),
+                    b"note" | b"strongs_derivation" | b"strongs_def" | b"kjv_def"
+                ) =>
+            {
+                field = None;
+            }
+            Event::Text(ref e) if !in_reference => {
+                if let Some(current) = entry.as_mut() {
+                    let text = String::from_utf8_lossy(e);
+                    push_text(current, &mut derivation, field, &text);
+                }
+            }
+            Event::GeneralRef(ref e) if !in_reference => {
+                if let Some(current) = entry.as_mut() {
+                    push_text(current, &mut derivation, field, &resolve_entity(e));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Adds text to the field of the entry that is being read, with whitespace collapsed.
+fn push_text(entry: &mut LexiconEntry, derivation: &mut String, field: Option<Field>, text: &str) {
+    let target = match field {
+        Some(Field::Derivation) => derivation,
+        Some(Field::Definition) => &mut entry.definition,
+        Some(Field::Usage) => &mut entry.kjv_usage,
+        None => return,
+    };
+    if text.starts_with(char::is_whitespace) && !target.ends_with(' ') {
+        target.push(' ');
+    }
+    target.push_str(&text.split_whitespace().collect::<Vec<_>>().join(" "));
+    if text.ends_with(char::is_whitespace) && !target.is_empty() {
+        target.push(' ');
+    }
+}
+
+fn is_entry(e: &BytesStart) -> bool {
+    match e.name().as_ref() {
+        b"div" => attr(e, b"type").as_deref() == Some("entry"),
+        b"entry" => true,
+        _ => false,
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DICTIONARY: &str = r#"<osis><osisText><div type="glossary">
+<div type="entry" n="H7225"><w ID="H7225" lemma="רֵאשִׁית" xlit="rêʼshîyth" pron="ray-sheeth'">רֵאשִׁית</w>
+<note type="exegesis">from the same as <w src="H7218">7218</w>;</note>
+<note type="explanation">the first, in place, time, order or rank</note>
+<note type="translation">beginning, chief(-est), first(-fruits)</note></div>
+</div></osisText></osis>
+<entries><entry strongs="03056"><strongs>3056</strongs> <greek BETA="LO/GOS" unicode="λόγος" translit="lógos"/>
+<pronunciation strongs="log'-os"/> <strongs_derivation>from <strongsref language="GREEK" strongs="3004"/>;</strongs_derivation>
+<strongs_def>something said (including the thought) &amp; reasoning</strongs_def>
+<kjv_def>:--account, word.</kjv_def></entry></entries>"#;
+
+    #[test]
+    fn imports_and_looks_up_entries() {
+        let dir = std::env::temp_dir().join(format!("logos-lexicon-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dictionary = dir.join("strongs.xml");
+        std::fs::write(&dictionary, DICTIONARY).unwrap();
+
+        let mut lexicon = Lexicon::from_file(dir.join("lexicon.json")).unwrap();
+        let imported = lexicon.import(&dictionary);
+        // Saved entries are found again after loading.
+        let reloaded = Lexicon::from_file(dir.join("lexicon.json"));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(imported.unwrap(), 2);
+
+        let hebrew = lexicon.get("h07225").unwrap();
+        assert_eq!(
+            hebrew,
+            LexiconEntry {
+                number: "H7225".to_string(),
+                lemma: "רֵאשִׁית".to_string(),
+                transliteration: "rêʼshîyth".to_string(),
+                pronunciation: "ray-sheeth'".to_string(),
+                definition: "from the same as H7218; the first, in place, time, order or rank"
+                    .to_string(),
+                kjv_usage: "beginning, chief(-est), first(-fruits)".to_string(),
+            }
+        );
+        let greek = lexicon.get("G3056").unwrap();
+        assert_eq!(greek.lemma, "λόγος");
+        assert_eq!(greek.transliteration, "lógos");
+        assert_eq!(greek.pronunciation, "log'-os");
+        assert_eq!(
+            greek.definition,
+            "from G3004; something said (including the thought) & reasoning"
+        );
+        assert_eq!(greek.kjv_usage, "account, word.");
+        assert!(lexicon.get("G9999").is_none());
+
+        let reloaded = reloaded.unwrap();
+        assert_eq!(reloaded.len(), 2);
+        assert_eq!(reloaded.get("H7225"), Some(hebrew));
+    }
+}
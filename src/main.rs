@@ -1,19 +1,45 @@
 mod app;
 mod bible;
+mod bookmarks;
+mod canon;
+mod cli;
+mod clipboard;
 mod components;
+mod concordance;
 mod config;
+mod crossrefs;
+mod editor;
 mod error;
 mod filesystem;
+mod formats;
+mod highlights;
+mod icons;
+mod import;
+mod lexicon;
+mod notes;
 mod prelude;
+mod reference;
+mod search;
+mod session;
+mod settings;
+mod store;
+mod sword;
+mod theme;
+mod translations;
+mod versification;
 
 use crate::app::events::KeyMap;
 use crate::app::state::AppStateEnum;
 use crate::app::state_dashboard::Dashboard;
+use crate::cli::Cli;
 use crate::prelude::*;
+use crate::settings::Settings;
+use clap::Parser;
 use crossterm::event::{self, KeyEventKind};
 use env_logger::{Env, Target};
 use ratatui::DefaultTerminal;
 use std::fs::OpenOptions;
+use std::process::ExitCode;
 use std::time::Duration;
 
 fn setup_logging() {
@@ -41,7 +67,7 @@ fn app_loop(terminal: &mut DefaultTerminal) -> Result<()> {
     })?;
     state = state.update(AppEvent::AppStart)?;
 
-    let keymap = KeyMap::default();
+    let keymap = KeyMap::from_bindings(&settings().keys);
     loop {
         // Wait indefinitely for event.
         // TODO: I really want some kind of tick mechanism.
@@ -58,12 +84,19 @@ fn app_loop(terminal: &mut DefaultTerminal) -> Result<()> {
                     continue;
                 }
 
-                if let Some(action) = keymap.get(&key.code, key.modifiers) {
+                if state.captures_input() {
+                    state = state.update(AppEvent::KeyInput(key))?;
+                } else if let Some(action) = keymap.get(&key.code, key.modifiers) {
                     trace!("Key: {}, Mod: {}", key.code, key.modifiers);
                     state = state.update(AppEvent::UserAction(action))?;
                 }
             }
 
+            if let Some(request) = state.take_edit_request() {
+                let edited = suspended(terminal, || editor::edit(&request))?;
+                state = state.update(AppEvent::Edited(edited))?;
+            }
+
             if matches!(state, AppStateEnum::Exit) {
                 info!("Exiting");
                 return Ok(());
@@ -77,9 +110,39 @@ fn app_loop(terminal: &mut DefaultTerminal) -> Result<()> {
     }
 }
 
-fn main() -> Result<()> {
+/// Leaves the terminal interface while `f` runs, e.g. for an editor that needs the terminal.
+fn suspended<T>(terminal: &mut DefaultTerminal, f: impl FnOnce() -> T) -> Result<T> {
+    ratatui::restore();
+    let result = f();
+    crossterm::terminal::enable_raw_mode()?;
+    crossterm::execute!(std::io::stdout(), crossterm::terminal::EnterAlternateScreen)?;
+    terminal.clear()?;
+    Ok(result)
+}
+
+fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
     setup_logging();
+    Settings::init(&cli);
+
+    if let Some(command) = cli.command {
+        if let Some(e) = &settings().error {
+            eprintln!("logos: {e}");
+        }
+        return match cli::run(command) {
+            Ok(code) => Ok(code),
+            // E.g. when piped into `head`.
+            Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+                Ok(ExitCode::SUCCESS)
+            }
+            Err(e) => {
+                eprintln!("logos: {e}");
+                Ok(ExitCode::FAILURE)
+            }
+        };
+    }
+
     color_eyre::install()?;
     ratatui::run(app_loop)?;
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
@@ -0,0 +1,148 @@
+use crate::canon;
+use crate::crossrefs::{Passage, osis_range};
+use crate::prelude::*;
+use crate::store::{Store, Stored};
+use serde::{Deserialize, Serialize};
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A note in markdown about a verse or range of verses.  Notes are numbered like the KJV, so
+/// they work in every translation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Note {
+    /// OSIS reference in the numbering of the KJV, e.g. "John.3.16" or "John.3.16-John.3.18".
+    pub reference: String,
+    pub text: String,
+}
+
+impl Note {
+    /// The (book, (chapter, verse), (chapter, verse)) of the note.
+    pub fn range(&self) -> Option<Passage<'_>> {
+        osis_range(&self.reference)
+    }
+
+    /// Whether the note is about the verse, in the numbering of the KJV.
+    pub fn contains(&self, book: &str, chapter: usize, verse: usize) -> bool {
+        self.range()
+            .is_some_and(|(b, start, end)| b == book && (start..=end).contains(&(chapter, verse)))
+    }
+
+    fn position(&self) -> (usize, (usize, usize), (usize, usize)) {
+        match self.range() {
+            Some((book, start, end)) => (canon::canonical_position(book), start, end),
+            None => (usize::MAX, (0, 0), (0, 0)),
+        }
+    }
+}
+
+/// The OSIS reference of the verses in the numbering of the KJV, e.g. "John.3.16-John.3.18".
+pub fn osis_reference(book: &str, start: (usize, usize), end: (usize, usize)) -> String {
+    match start == end {
+        true => format!("{book}.{}.{}", start.0, start.1),
+        false => format!("{book}.{}.{}-{book}.{}.{}", start.0, start.1, end.0, end.1),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl Stored for Note {
+    const NAME: &'static str = "notes";
+    const VERSION: u32 = 1;
+
+    fn file() -> Option<PathBuf> {
+        notes_file()
+    }
+}
+
+/// The notes of the user in canon order, saved after every change.
+pub type Notes = Store<Note>;
+
+impl Store<Note> {
+    /// Replaces the note of the reference, an empty text removes it.
+    pub fn set(&mut self, reference: &str, text: &str) -> Result<()> {
+        let text = text.trim_end();
+        let existing = self.iter().position(|n| n.reference == reference);
+        if existing.is_none() && text.trim().is_empty() {
+            return Ok(());
+        }
+        self.change(|notes| match existing {
+            Some(i) if text.trim().is_empty() => {
+                notes.remove(i);
+            }
+            Some(i) => notes[i].text = text.to_string(),
+            None => {
+                notes.push(Note {
+                    reference: reference.to_string(),
+                    text: text.to_string(),
+                });
+                notes.sort_by_key(Note::position);
+            }
+        })
+    }
+
+    pub fn remove(&mut self, reference: &str) -> Result<()> {
+        self.set(reference, "")
+    }
+
+    pub fn get(&self, reference: &str) -> Option<&Note> {
+        self.iter().find(|n| n.reference == reference)
+    }
+
+    /// The notes about the verse, in the numbering of the KJV.
+    pub fn for_verse(&self, book: &str, chapter: usize, verse: usize) -> Vec<&Note> {
+        self.iter()
+            .filter(|n| n.contains(book, chapter, verse))
+            .collect()
+    }
+
+    /// The notes about the verses of a book.
+    pub fn in_book<'a>(&'a self, book: &'a str) -> impl Iterator<Item = &'a Note> {
+        self.iter()
+            .filter(move |n| n.range().is_some_and(|(b, _, _)| b == book))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn references(notes: &Notes) -> Vec<(&str, &str)> {
+        notes
+            .iter()
+            .map(|n| (n.reference.as_str(), n.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn sets_and_removes_notes() {
+        let path = std::env::temp_dir().join(format!("logos-{}-notes.json", std::process::id()));
+        let mut notes = Notes::from_file(path.clone()).unwrap();
+
+        // Added in canon order, without the trailing whitespace of the editor.
+        notes
+            .set("John.3.16-John.3.18", "God so loved\n\n")
+            .unwrap();
+        notes.set("Gen.1.1", "In the beginning").unwrap();
+        assert_eq!(
+            references(&notes),
+            [
+                ("Gen.1.1", "In the beginning"),
+                ("John.3.16-John.3.18", "God so loved")
+            ]
+        );
+        assert_eq!(notes.for_verse("John", 3, 17).len(), 1);
+
+        // Replaced, and removed by an empty text.
+        notes.set("Gen.1.1", "# Creation").unwrap();
+        notes.set("John.3.16-John.3.18", "  \n").unwrap();
+        // Nothing to remove.
+        notes.set("Rom.1.1", "").unwrap();
+        assert_eq!(references(&notes), [("Gen.1.1", "# Creation")]);
+
+        let reloaded = Notes::from_file(path.clone());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(references(&reloaded.unwrap()), [("Gen.1.1", "# Creation")]);
+    }
+}
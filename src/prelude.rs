@@ -3,6 +3,9 @@
 pub use crate::app::events::AppEvent;
 pub use crate::config::*;
 pub use crate::error::{Error, Result};
+pub use crate::icons::{Icon, border_set, icon};
+pub use crate::settings::settings;
+pub use crate::theme::theme;
 
 pub use std::collections::HashMap;
 pub use std::path::{Path, PathBuf};
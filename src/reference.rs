@@ -0,0 +1,248 @@
+use crate::{bible::Bible, canon, prelude::*};
+use std::fmt;
+use std::ops::RangeInclusive;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A reference to a passage, resolved against a bible.
+///
+/// Examples of references that can be parsed:
+/// - "John 3:16"
+/// - "Jn 3"
+/// - "1 Cor 13:4-7"
+/// - "Ps 23"
+/// - "Jude 3", the verse of a book with a single chapter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    /// OSIS ID of the book.
+    pub book: String,
+    pub chapter: usize,
+    /// Inclusive range of verses, `None` refers to the whole chapter.
+    pub verses: Option<RangeInclusive<usize>>,
+}
+
+impl Reference {
+    pub fn parse(input: &str, bible: &Bible) -> Result<Reference> {
+        let input = input.trim();
+        let (book, location) = Self::split_location(input)?;
+        let book = bible.find_book(book)?.to_string();
+        let book_index = bible.get_book_index(&book)?;
+
+        let (chapter, verses) = match location {
+            None => (1, None),
+            Some(Location::Chapter(n)) if canon::has_single_chapter(&book) => {
+                // Books with a single chapter are referred to by verse, e.g. "Jude 3".
+                (1, Some(n..=n))
+            }
+            Some(Location::Chapter(n)) => (n, None),
+            Some(Location::Verses(n, verses)) => (n, Some(verses)),
+        };
+
+        let chapter_index = bible.get_chapter(&book, chapter)?;
+        if let Some(verses) = &verses {
+            for verse in [*verses.start(), *verses.end()] {
+                if !chapter_index.verses.iter().any(|v| v.number == verse) {
+                    let name = book_index.name.clone();
+                    return Err(Error::VerseNotFound(name, chapter, verse));
+                }
+            }
+        }
+
+        Ok(Reference {
+            book,
+            chapter,
+            verses,
+        })
+    }
+
+    /// The first verse that is referred to.
+    pub fn first_verse(&self) -> usize {
+        self.verses.as_ref().map_or(1, |v| *v.start())
+    }
+
+    /// Splits "1 Cor 13:4-7" into ("1 Cor", "13:4-7").  The location has to be the last word, and
+    /// the book has to be something other than just a number.
+    fn split_location(input: &str) -> Result<(&str, Option<Location>)> {
+        match input.rsplit_once(char::is_whitespace) {
+            Some((book, location)) if !book.trim().chars().all(|c| c.is_ascii_digit()) => {
+                match Location::parse(location)? {
+                    Some(location) => Ok((book.trim(), Some(location))),
+                    None => Ok((input, None)),
+                }
+            }
+            _ => Ok((input, None)),
+        }
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", canon::book_name(&self.book), self.chapter)?;
+        match &self.verses {
+            Some(v) if v.start() == v.end() => write!(f, ":{}", v.start()),
+            Some(v) => write!(f, ":{}-{}", v.start(), v.end()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Formats the verses from `start` to `end`, which are (chapter, verse), e.g. "John 3:16-17" or
+/// "John 3:36-4:2".
+pub fn format_range(book: &str, start: (usize, usize), end: (usize, usize)) -> String {
+    let name = canon::book_name(book);
+    match (start, end) {
+        _ if start == end => format!("{name} {}:{}", start.0, start.1),
+        ((c1, v1), (c2, v2)) if c1 == c2 => format!("{name} {c1}:{v1}-{v2}"),
+        ((c1, v1), (c2, v2)) => format!("{name} {c1}:{v1}-{c2}:{v2}"),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, PartialEq)]
+enum Location {
+    Chapter(usize),
+    Verses(usize, RangeInclusive<usize>),
+}
+
+impl Location {
+    /// Parses "3", "3:16" or "3:16-18".  Anything else that is made of numbers, colons and dashes
+    /// is an error rather than part of the name of the book, e.g. "3:18-16".  `None` if it is not
+    /// a location.
+    fn parse(s: &str) -> Result<Option<Location>> {
+        let is_location = s.starts_with(|c: char| c.is_ascii_digit())
+            && s.chars()
+                .all(|c| c.is_ascii_digit() || c == ':' || c == '-');
+        if !is_location {
+            return Ok(None);
+        }
+        let invalid = |reason: &str| Error::InvalidReference(format!("{s} {reason}"));
+        let number = |n: &str| {
+            n.parse::<usize>()
+                .map_err(|_| invalid("is not a chapter and verses, e.g. 3:16-18"))
+        };
+
+        let Some((chapter, verses)) = s.split_once(':') else {
+            return match s.contains('-') {
+                true => Err(invalid("is a range of chapters, which is not supported")),
+                false => Ok(Some(Location::Chapter(number(s)?))),
+            };
+        };
+        let chapter = number(chapter)?;
+        let (start, end) = match verses.split_once('-') {
+            Some((_, end)) if end.contains(':') => {
+                return Err(invalid("spans chapters, which is not supported"));
+            }
+            Some((start, end)) => (number(start)?, number(end)?),
+            None => (number(verses)?, number(verses)?),
+        };
+        if start > end {
+            return Err(invalid("ends before it starts"));
+        }
+        Ok(Some(Location::Verses(chapter, start..=end)))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bible::tests::bible_from;
+
+    fn location(s: &str) -> Option<Location> {
+        Location::parse(s).unwrap()
+    }
+
+    /// John 3:16-18 and Jude 1:1-3, and nothing else of either book.
+    fn partial_bible(name: &str) -> Bible {
+        let verses = |book: &str, chapter: usize, verses: std::ops::RangeInclusive<usize>| {
+            verses
+                .map(|v| format!("<verse osisID=\"{book}.{chapter}.{v}\">Verse {v}.</verse>"))
+                .collect::<String>()
+        };
+        bible_from(
+            name,
+            &format!(
+                "<osis><osisText>\
+                 <div type=\"book\" osisID=\"John\"><chapter osisID=\"John.3\">{}</chapter></div>\
+                 <div type=\"book\" osisID=\"Jude\"><chapter osisID=\"Jude.1\">{}</chapter></div>\
+                 </osisText></osis>",
+                verses("John", 3, 16..=18),
+                verses("Jude", 1, 1..=3),
+            ),
+        )
+    }
+
+    #[test]
+    fn parses_locations() {
+        assert_eq!(location("3"), Some(Location::Chapter(3)));
+        assert_eq!(location("3:16"), Some(Location::Verses(3, 16..=16)));
+        assert_eq!(location("13:4-7"), Some(Location::Verses(13, 4..=7)));
+        assert_eq!(location("John"), None);
+        assert_eq!(location("Solomon"), None);
+    }
+
+    #[test]
+    fn rejects_invalid_locations() {
+        for s in ["3:18-16", "1:1-2:3", "1-3", "3:", "3:-5", "3::16"] {
+            assert!(
+                matches!(Location::parse(s), Err(Error::InvalidReference(_))),
+                "{s} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn splits_the_book_from_the_location() {
+        let (book, location) = Reference::split_location("1 Cor 13:4-7").unwrap();
+        assert_eq!(
+            (book, location),
+            ("1 Cor", Some(Location::Verses(13, 4..=7)))
+        );
+        let (book, location) = Reference::split_location("1 John").unwrap();
+        assert_eq!((book, location), ("1 John", None));
+        let (book, location) = Reference::split_location("Song of Solomon").unwrap();
+        assert_eq!((book, location), ("Song of Solomon", None));
+        assert!(Reference::split_location("John 3:18-16").is_err());
+    }
+
+    #[test]
+    fn resolves_against_the_bible() {
+        let bible = partial_bible("reference-resolve.osis.xml");
+        let reference = Reference::parse("Jn 3:16-17", &bible).unwrap();
+        assert_eq!(reference.to_string(), "John 3:16-17");
+        assert!(matches!(
+            Reference::parse("John 3:19", &bible),
+            Err(Error::VerseNotFound(_, 3, 19))
+        ));
+        assert!(matches!(
+            Reference::parse("John 3:18-16", &bible),
+            Err(Error::InvalidReference(_))
+        ));
+        assert!(matches!(
+            Reference::parse("Gen 1:1", &bible),
+            Err(Error::BookNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn single_chapter_books_come_from_the_canon() {
+        let bible = partial_bible("reference-single-chapter.osis.xml");
+        // The file only has one chapter of John, which is still not a single chapter book.
+        let reference = Reference::parse("John 3", &bible).unwrap();
+        assert_eq!((reference.chapter, reference.verses), (3, None));
+        let reference = Reference::parse("Jude 3", &bible).unwrap();
+        assert_eq!((reference.chapter, reference.verses), (1, Some(3..=3)));
+    }
+
+    #[test]
+    fn formats_ranges() {
+        assert_eq!(format_range("John", (3, 16), (3, 16)), "John 3:16");
+        assert_eq!(format_range("John", (3, 16), (3, 17)), "John 3:16-17");
+        assert_eq!(
+            format_range("1Cor", (3, 36), (4, 2)),
+            "1 Corinthians 3:36-4:2"
+        );
+    }
+}
@@ -0,0 +1,600 @@
+use crate::{
+    bible::{Bible, Testament, VerseView},
+    canon,
+    prelude::*,
+    reference::Reference,
+};
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Position of a verse in the bible index.
+#[derive(Debug, Clone, Copy)]
+struct VerseLocation {
+    book: usize,
+    chapter: usize,
+    verse: usize,
+}
+
+/// Inverted index of the words in a bible, used for full-text search.
+pub struct SearchIndex {
+    /// Verse ids are indices into this.
+    verses: Vec<VerseLocation>,
+    /// Normalized word -> sorted ids of the verses containing it.
+    words: HashMap<String, Vec<usize>>,
+}
+
+impl SearchIndex {
+    pub fn build(bible: &Bible) -> SearchIndex {
+        info!("Building search index");
+        let start = Instant::now();
+
+        let mut verses = Vec::new();
+        let mut words: HashMap<String, Vec<usize>> = HashMap::new();
+
+        for (b, (_, book)) in bible.iter_books().enumerate() {
+            for (c, chapter) in book.chapters.iter().enumerate() {
+                for (v, verse) in chapter.verses.iter().enumerate() {
+                    let id = verses.len();
+                    verses.push(VerseLocation {
+                        book: b,
+                        chapter: c,
+                        verse: v,
+                    });
+
+                    for token in tokenize(&verse.collect_string(bible.get_raw_data())) {
+                        let postings = words.entry(token.word).or_default();
+                        if postings.last() != Some(&id) {
+                            postings.push(id);
+                        }
+                    }
+                }
+            }
+        }
+
+        info!(
+            "Built search index of {} words in {:?}",
+            words.len(),
+            start.elapsed()
+        );
+        SearchIndex { verses, words }
+    }
+
+    pub fn search(&self, bible: &Bible, request: &SearchRequest) -> SearchResults {
+        let start = Instant::now();
+        let scope = self.scope_ids(bible, &request.scope);
+        let ids = self.evaluate(bible, &request.query, &scope);
+
+        let terms = request.query.positive_terms();
+        let mut matches = 0;
+        let hits: Vec<SearchHit> = ids
+            .into_iter()
+            .map(|id| {
+                let (reference, verse) = self.resolve(bible, id);
+                let text = verse.collect_string(bible.get_raw_data());
+                let highlights = find_matches(&text, &terms);
+                matches += highlights.len();
+                SearchHit {
+                    reference,
+                    text,
+                    highlights,
+                }
+            })
+            .collect();
+
+        debug!(
+            "Search {:?} found {} verses in {:?}",
+            request.input,
+            hits.len(),
+            start.elapsed()
+        );
+        SearchResults {
+            request: request.clone(),
+            hits,
+            matches,
+        }
+    }
+
+    fn resolve<'a>(&self, bible: &'a Bible, id: usize) -> (Reference, &'a VerseView) {
+        let location = self.verses[id];
+        let (name, book) = bible
+            .get_book_at(location.book)
+            .expect("Index was built from this bible");
+        let chapter = &book.chapters[location.chapter];
+        let verse = &chapter.verses[location.verse];
+        let reference = Reference {
+            book: name.to_string(),
+            chapter: chapter.number,
+            verses: Some(verse.number..=verse.number),
+        };
+        (reference, verse)
+    }
+
+    fn scope_ids(&self, bible: &Bible, scope: &SearchScope) -> BTreeSet<usize> {
+        let books: Vec<bool> = bible
+            .iter_books()
+            .map(|(name, book)| match scope {
+                SearchScope::All => true,
+                SearchScope::Testament(t) => book.testament == Some(*t),
+                SearchScope::Book(b) => name == b,
+            })
+            .collect();
+
+        (0..self.verses.len())
+            .filter(|id| books[self.verses[*id].book])
+            .collect()
+    }
+
+    fn postings(&self, word: &str) -> impl Iterator<Item = usize> + '_ {
+        self.words.get(word).into_iter().flatten().copied()
+    }
+
+    /// Returns the ids of the verses in `scope` that match the query.
+    fn evaluate(&self, bible: &Bible, query: &Query, scope: &BTreeSet<usize>) -> BTreeSet<usize> {
+        match query {
+            Query::Word(word) => self
+                .postings(word)
+                .filter(|id| scope.contains(id))
+                .collect(),
+            Query::Prefix(prefix) => self
+                .words
+                .iter()
+                .filter(|(word, _)| word.starts_with(prefix.as_str()))
+                .flat_map(|(_, ids)| ids.iter().copied())
+                .filter(|id| scope.contains(id))
+                .collect(),
+            Query::Phrase(words) => {
+                let candidates = words.iter().fold(scope.clone(), |acc, word| {
+                    self.postings(word).filter(|id| acc.contains(id)).collect()
+                });
+                let terms = [query];
+                candidates
+                    .into_iter()
+                    .filter(|id| {
+                        let (_, verse) = self.resolve(bible, *id);
+                        let text = verse.collect_string(bible.get_raw_data());
+                        !find_matches(&text, &terms).is_empty()
+                    })
+                    .collect()
+            }
+            Query::And(queries) => queries.iter().fold(scope.clone(), |acc, q| {
+                match q {
+                    // Only remove from what is left, no need to evaluate the complement.
+                    Query::Not(q) => {
+                        let excluded = self.evaluate(bible, q, &acc);
+                        acc.difference(&excluded).copied().collect()
+                    }
+                    q => self.evaluate(bible, q, &acc),
+                }
+            }),
+            Query::Or(queries) => queries
+                .iter()
+                .flat_map(|q| self.evaluate(bible, q, scope))
+                .collect(),
+            Query::Not(query) => {
+                let excluded = self.evaluate(bible, query, scope);
+                scope.difference(&excluded).copied().collect()
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchScope {
+    All,
+    Testament(Testament),
+    /// OSIS ID of the book.
+    Book(String),
+}
+
+impl SearchScope {
+    /// Parses the value of an `in:` filter, e.g. "nt" or "john".
+    fn parse(s: &str, bible: &Bible) -> Result<SearchScope> {
+        Ok(match s.to_lowercase().as_str() {
+            "all" | "bible" => SearchScope::All,
+            "ot" | "old" => SearchScope::Testament(Testament::Old),
+            "nt" | "new" => SearchScope::Testament(Testament::New),
+            "ap" | "apoc" | "apocrypha" => SearchScope::Testament(Testament::Apocrypha),
+            _ => SearchScope::Book(bible.find_book(s)?.to_string()),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            SearchScope::All => "Bible",
+            SearchScope::Testament(Testament::Old) => "OT",
+            SearchScope::Testament(Testament::New) => "NT",
+            SearchScope::Testament(Testament::Apocrypha) => "Apocrypha",
+            SearchScope::Book(book) => canon::book_name(book),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    Word(String),
+    /// Written as `faith*`.
+    Prefix(String),
+    /// Written as `"in the beginning"`.
+    Phrase(Vec<String>),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+    /// Written as `NOT word` or `-word`.
+    Not(Box<Query>),
+}
+
+impl Query {
+    /// The terms that will be highlighted in results, i.e. everything that is not negated.
+    fn positive_terms(&self) -> Vec<&Query> {
+        match self {
+            Query::Word(_) | Query::Prefix(_) | Query::Phrase(_) => vec![self],
+            Query::And(queries) | Query::Or(queries) => {
+                queries.iter().flat_map(|q| q.positive_terms()).collect()
+            }
+            Query::Not(_) => Vec::new(),
+        }
+    }
+}
+
+/// A parsed search, e.g. `faith (hope OR charity) -works in:nt`.
+///
+/// - Terms separated by whitespace (or `AND`) all have to match.
+/// - `OR` matches either side, and binds weaker than `AND`.
+/// - `NOT` or `-` excludes a term.
+/// - `"..."` matches a phrase and `word*` matches a prefix.
+/// - `in:` limits the scope to "ot", "nt", "apoc" or a book.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchRequest {
+    pub input: String,
+    pub query: Query,
+    pub scope: SearchScope,
+}
+
+impl SearchRequest {
+    pub fn parse(input: &str, bible: &Bible) -> Result<SearchRequest> {
+        let mut scope = SearchScope::All;
+        let mut tokens = Vec::new();
+        for token in lex(input)? {
+            match token {
+                QueryToken::Term(term) if term.starts_with("in:") => {
+                    scope = SearchScope::parse(&term[3..], bible)?;
+                }
+                token => tokens.push(token),
+            }
+        }
+
+        let mut parser = QueryParser {
+            tokens: tokens.into_iter().peekable(),
+        };
+        let query = parser.parse_or()?;
+        if let Some(token) = parser.tokens.next() {
+            return Err(Error::InvalidQuery(format!("unexpected {token:?}")));
+        }
+
+        Ok(SearchRequest {
+            input: input.trim().to_string(),
+            query,
+            scope,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum QueryToken {
+    Term(String),
+    Phrase(Vec<String>),
+    Open,
+    Close,
+}
+
+fn lex(input: &str) -> Result<Vec<QueryToken>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '(' => tokens.push(QueryToken::Open),
+            ')' => tokens.push(QueryToken::Close),
+            '"' => {
+                let mut phrase = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => phrase.push(c),
+                        None => return Err(Error::InvalidQuery("missing closing \"".to_string())),
+                    }
+                }
+                let words: Vec<String> = tokenize(&phrase).map(|t| t.word).collect();
+                if words.is_empty() {
+                    return Err(Error::InvalidQuery("empty phrase".to_string()));
+                }
+                tokens.push(QueryToken::Phrase(words));
+            }
+            c => {
+                let mut term = String::from(c);
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace() && !"()\"".contains(*c)) {
+                    term.push(c);
+                }
+                tokens.push(QueryToken::Term(term));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct QueryParser {
+    tokens: std::iter::Peekable<std::vec::IntoIter<QueryToken>>,
+}
+
+impl QueryParser {
+    fn parse_or(&mut self) -> Result<Query> {
+        let mut queries = vec![self.parse_and()?];
+        while self
+            .tokens
+            .next_if_eq(&QueryToken::Term("OR".into()))
+            .is_some()
+        {
+            queries.push(self.parse_and()?);
+        }
+        Ok(match queries.len() {
+            1 => queries.remove(0),
+            _ => Query::Or(queries),
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Query> {
+        let mut queries = vec![self.parse_unary()?];
+        loop {
+            match self.tokens.peek() {
+                None | Some(QueryToken::Close) => break,
+                Some(QueryToken::Term(t)) if t == "OR" => break,
+                Some(QueryToken::Term(t)) if t == "AND" => {
+                    self.tokens.next();
+                }
+                _ => {}
+            }
+            queries.push(self.parse_unary()?);
+        }
+        Ok(match queries.len() {
+            1 => queries.remove(0),
+            _ => Query::And(queries),
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Query> {
+        match self.tokens.next() {
+            Some(QueryToken::Term(t)) if t == "NOT" => Ok(Query::Not(self.parse_unary()?.into())),
+            Some(QueryToken::Term(t)) if t.len() > 1 && t.starts_with('-') => {
+                Ok(Query::Not(Self::term(&t[1..])?.into()))
+            }
+            Some(QueryToken::Term(t)) if t == "AND" || t == "OR" => {
+                Err(Error::InvalidQuery(format!("expected a term before {t}")))
+            }
+            Some(QueryToken::Term(t)) => Self::term(&t),
+            Some(QueryToken::Phrase(words)) => Ok(Query::Phrase(words)),
+            Some(QueryToken::Open) => {
+                let query = self.parse_or()?;
+                match self.tokens.next() {
+                    Some(QueryToken::Close) => Ok(query),
+                    _ => Err(Error::InvalidQuery("missing )".to_string())),
+                }
+            }
+            Some(QueryToken::Close) => Err(Error::InvalidQuery("unexpected )".to_string())),
+            None => Err(Error::InvalidQuery("expected a term".to_string())),
+        }
+    }
+
+    fn term(term: &str) -> Result<Query> {
+        let (term, prefix) = match term.strip_suffix('*') {
+            Some(term) => (term, true),
+            None => (term, false),
+        };
+        let mut words: Vec<String> = tokenize(term).map(|t| t.word).collect();
+        match (words.len(), prefix) {
+            (0, _) => Err(Error::InvalidQuery(format!("no words in {term:?}"))),
+            (1, true) => Ok(Query::Prefix(words.remove(0))),
+            (1, false) => Ok(Query::Word(words.remove(0))),
+            // Something like "wife’s" or "new-born" that tokenizes into multiple words.
+            _ => Ok(Query::Phrase(words)),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub struct SearchHit {
+    pub reference: Reference,
+    pub text: String,
+    /// Byte ranges of the matches in `text`.
+    pub highlights: Vec<Range<usize>>,
+}
+
+pub struct SearchResults {
+    pub request: SearchRequest,
+    pub hits: Vec<SearchHit>,
+    /// Total number of matches over all of the hits.
+    pub matches: usize,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub struct Token {
+    /// Byte range in the text.
+    pub range: Range<usize>,
+    /// Lowercase word with normalized apostrophes.
+    pub word: String,
+}
+
+/// Splits text into words, keeping apostrophes inside of words ("wife’s").
+pub fn tokenize(text: &str) -> impl Iterator<Item = Token> + '_ {
+    let is_apostrophe = |c: char| c == '\'' || c == '’';
+    text.split(move |c: char| !c.is_alphanumeric() && !is_apostrophe(c))
+        .filter_map(move |part| {
+            let trimmed = part.trim_matches(is_apostrophe);
+            if trimmed.is_empty() {
+                return None;
+            }
+            let start = trimmed.as_ptr() as usize - text.as_ptr() as usize;
+            let word = trimmed
+                .chars()
+                .map(|c| if c == '’' { '\'' } else { c })
+                .flat_map(char::to_lowercase)
+                .collect();
+            Some(Token {
+                range: start..start + trimmed.len(),
+                word,
+            })
+        })
+}
+
+/// Finds the byte ranges in `text` matching any of the terms.
+fn find_matches(text: &str, terms: &[&Query]) -> Vec<Range<usize>> {
+    let tokens: Vec<Token> = tokenize(text).collect();
+    let mut matches: Vec<Range<usize>> = Vec::new();
+
+    for term in terms {
+        match term {
+            Query::Word(word) => matches.extend(
+                tokens
+                    .iter()
+                    .filter(|t| &t.word == word)
+                    .map(|t| t.range.clone()),
+            ),
+            Query::Prefix(prefix) => matches.extend(
+                tokens
+                    .iter()
+                    .filter(|t| t.word.starts_with(prefix.as_str()))
+                    .map(|t| t.range.clone()),
+            ),
+            Query::Phrase(words) => matches.extend(
+                tokens
+                    .windows(words.len())
+                    .filter(|w| w.iter().zip(words).all(|(t, word)| &t.word == word))
+                    .map(|w| w[0].range.start..w[w.len() - 1].range.end),
+            ),
+            _ => {}
+        }
+    }
+
+    // Overlapping terms, e.g. "light*" and "light", should only count once.
+    matches.sort_by_key(|r| (r.start, std::cmp::Reverse(r.end)));
+    matches.dedup_by(|next, prev| next.start < prev.end);
+    matches
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bible::tests::bible_from;
+
+    fn bible() -> Bible {
+        bible_from(
+            "search.xml",
+            "<osis><osisText><div type=\"bookGroup\"><title>New Testament</title>\
+             <div type=\"book\"><title short=\"John\">John</title><chapter sID=\"John.1\"/>\
+             <verse sID=\"John.1.1\"/>In the beginning was the Word.<verse eID=\"John.1.1\"/>\
+             <chapter eID=\"John.1\"/></div></div></osisText></osis>",
+        )
+    }
+
+    fn word(w: &str) -> Query {
+        Query::Word(w.to_string())
+    }
+
+    fn parse(bible: &Bible, input: &str) -> Result<Query> {
+        SearchRequest::parse(input, bible).map(|r| r.query)
+    }
+
+    #[test]
+    fn and_binds_stronger_than_or() {
+        let bible = bible();
+        let expected = Query::Or(vec![Query::And(vec![word("a"), word("b")]), word("c")]);
+        assert_eq!(parse(&bible, "a b OR c").unwrap(), expected);
+        assert_eq!(parse(&bible, "a AND b OR c").unwrap(), expected);
+        assert_eq!(
+            parse(&bible, "a (b OR c)").unwrap(),
+            Query::And(vec![word("a"), Query::Or(vec![word("b"), word("c")])])
+        );
+    }
+
+    #[test]
+    fn parses_negation() {
+        let bible = bible();
+        let not = |w| Query::Not(Box::new(word(w)));
+        assert_eq!(parse(&bible, "-works").unwrap(), not("works"));
+        assert_eq!(parse(&bible, "NOT works").unwrap(), not("works"));
+        assert_eq!(
+            parse(&bible, "faith -works").unwrap(),
+            Query::And(vec![word("faith"), not("works")])
+        );
+        assert_eq!(
+            parse(&bible, "NOT a OR b").unwrap(),
+            Query::Or(vec![not("a"), word("b")])
+        );
+    }
+
+    #[test]
+    fn parses_phrases_and_prefixes() {
+        let bible = bible();
+        assert_eq!(
+            parse(&bible, "\"In the  Beginning\" faith*").unwrap(),
+            Query::And(vec![
+                Query::Phrase(vec!["in".into(), "the".into(), "beginning".into()]),
+                Query::Prefix("faith".into()),
+            ])
+        );
+        // Words that tokenize into more than one word are phrases.
+        assert_eq!(
+            parse(&bible, "new-born").unwrap(),
+            Query::Phrase(vec!["new".into(), "born".into()])
+        );
+    }
+
+    #[test]
+    fn parses_scopes() {
+        let bible = bible();
+        let request = SearchRequest::parse("in:nt love", &bible).unwrap();
+        assert_eq!(request.scope, SearchScope::Testament(Testament::New));
+        let request = SearchRequest::parse("word in:jn", &bible).unwrap();
+        assert_eq!(request.scope, SearchScope::Book("John".to_string()));
+        assert_eq!(request.query, word("word"));
+        assert!(SearchRequest::parse("in:gen word", &bible).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_queries() {
+        let bible = bible();
+        for input in [
+            "\"in the beginning",
+            "\"\"",
+            "",
+            "OR a",
+            "a OR",
+            "(a b",
+            "a b)",
+            "NOT",
+        ] {
+            assert!(
+                matches!(parse(&bible, input), Err(Error::InvalidQuery(_))),
+                "{input:?} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn negation_only_matches_everything_else() {
+        let bible = bible();
+        let index = SearchIndex::build(&bible);
+        let request = SearchRequest::parse("-word", &bible).unwrap();
+        assert!(index.search(&bible, &request).hits.is_empty());
+        let request = SearchRequest::parse("-faith", &bible).unwrap();
+        assert_eq!(index.search(&bible, &request).hits.len(), 1);
+    }
+}
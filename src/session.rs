@@ -0,0 +1,202 @@
+use crate::app::state_default_reader::FocusedWindow;
+use crate::filesystem::write_atomic;
+use crate::prelude::*;
+use crate::reference::Reference;
+use serde::{Deserialize, Serialize};
+
+/// Version of the session file, bumped when the format changes.
+const SESSION_VERSION: u32 = 2;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Where the user was reading when they quit, restored with "Continue reading".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub translation: String,
+    /// Translations shown in the parallel view.
+    pub parallel: Vec<String>,
+    /// Whether the parallel view was open.
+    pub parallel_open: bool,
+    /// OSIS ID of the book.
+    pub book: String,
+    pub chapter: usize,
+    pub verse: usize,
+    /// Scroll position of the reader, only used while it still shows the verse since it depends
+    /// on the size of the terminal.
+    pub scrolled_offset: usize,
+    pub focused: FocusedWindow,
+    /// The panel that was open below the reader.
+    pub panel: Option<Panel>,
+    pub red_letter: bool,
+}
+
+/// A panel that is shown in place of the search results, with what it was showing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Panel {
+    /// The query of the search.
+    Search(String),
+    /// The word or Strong's number that was looked up.
+    Concordance(String),
+    Highlights,
+}
+
+/// The session with the version of its format, which is read first to migrate older sessions.
+#[derive(Serialize, Deserialize)]
+struct SessionFile<S> {
+    #[serde(default)]
+    version: u32,
+    session: S,
+}
+
+impl Session {
+    /// Loads the session of the last run.  A missing or corrupt session is not worth stopping
+    /// for, so there is just no session then.
+    pub fn load() -> Option<Session> {
+        let path = session_file()?;
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                warn!("Failed to read the session from {path:?}: {e}");
+                return None;
+            }
+        };
+
+        match Self::parse(&contents, &path) {
+            Ok(session) => {
+                info!(
+                    "Loaded session at {} ({})",
+                    session.reference(),
+                    session.translation
+                );
+                Some(session)
+            }
+            Err(e) => {
+                warn!("Ignoring the session in {path:?}: {e}");
+                None
+            }
+        }
+    }
+
+    fn parse(contents: &str, path: &Path) -> Result<Session> {
+        let mut file: SessionFile<serde_json::Value> = serde_json::from_str(contents)?;
+        match file.version {
+            // Only the search panel was saved, as its query.
+            1 => {
+                if let Some(session) = file.session.as_object_mut() {
+                    let panel = match session.remove("search") {
+                        Some(serde_json::Value::String(query)) => Some(Panel::Search(query)),
+                        _ => None,
+                    };
+                    session.insert("panel".to_string(), serde_json::to_value(panel)?);
+                }
+            }
+            SESSION_VERSION => {}
+            version => {
+                return Err(Error::UnsupportedVersion(
+                    path.display().to_string(),
+                    version,
+                ));
+            }
+        }
+        Ok(serde_json::from_value(file.session)?)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = session_file() else {
+            warn!("No data directory, the session will not be saved");
+            return Ok(());
+        };
+        let file = SessionFile {
+            version: SESSION_VERSION,
+            session: self,
+        };
+        write_atomic(&path, &serde_json::to_string_pretty(&file)?)?;
+        info!(
+            "Saved session at {} ({})",
+            self.reference(),
+            self.translation
+        );
+        Ok(())
+    }
+
+    pub fn reference(&self) -> Reference {
+        Reference {
+            book: self.book.clone(),
+            chapter: self.chapter,
+            verses: Some(self.verse..=self.verse),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> Session {
+        Session {
+            translation: "KJV".to_string(),
+            parallel: vec!["WEB".to_string()],
+            parallel_open: false,
+            book: "John".to_string(),
+            chapter: 3,
+            verse: 16,
+            scrolled_offset: 2,
+            focused: FocusedWindow::Reader,
+            panel: Some(Panel::Concordance("G3056".to_string())),
+            red_letter: true,
+        }
+    }
+
+    #[test]
+    fn parses_a_saved_session() {
+        let session = session();
+        let file = SessionFile {
+            version: SESSION_VERSION,
+            session: &session,
+        };
+        let contents = serde_json::to_string(&file).unwrap();
+        let parsed = Session::parse(&contents, Path::new("session.json")).unwrap();
+        assert_eq!(parsed.reference(), session.reference());
+        assert_eq!(parsed.parallel, session.parallel);
+        assert_eq!(parsed.panel, session.panel);
+    }
+
+    #[test]
+    fn migrates_the_search_of_version_1() {
+        let mut session = serde_json::to_value(session()).unwrap();
+        let fields = session.as_object_mut().unwrap();
+        fields.remove("panel");
+        fields.insert("search".to_string(), "faith AND hope".into());
+        let contents = serde_json::json!({"version": 1, "session": session}).to_string();
+        let parsed = Session::parse(&contents, Path::new("session.json")).unwrap();
+        assert_eq!(
+            parsed.panel,
+            Some(Panel::Search("faith AND hope".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let path = Path::new("session.json");
+        let contents = r#"{"version": 3, "session": {}}"#;
+        assert!(matches!(
+            Session::parse(contents, path),
+            Err(Error::UnsupportedVersion(_, 3))
+        ));
+        let contents = r#"{"session": {}}"#;
+        assert!(matches!(
+            Session::parse(contents, path),
+            Err(Error::UnsupportedVersion(_, 0))
+        ));
+    }
+
+    #[test]
+    fn rejects_json_that_is_not_an_object() {
+        for contents in ["[]", "42", "\"x\"", "null", "{\"version\": \"1\"}"] {
+            assert!(Session::parse(contents, Path::new("session.json")).is_err());
+        }
+    }
+}
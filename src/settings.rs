@@ -0,0 +1,282 @@
+use crate::app::events::{BoundAction, KeyBinding};
+use crate::cli::Cli;
+use crate::filesystem::line_column;
+use crate::icons::{IconSet, IconsSetting};
+use crate::prelude::*;
+use crate::theme::{DEFAULT_THEME, StyleSpec, Theme, ThemeSpec};
+use serde::{Deserialize, Deserializer, de};
+use std::ops::Range;
+use std::sync::OnceLock;
+use toml::Spanned;
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+/// The settings of the config file, the defaults until `Settings::init` is called.
+pub fn settings() -> &'static Settings {
+    SETTINGS.get_or_init(Settings::default)
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Settings from the TOML config file, every one of them is optional.
+///
+/// ```toml
+/// default_translation = "WEB"
+/// icons = "ascii"
+/// theme = "sepia"
+///
+/// [keys]
+/// "ctrl+f" = "page_down"
+/// "g" = "none"
+///
+/// [layout]
+/// sidebar = 25
+///
+/// [themes.sepia]
+/// extends = "light"
+/// title = "#8b4513 bold"
+/// search_hit = "black on #f4e4bc"
+///
+/// [[highlights]]
+/// name = "promise"
+/// style = "black on green"
+/// ```
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// Translation opened on start, when there is no session to continue.
+    pub default_translation: String,
+    /// "nerd-font", "unicode", "ascii" or "auto".
+    pub icons: IconsSetting,
+    /// Bindings applied on top of the default key map.
+    pub keys: HashMap<KeyBinding, BoundAction>,
+    pub layout: LayoutSettings,
+    /// A built-in theme or one of `themes`.
+    #[serde(rename = "theme")]
+    pub theme_name: Option<Spanned<String>>,
+    /// Themes of the user.
+    pub themes: HashMap<String, ThemeSpec>,
+    /// Categories that verses can be highlighted in, replacing the default ones.
+    pub highlights: Vec<HighlightCategory>,
+    /// The theme that `theme_name` refers to.
+    #[serde(skip)]
+    pub theme: Theme,
+    /// The icon set of `icons` or the command line.
+    #[serde(skip)]
+    pub icon_set: IconSet,
+    /// Why the config file was not used, shown on the dashboard.
+    #[serde(skip)]
+    pub error: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            default_translation: DEFAULT_TRANSLATION.to_string(),
+            icons: IconsSetting::Auto,
+            keys: HashMap::new(),
+            layout: LayoutSettings::default(),
+            theme_name: None,
+            themes: HashMap::new(),
+            highlights: HighlightCategory::defaults(),
+            theme: Theme::default(),
+            icon_set: IconSet::Unicode,
+            error: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Loads the config file for `settings()`, or the one given on the command line.  The defaults
+    /// are used when it can not be loaded.  The arguments override the config file.
+    pub fn init(cli: &Cli) {
+        let mut settings = match cli.config.clone().or_else(config_file) {
+            Some(path) if cli.config.is_some() && !path.exists() => Settings {
+                error: Some(format!("Config not loaded, {path:?} does not exist")),
+                ..Settings::default()
+            },
+            Some(path) => Self::from_file(&path).unwrap_or_else(|e| {
+                error!("Ignoring the config file: {e}");
+                Settings {
+                    error: Some(format!("Config not loaded, {e}")),
+                    ..Settings::default()
+                }
+            }),
+            None => Settings::default(),
+        };
+        settings.icon_set = cli.icons.unwrap_or_else(|| settings.icons.icon_set());
+        info!("Using the {:?} icon set", settings.icon_set);
+        if SETTINGS.set(settings).is_err() {
+            warn!("Settings were used before they were loaded");
+        }
+    }
+
+    /// Loads the settings from `path`, the defaults when it does not exist.
+    pub fn from_file(path: &Path) -> Result<Settings> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                debug!("No config file at {path:?}");
+                return Ok(Settings::default());
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let invalid = |message: &str, span: Option<Range<usize>>| {
+            let (line, column) = span.map_or((1, 1), |span| line_column(&contents, span.start));
+            Error::InvalidConfig {
+                path: path.display().to_string(),
+                line,
+                column,
+                message: message.to_string(),
+            }
+        };
+
+        let mut settings: Settings =
+            toml::from_str(&contents).map_err(|e| invalid(e.message(), e.span()))?;
+        let theme_name = settings.theme_name.as_ref();
+        let name = theme_name.map_or(DEFAULT_THEME, |name| name.get_ref());
+        settings.theme = Theme::resolve(name, &settings.themes).map_err(|(message, span)| {
+            invalid(
+                &message,
+                span.or_else(|| theme_name.map(|name| name.span())),
+            )
+        })?;
+        info!("Loaded config from {path:?} with the {name} theme");
+        Ok(settings)
+    }
+
+    /// The style of a highlight category, `None` if the category is not in the settings.
+    pub fn highlight_style(&self, category: &str) -> Option<Style> {
+        self.highlights
+            .iter()
+            .find(|c| c.name == category)
+            .map(|c| c.style.0)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A category of highlights, usually with a background colour, e.g. "black on green".
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HighlightCategory {
+    pub name: String,
+    pub style: StyleSpec,
+}
+
+impl HighlightCategory {
+    fn defaults() -> Vec<HighlightCategory> {
+        [
+            ("promise", Color::Green),
+            ("command", Color::Red),
+            ("prophecy", Color::Magenta),
+            ("favourite", Color::Yellow),
+        ]
+        .into_iter()
+        .map(|(name, color)| HighlightCategory {
+            name: name.to_string(),
+            style: StyleSpec(Style::new().black().bg(color)),
+        })
+        .collect()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Sizes of the panels of the reader, in percent of the screen.
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutSettings {
+    /// Width of the books on the left.
+    #[serde(deserialize_with = "percent")]
+    pub books: u16,
+    /// Width of the panels on the right.
+    #[serde(deserialize_with = "percent")]
+    pub sidebar: u16,
+    /// Height of the search results below the reader.
+    #[serde(deserialize_with = "percent")]
+    pub search: u16,
+}
+
+impl Default for LayoutSettings {
+    fn default() -> Self {
+        Self {
+            books: 15,
+            sidebar: 20,
+            search: 35,
+        }
+    }
+}
+
+fn percent<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<u16, D::Error> {
+    match u16::deserialize(deserializer)? {
+        p @ 0..=100 => Ok(p),
+        p => Err(de::Error::custom(format!("{p} is not a percentage"))),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::events::{KeyMap, UserAction};
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    /// The (line, column, message) of the error in a config file with `contents`.
+    fn config_error(contents: &str) -> (usize, usize, String) {
+        let path = std::env::temp_dir().join(format!("logos-{}-config.toml", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        let result = Settings::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        match result {
+            Err(Error::InvalidConfig {
+                path: error_path,
+                line,
+                column,
+                message,
+            }) => {
+                assert_eq!(error_path, path.display().to_string());
+                (line, column, message)
+            }
+            Err(e) => panic!("{e}"),
+            Ok(_) => panic!("{contents:?} was loaded"),
+        }
+    }
+
+    #[test]
+    fn loads_the_config_file() {
+        let path = std::env::temp_dir().join(format!("logos-{}-valid.toml", std::process::id()));
+        std::fs::write(&path, "[keys]\n\"ctrl+x\" = \"quit\"\n\"q\" = \"none\"\n").unwrap();
+        let settings = Settings::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        let settings = settings.unwrap();
+        let map = KeyMap::from_bindings(&settings.keys);
+        assert!(matches!(
+            map.get(&KeyCode::Char('x'), KeyModifiers::CONTROL),
+            Some(UserAction::Quit)
+        ));
+        assert!(map.get(&KeyCode::Char('q'), KeyModifiers::NONE).is_none());
+    }
+
+    #[test]
+    fn reports_where_the_config_is_invalid() {
+        let (line, column, message) =
+            config_error("[keys]\n\"j\" = \"down\"\n\"hyper+x\" = \"quit\"\n");
+        assert_eq!((line, column), (3, 1));
+        assert!(message.contains("unknown modifier \"hyper\""), "{message}");
+
+        let (line, column, message) = config_error("[layout]\nbooks = 150\n");
+        assert_eq!((line, column), (2, 9));
+        assert!(message.contains("150 is not a percentage"), "{message}");
+
+        let (line, column, message) = config_error("icons = \"auto\"\n[keys]\n\"j\" = \"fly\"\n");
+        assert_eq!((line, column), (3, 7));
+        assert!(message.contains("fly"), "{message}");
+
+        let (line, column, message) = config_error("icons = \"auto\"\ntheme = \"nope\"\n");
+        assert_eq!((line, column), (2, 9));
+        assert!(message.contains("nope"), "{message}");
+    }
+}
@@ -0,0 +1,165 @@
+use crate::filesystem::write_atomic;
+use crate::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Something the user makes, like a bookmark, that is kept in a `Store`.
+pub trait Stored: Serialize + DeserializeOwned {
+    /// Name of the items in the file and in messages, e.g. "bookmarks".
+    const NAME: &'static str;
+    /// Version of the file, bumped when the format changes.
+    const VERSION: u32;
+
+    /// Where the items are saved, `None` when there is no data directory.
+    fn file() -> Option<PathBuf>;
+}
+
+/// The items of the user, saved as JSON after every change, e.g.
+/// `{"version": 1, "bookmarks": [...]}`.
+pub struct Store<T> {
+    /// Where the items are saved, `None` keeps them in memory only.
+    path: Option<PathBuf>,
+    items: Vec<T>,
+}
+
+impl<T: Stored> Store<T> {
+    /// Loads the items from the data directory.
+    pub fn load() -> Result<Store<T>> {
+        match T::file() {
+            Some(path) => Self::from_file(path),
+            None => {
+                warn!("No data directory, {} will not be saved", T::NAME);
+                Ok(Self::in_memory())
+            }
+        }
+    }
+
+    /// Items that are not saved, used when the file can not be read so it does not get
+    /// overwritten.
+    pub fn in_memory() -> Store<T> {
+        Store {
+            path: None,
+            items: Vec::new(),
+        }
+    }
+
+    /// Loads the items from `path`, there are none when it does not exist yet.
+    pub fn from_file(path: PathBuf) -> Result<Store<T>> {
+        let items = match std::fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents, &path)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        info!("Loaded {} {} from {path:?}", items.len(), T::NAME);
+
+        Ok(Store {
+            path: Some(path),
+            items,
+        })
+    }
+
+    fn parse(contents: &str, path: &Path) -> Result<Vec<T>> {
+        let mut file: serde_json::Map<String, serde_json::Value> = serde_json::from_str(contents)?;
+        let version = file.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+        if version > T::VERSION {
+            return Err(Error::UnsupportedVersion(
+                path.display().to_string(),
+                version,
+            ));
+        }
+        let items = file.remove(T::NAME).unwrap_or_default();
+        Ok(serde_json::from_value(items)?)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let file = StoreFile(&self.items);
+        write_atomic(path, &serde_json::to_string_pretty(&file)?)?;
+        debug!("Saved {} {} to {path:?}", self.items.len(), T::NAME);
+        Ok(())
+    }
+
+    /// Changes the items and saves them.
+    pub fn change<R>(&mut self, change: impl FnOnce(&mut Vec<T>) -> R) -> Result<R> {
+        let result = change(&mut self.items);
+        self.save()?;
+        Ok(result)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+/// The items with the version of the file, under the name of the items.
+struct StoreFile<'a, T>(&'a [T]);
+
+impl<T: Stored> Serialize for StoreFile<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("version", &T::VERSION)?;
+        map.serialize_entry(T::NAME, self.0)?;
+        map.end()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bookmarks::Bookmark;
+
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("logos-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn saves_and_loads_the_items() {
+        let path = temp_file("bookmarks.json");
+        let mut bookmarks = Store::<Bookmark>::from_file(path.clone()).unwrap();
+        assert!(bookmarks.is_empty());
+        bookmarks
+            .add(Bookmark::new("John", (3, 16), (3, 17)))
+            .unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let file: serde_json::Value = serde_json::from_str(&contents).unwrap();
+        assert_eq!(file["version"], 1);
+        assert_eq!(file["bookmarks"][0]["name"], "John 3:16-17");
+
+        let bookmarks = Store::<Bookmark>::from_file(path.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(bookmarks.get(0).unwrap().location(), "John 3:16-17");
+    }
+
+    #[test]
+    fn rejects_files_it_can_not_read() {
+        let path = Path::new("bookmarks.json");
+        for contents in [
+            "[]",
+            "42",
+            r#"{"version": 2, "bookmarks": []}"#,
+            r#"{"version": 1, "bookmarks": {}}"#,
+            r#"{"version": 1}"#,
+        ] {
+            assert!(
+                Store::<Bookmark>::parse(contents, path).is_err(),
+                "{contents}"
+            );
+        }
+    }
+}
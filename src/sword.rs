@@ -0,0 +1,955 @@
+use crate::prelude::*;
+use crate::versification::Versification;
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+
+/// The CrossWire SWORD modules in `sword_dirs`, sorted by name.  When names clash, the first
+/// directory wins.  Only the drivers most modules use can be read: `zText`/`zCom` and
+/// `rawText`/`rawCom` for verses, `RawLD` for lexicons.
+pub fn discover() -> Vec<SwordModule> {
+    let mut modules: Vec<SwordModule> = Vec::new();
+    for dir in sword_dirs() {
+        let Ok(entries) = std::fs::read_dir(dir.join("mods.d")) else {
+            continue;
+        };
+        let mut confs: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| is_conf_file(p))
+            .collect();
+        confs.sort();
+
+        for conf in confs {
+            match SwordModule::open(&conf) {
+                Ok(module) if modules.iter().any(|m| m.name == module.name) => {
+                    debug!("Skipping {conf:?}, {} was found before", module.name);
+                }
+                Ok(module) => modules.push(module),
+                Err(e) => debug!("Skipping SWORD module {conf:?}: {e}"),
+            }
+        }
+    }
+    modules.sort_by(|a, b| a.name.cmp(&b.name));
+    modules
+}
+
+/// Looks up an installed module by name, ignoring case.
+pub fn find(name: &str) -> Result<SwordModule> {
+    discover()
+        .into_iter()
+        .find(|m| m.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| Error::UnsupprtedTranslation(name.to_string()))
+}
+
+pub fn is_conf_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("conf"))
+}
+
+/// Whether the text is a module conf file, e.g. "[KJV]\nDataPath=...".
+pub fn is_conf(text: &str) -> bool {
+    text.trim_start().starts_with('[') && text.contains("ModDrv=")
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The settings of a module, from its file in `mods.d`.
+#[derive(Debug, Clone, Default)]
+pub struct Conf {
+    pub name: String,
+    /// In the order of the file, keys like `GlobalOptionFilter` can be repeated.
+    pub entries: Vec<(String, String)>,
+}
+
+impl Conf {
+    /// Parses the `[Name]` and `Key=Value` lines.  A value continues on the next line when the
+    /// line ends with a backslash.
+    pub fn parse(text: &str) -> Result<Conf> {
+        let mut conf = Conf::default();
+        let mut lines = text.lines();
+        while let Some(line) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                conf.name = name.trim().to_string();
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            let mut value = value.trim().to_string();
+            while let Some(start) = value.strip_suffix('\\') {
+                value = format!("{start}\n{}", lines.next().unwrap_or_default().trim());
+            }
+            conf.entries.push((key.trim().to_string(), value));
+        }
+
+        match conf.name.is_empty() {
+            true => Err(Error::UnsupportedFormat(
+                "a SWORD conf file without a [Name]".to_string(),
+            )),
+            false => Ok(conf),
+        }
+    }
+
+    /// The first value of the key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn description(&self) -> &str {
+        self.get("Description").unwrap_or(&self.name)
+    }
+
+    pub fn language(&self) -> &str {
+        self.get("Lang").unwrap_or_default()
+    }
+
+    pub fn rights(&self) -> &str {
+        self.get("DistributionLicense")
+            .or_else(|| self.get("Copyright"))
+            .unwrap_or_default()
+    }
+
+    /// SWORD numbers verses as in the KJV unless the module says otherwise.
+    pub fn versification(&self) -> &str {
+        self.get("Versification").unwrap_or("KJV")
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// What a module contains, from its driver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleKind {
+    Bible,
+    Commentary,
+    Lexicon,
+}
+
+impl fmt::Display for ModuleKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ModuleKind::Bible => write!(f, "bible"),
+            ModuleKind::Commentary => write!(f, "commentary"),
+            ModuleKind::Lexicon => write!(f, "lexicon"),
+        }
+    }
+}
+
+/// How the entries of a module are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Storage {
+    /// Verses in compressed blocks, `zText` and `zCom`.  The 4 variants have 32 bit sizes.
+    Compressed { wide: bool },
+    /// Verses one after the other, `rawText` and `rawCom`.
+    Raw { wide: bool },
+    /// Entries by key, `RawLD`.
+    Keyed { wide: bool },
+}
+
+/// How the text of the entries is marked up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Markup {
+    Osis,
+    Thml,
+    Gbf,
+    Plain,
+}
+
+/// An installed module.  The data is only read when entries are looked up.
+#[derive(Debug, Clone)]
+pub struct SwordModule {
+    pub name: String,
+    pub kind: ModuleKind,
+    pub markup: Markup,
+    pub conf: Conf,
+    /// The conf file in `mods.d`.
+    pub path: PathBuf,
+    /// The directory of verse modules, or the path without the extensions for lexicons.
+    data: PathBuf,
+    storage: Storage,
+    compression: Option<String>,
+    versification: &'static Versification,
+    latin1: bool,
+}
+
+impl SwordModule {
+    /// Reads the conf file of a module.  Modules that can not be read are an error, e.g. ones
+    /// that are locked or use another versification.
+    pub fn open(path: &Path) -> Result<SwordModule> {
+        let conf = Conf::parse(&std::fs::read_to_string(path)?)?;
+        let unsupported = |what: String| Error::UnsupportedFormat(format!("{}: {what}", conf.name));
+
+        let driver = conf.get("ModDrv").unwrap_or_default();
+        let (kind, storage) = match driver.to_lowercase().as_str() {
+            "ztext" => (ModuleKind::Bible, Storage::Compressed { wide: false }),
+            "ztext4" => (ModuleKind::Bible, Storage::Compressed { wide: true }),
+            "rawtext" => (ModuleKind::Bible, Storage::Raw { wide: false }),
+            "rawtext4" => (ModuleKind::Bible, Storage::Raw { wide: true }),
+            "zcom" => (ModuleKind::Commentary, Storage::Compressed { wide: false }),
+            "zcom4" => (ModuleKind::Commentary, Storage::Compressed { wide: true }),
+            "rawcom" => (ModuleKind::Commentary, Storage::Raw { wide: false }),
+            "rawcom4" => (ModuleKind::Commentary, Storage::Raw { wide: true }),
+            "rawld" => (ModuleKind::Lexicon, Storage::Keyed { wide: false }),
+            "rawld4" => (ModuleKind::Lexicon, Storage::Keyed { wide: true }),
+            _ => return Err(unsupported(format!("the {driver} driver"))),
+        };
+        if conf.get("CipherKey").is_some() {
+            return Err(unsupported("encrypted modules".to_string()));
+        }
+        let compression = conf.get("CompressType").map(str::to_uppercase);
+        if let Some(other) = compression
+            .as_deref()
+            .filter(|c| !matches!(*c, "ZIP" | "XZ"))
+        {
+            return Err(unsupported(format!("{other} compression")));
+        }
+        // The layout of the files can only be worked out for systems whose verses are known.
+        let versification = conf.versification();
+        let versification = Versification::from_name(versification)
+            .filter(|v| v.books().next().is_some())
+            .ok_or_else(|| unsupported(format!("the {versification} versification")))?;
+
+        // The data path is relative to the directory that has `mods.d`.
+        let root = path
+            .parent()
+            .and_then(Path::parent)
+            .unwrap_or(Path::new("."));
+        let data_path = conf.get("DataPath").unwrap_or_default();
+        let data = root.join(data_path.trim_start_matches("./").trim_end_matches('/'));
+
+        Ok(SwordModule {
+            name: conf.name.clone(),
+            kind,
+            markup: match conf.get("SourceType").map(str::to_lowercase).as_deref() {
+                Some("osis") => Markup::Osis,
+                Some("thml") => Markup::Thml,
+                Some("gbf") => Markup::Gbf,
+                _ => Markup::Plain,
+            },
+            latin1: conf
+                .get("Encoding")
+                .is_none_or(|e| e.eq_ignore_ascii_case("latin-1")),
+            path: path.to_path_buf(),
+            data,
+            storage,
+            compression,
+            versification,
+            conf,
+        })
+    }
+
+    pub fn versification(&self) -> &'static Versification {
+        self.versification
+    }
+
+    /// Opens the verse data for reading a lot of entries, e.g. a whole bible.
+    pub fn verses(&self) -> Result<VerseReader<'_>> {
+        match self.storage {
+            Storage::Keyed { .. } => Err(Error::UnsupportedFormat(format!(
+                "{} is a {}, not keyed by verse",
+                self.name, self.kind
+            ))),
+            _ => Ok(VerseReader {
+                module: self,
+                testaments: [None, None],
+                block: None,
+            }),
+        }
+    }
+
+    /// The entry of a lexicon, e.g. "G3056" in Strong's Greek.  Keys are compared ignoring case
+    /// and Strong's numbers are padded the way the SWORD lexicons have them ("03056").
+    pub fn lexicon_entry(&self, key: &str) -> Result<Option<String>> {
+        let Storage::Keyed { wide } = self.storage else {
+            return Err(Error::UnsupportedFormat(format!(
+                "{} is a {}, not a lexicon",
+                self.name, self.kind
+            )));
+        };
+        let mut index = File::open(self.data.with_extension("idx"))?;
+        let mut data = File::open(self.data.with_extension("dat"))?;
+        let entry_size = if wide { 8 } else { 6 };
+        let count = index.metadata()?.len() / entry_size;
+
+        // The keys are sorted, the entries are "KEY\ntext".
+        let mut find = |key: &str| -> Result<Option<String>> {
+            let (mut low, mut high) = (0, count);
+            while low < high {
+                let middle = (low + high) / 2;
+                let (start, size) = read_index_entry(&mut index, middle * entry_size, wide)?;
+                let entry = self.decode(&read_at(&mut data, start as u64, size)?);
+                let (entry_key, text) = entry.split_once('\n').unwrap_or((entry.as_str(), ""));
+                match entry_key.trim().to_uppercase().as_str().cmp(key) {
+                    std::cmp::Ordering::Less => low = middle + 1,
+                    std::cmp::Ordering::Greater => high = middle,
+                    std::cmp::Ordering::Equal => return Ok(Some(text.trim().to_string())),
+                }
+            }
+            Ok(None)
+        };
+
+        // Entries that are the same as another one link to it, links that go around in a circle
+        // lead nowhere.
+        let mut key = lexicon_key(key);
+        let mut visited = Vec::new();
+        while let Some(text) = find(&key)? {
+            let Some(target) = text.strip_prefix("@LINK") else {
+                return Ok((!text.is_empty()).then_some(text));
+            };
+            visited.push(std::mem::replace(&mut key, lexicon_key(target)));
+            if visited.contains(&key) {
+                warn!("{}: the entries {visited:?} link to each other", self.name);
+                return Ok(None);
+            }
+        }
+        Ok(None)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        match self.latin1 {
+            true if std::str::from_utf8(bytes).is_err() => {
+                bytes.iter().map(|b| *b as char).collect()
+            }
+            _ => String::from_utf8_lossy(bytes).into_owned(),
+        }
+    }
+
+    fn decompress(&self, compressed: &[u8]) -> Result<Vec<u8>> {
+        let mut block = Vec::new();
+        match self.compression.as_deref() {
+            Some("XZ") => xz2::read::XzDecoder::new(compressed).read_to_end(&mut block)?,
+            _ => flate2::read::ZlibDecoder::new(compressed).read_to_end(&mut block)?,
+        };
+        Ok(block)
+    }
+}
+
+/// "G3056" -> "03056", other keys are upper cased.
+fn lexicon_key(key: &str) -> String {
+    let key = key.trim().to_uppercase();
+    match key
+        .strip_prefix(['G', 'H'])
+        .filter(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+    {
+        Some(number) => format!("{number:0>5}"),
+        None => key,
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Reads verse entries, keeping the files open and the last decompressed block.
+pub struct VerseReader<'a> {
+    module: &'a SwordModule,
+    /// Index and data files of the Old and New Testament, opened when first needed.
+    testaments: [Option<(File, File, Option<File>)>; 2],
+    /// (testament, block number, text) of the last block that was decompressed.
+    block: Option<(usize, u64, Vec<u8>)>,
+}
+
+impl VerseReader<'_> {
+    /// The entry of a verse, `None` when it is empty or not in the versification.
+    pub fn entry(&mut self, book: &str, chapter: usize, verse: usize) -> Result<Option<String>> {
+        let Some((testament, index)) = self.position(book, chapter, verse) else {
+            return Ok(None);
+        };
+        let module = self.module;
+        if !self.open(testament)? {
+            return Ok(None);
+        }
+        let (verses, data, blocks) = self.testaments[testament].as_mut().expect("Opened above");
+
+        let bytes = match module.storage {
+            Storage::Raw { wide } => {
+                let (start, size) = read_index_entry(verses, index * entry_size(wide, 6), wide)?;
+                read_at(data, start as u64, size)?
+            }
+            Storage::Compressed { wide } => {
+                let size = entry_size(wide, 10);
+                let entry = read_at(verses, index * size, size as u32)?;
+                if entry.len() < size as usize {
+                    return Ok(None);
+                }
+                let block = u32::from_le_bytes(entry[0..4].try_into().expect("4 bytes")) as u64;
+                let start = u32::from_le_bytes(entry[4..8].try_into().expect("4 bytes")) as usize;
+                let length = match wide {
+                    true => u32::from_le_bytes(entry[8..12].try_into().expect("4 bytes")) as usize,
+                    false => u16::from_le_bytes(entry[8..10].try_into().expect("2 bytes")) as usize,
+                };
+                if length == 0 {
+                    return Ok(None);
+                }
+
+                let cached =
+                    matches!(&self.block, Some((t, b, _)) if *t == testament && *b == block);
+                if !cached {
+                    let blocks = blocks.as_mut().expect("Opened for compressed modules");
+                    let (start, size) = read_index_entry(blocks, block * 12, true)?;
+                    let compressed = read_at(data, start as u64, size)?;
+                    self.block = Some((testament, block, module.decompress(&compressed)?));
+                }
+                let text = &self.block.as_ref().expect("Decompressed above").2;
+                text.get(start..start + length).unwrap_or_default().to_vec()
+            }
+            Storage::Keyed { .. } => unreachable!("Checked by SwordModule::verses"),
+        };
+
+        let text = module.decode(&bytes);
+        let text = text.trim();
+        Ok((!text.is_empty()).then(|| text.to_string()))
+    }
+
+    /// The testament (0 for the Old, 1 for the New) and the index of the verse in its files.
+    ///
+    /// Every testament starts with an entry for the module and one for the testament, then each
+    /// book has an entry before its chapters and each chapter one before its verses.
+    fn position(&self, book: &str, chapter: usize, verse: usize) -> Option<(usize, u64)> {
+        let versification = self.module.versification;
+        let testament = [false, true]
+            .into_iter()
+            .position(|new| versification.testament(new).any(|(id, _)| *id == book))?;
+        let chapters = versification.chapters(book)?;
+        if chapter == 0 || chapter > chapters.len() || verse > chapters[chapter - 1] as usize {
+            return None;
+        }
+
+        let mut index = 1;
+        for (id, verses) in versification.testament(testament == 1) {
+            index += 1;
+            if *id == book {
+                let before: u64 = verses[..chapter - 1].iter().map(|v| *v as u64 + 1).sum();
+                return Some((testament, index + before + 1 + verse as u64));
+            }
+            index += verses.iter().map(|v| *v as u64 + 1).sum::<u64>();
+        }
+        None
+    }
+
+    /// Opens the verse index, data and block index of a testament.  A module without one of the
+    /// testaments has no files for it.
+    fn open(&mut self, testament: usize) -> Result<bool> {
+        if self.testaments[testament].is_some() {
+            return Ok(true);
+        }
+        let prefix = self.module.data.join(["ot", "nt"][testament]);
+        let with = |extension: &str| prefix.with_extension(extension);
+        let files = match self.module.storage {
+            Storage::Compressed { .. } => (
+                File::open(with("bzv")),
+                File::open(with("bzz")),
+                Some(File::open(with("bzs"))),
+            ),
+            _ => (File::open(with("vss")), File::open(&prefix), None),
+        };
+        self.testaments[testament] = match files {
+            (Ok(verses), Ok(data), None) => Some((verses, data, None)),
+            (Ok(verses), Ok(data), Some(Ok(blocks))) => Some((verses, data, Some(blocks))),
+            _ => None,
+        };
+        Ok(self.testaments[testament].is_some())
+    }
+}
+
+/// Size of an index entry, the wide variants have a 32 bit size instead of a 16 bit one.
+fn entry_size(wide: bool, size: u64) -> u64 {
+    match wide {
+        true => size + 2,
+        false => size,
+    }
+}
+
+/// The start and size of the entry at `offset` of an index file.
+fn read_index_entry(file: &mut File, offset: u64, wide: bool) -> Result<(u32, u32)> {
+    let entry = read_at(file, offset, if wide { 8 } else { 6 })?;
+    if entry.len() < 6 {
+        return Ok((0, 0));
+    }
+    let start = u32::from_le_bytes(entry[0..4].try_into().expect("4 bytes"));
+    let size = match wide {
+        true => u32::from_le_bytes(entry[4..8].try_into().expect("4 bytes")),
+        false => u16::from_le_bytes(entry[4..6].try_into().expect("2 bytes")) as u32,
+    };
+    Ok((start, size))
+}
+
+/// Up to `size` bytes at `offset`, less at the end of the file.
+fn read_at(file: &mut File, offset: u64, size: u32) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(size as usize);
+    file.seek(SeekFrom::Start(offset))?;
+    file.take(size as u64).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Where modules are installed, `$SWORD_PATH` first and then where the SWORD library looks.
+pub fn sword_dirs() -> Vec<PathBuf> {
+    let from_env = std::env::var_os(SWORD_PATH_ENV).map(PathBuf::from);
+    let home = dirs::home_dir().map(|home| home.join(".sword"));
+    let data = dirs::data_dir().map(|dir| dir.join("sword"));
+    from_env
+        .into_iter()
+        .chain(home)
+        .chain(data)
+        .chain([
+            PathBuf::from("/usr/share/sword"),
+            PathBuf::from("/usr/local/share/sword"),
+        ])
+        .collect()
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Converts an entry to OSIS, so that it can be read like an OSIS bible.  Chapters and verses
+/// come from the versification, the ones in the entry are left out.
+pub fn to_osis(markup: Markup, entry: &str) -> String {
+    match markup {
+        Markup::Osis => strip_tags(entry, |name| {
+            matches!(name, "chapter" | "verse" | "div" | "osis" | "osisText")
+        }),
+        Markup::Thml => {
+            let without_notes = remove_between(entry, "<note", "</note>");
+            strip_tags(&without_notes, |_| true)
+        }
+        Markup::Gbf => gbf_to_osis(entry),
+        Markup::Plain => escape(entry),
+    }
+}
+
+/// The text of an entry without markup, e.g. a commentary.
+pub fn plain_text(markup: Markup, entry: &str) -> String {
+    let text = match markup {
+        Markup::Gbf => gbf_to_osis(entry),
+        _ => ["<br/>", "<br />", "<lb/>", "</p>"]
+            .iter()
+            .fold(entry.to_string(), |text, tag| text.replace(tag, "\n")),
+    };
+    let text = strip_tags(&remove_between(&text, "<note", "</note>"), |_| true);
+    let text = quick_xml::escape::unescape(&text).map_or(text.clone(), |t| t.into_owned());
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Removes the tags `strip` returns true for, keeping their content.
+fn strip_tags(text: &str, strip: impl Fn(&str) -> bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start..start + end + 1];
+        let name = tag[1..]
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/' || c == '>')
+            .next()
+            .unwrap_or_default();
+        if !strip(name) {
+            out.push_str(tag);
+        }
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Removes everything from `start` up to and including `end`.
+fn remove_between(text: &str, start: &str, end: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find(start) {
+        out.push_str(&rest[..i]);
+        rest = rest[i..]
+            .find(end)
+            .map_or("", |j| &rest[i + j + end.len()..]);
+    }
+    out.push_str(rest);
+    out
+}
+
+/// GBF has tokens like `<FI>` and `<Fi>` around italics.  Added words and the words of Christ
+/// are kept, notes and everything else is left out.
+fn gbf_to_osis(entry: &str) -> String {
+    let mut out = String::with_capacity(entry.len());
+    let mut rest = entry;
+    let mut in_note = false;
+    while let Some(start) = rest.find('<') {
+        if !in_note {
+            out.push_str(&escape(&rest[..start]));
+        }
+        let end = rest[start..]
+            .find('>')
+            .map_or(rest.len(), |i| start + i + 1);
+        let token = &rest[start + 1..end.saturating_sub(1).max(start + 1)];
+        match token.get(..2).unwrap_or(token) {
+            "FI" => out.push_str("<transChange type=\"added\">"),
+            "Fi" => out.push_str("</transChange>"),
+            "FR" => out.push_str("<q who=\"Jesus\">"),
+            "Fr" => out.push_str("</q>"),
+            "RF" => in_note = true,
+            "Rf" => in_note = false,
+            _ => {}
+        }
+        rest = &rest[end..];
+    }
+    if !in_note {
+        out.push_str(&escape(rest));
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    quick_xml::escape::escape(text).into_owned()
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bible::Bible;
+    use std::io::Write;
+
+    /// A module in a directory of its own, which is removed with it.
+    struct Fixture {
+        root: PathBuf,
+        conf: PathBuf,
+    }
+
+    impl Fixture {
+        fn new(name: &str, conf: &str, verses: &[(&str, usize, usize, &[u8])]) -> Fixture {
+            Self::with_blocks(name, conf, verses, 1)
+        }
+
+        /// Writes the `verses` at their place in the versification of the `conf`, with
+        /// `per_block` verses in each block of zText modules.
+        fn with_blocks(
+            name: &str,
+            conf: &str,
+            verses: &[(&str, usize, usize, &[u8])],
+            per_block: usize,
+        ) -> Fixture {
+            let root =
+                std::env::temp_dir().join(format!("logos-sword-{}-{name}", std::process::id()));
+            let data = root.join("modules").join(name.to_lowercase());
+            std::fs::create_dir_all(root.join("mods.d")).unwrap();
+            std::fs::create_dir_all(&data).unwrap();
+            let conf_path = root
+                .join("mods.d")
+                .join(format!("{}.conf", name.to_lowercase()));
+            let conf = format!(
+                "[{name}]\nDataPath=./modules/{}/\n{conf}",
+                name.to_lowercase()
+            );
+            std::fs::write(&conf_path, conf).unwrap();
+            let fixture = Fixture {
+                root,
+                conf: conf_path,
+            };
+            if verses.is_empty() {
+                return fixture;
+            }
+
+            let module = fixture.module();
+            let reader = module.verses().unwrap();
+            for testament in 0..2 {
+                let mut entries: Vec<(u64, &[u8])> = verses
+                    .iter()
+                    .filter_map(|(book, chapter, verse, text)| {
+                        let (t, index) = reader.position(book, *chapter, *verse)?;
+                        (t == testament).then_some((index, *text))
+                    })
+                    .collect();
+                if entries.is_empty() {
+                    continue;
+                }
+                entries.sort();
+                let prefix = data.join(["ot", "nt"][testament]);
+                let count = entries.last().unwrap().0 as usize + 1;
+                match module.storage {
+                    Storage::Raw { .. } => {
+                        let mut index = vec![0u8; count * 6];
+                        let mut text = Vec::new();
+                        for (i, entry) in &entries {
+                            let i = *i as usize * 6;
+                            index[i..i + 4].copy_from_slice(&(text.len() as u32).to_le_bytes());
+                            index[i + 4..i + 6]
+                                .copy_from_slice(&(entry.len() as u16).to_le_bytes());
+                            text.extend_from_slice(entry);
+                        }
+                        std::fs::write(prefix.with_extension("vss"), index).unwrap();
+                        std::fs::write(&prefix, text).unwrap();
+                    }
+                    Storage::Compressed { .. } => {
+                        let (mut verses, mut blocks, mut data) =
+                            (vec![0u8; count * 10], vec![], vec![]);
+                        for (block, chunk) in entries.chunks(per_block).enumerate() {
+                            let mut text = Vec::new();
+                            for (i, entry) in chunk {
+                                let i = *i as usize * 10;
+                                verses[i..i + 4].copy_from_slice(&(block as u32).to_le_bytes());
+                                verses[i + 4..i + 8]
+                                    .copy_from_slice(&(text.len() as u32).to_le_bytes());
+                                verses[i + 8..i + 10]
+                                    .copy_from_slice(&(entry.len() as u16).to_le_bytes());
+                                text.extend_from_slice(entry);
+                            }
+                            let mut encoder = flate2::write::ZlibEncoder::new(
+                                Vec::new(),
+                                flate2::Compression::default(),
+                            );
+                            encoder.write_all(&text).unwrap();
+                            let compressed = encoder.finish().unwrap();
+                            blocks.extend_from_slice(&(data.len() as u32).to_le_bytes());
+                            blocks.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+                            blocks.extend_from_slice(&(text.len() as u32).to_le_bytes());
+                            data.extend_from_slice(&compressed);
+                        }
+                        std::fs::write(prefix.with_extension("bzv"), verses).unwrap();
+                        std::fs::write(prefix.with_extension("bzs"), blocks).unwrap();
+                        std::fs::write(prefix.with_extension("bzz"), data).unwrap();
+                    }
+                    Storage::Keyed { .. } => unreachable!("Only verse modules are made"),
+                }
+            }
+            fixture
+        }
+
+        /// A `RawLD` lexicon with the (key, text) `entries`, which are sorted by key.
+        fn lexicon(name: &str, entries: &[(&str, &str)]) -> Fixture {
+            let fixture = Self::new(name, "ModDrv=RawLD\nEncoding=UTF-8\n", &[]);
+            let (mut index, mut data) = (Vec::new(), Vec::new());
+            for (key, text) in entries {
+                let entry = format!("{key}\n{text}");
+                index.extend_from_slice(&(data.len() as u32).to_le_bytes());
+                index.extend_from_slice(&(entry.len() as u16).to_le_bytes());
+                data.extend_from_slice(entry.as_bytes());
+            }
+            let prefix = fixture.module().data;
+            std::fs::write(prefix.with_extension("idx"), index).unwrap();
+            std::fs::write(prefix.with_extension("dat"), data).unwrap();
+            fixture
+        }
+
+        fn module(&self) -> SwordModule {
+            SwordModule::open(&self.conf).unwrap()
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn positions_follow_the_versification() {
+        let kjv = Fixture::new("PosKjv", "ModDrv=rawText\n", &[]);
+        let kjv = kjv.module();
+        let kjv = kjv.verses().unwrap();
+        // The module and the testament, then the book and the chapter.
+        assert_eq!(kjv.position("Gen", 1, 1), Some((0, 4)));
+        assert_eq!(kjv.position("Gen", 1, 31), Some((0, 34)));
+        assert_eq!(kjv.position("Gen", 2, 1), Some((0, 36)));
+        // Genesis has an entry of its own, 50 chapters and 1533 verses.
+        assert_eq!(
+            kjv.position("Exod", 1, 1),
+            Some((0, 2 + (1 + 50 + 1533) + 2))
+        );
+        assert_eq!(kjv.position("Matt", 1, 1), Some((1, 4)));
+        assert_eq!(kjv.position("Gen", 1, 32), None);
+        assert_eq!(kjv.position("3John", 1, 15), None);
+        assert_eq!(kjv.position("Tob", 1, 1), None);
+
+        let nrsv = Fixture::new("PosNrsv", "ModDrv=rawText\nVersification=NRSV\n", &[]);
+        let nrsv = nrsv.module();
+        let nrsv = nrsv.verses().unwrap();
+        let shifted = |book, chapter, verse, by| {
+            let (testament, index) = kjv.position(book, chapter, verse).unwrap();
+            assert_eq!(
+                nrsv.position(book, chapter, verse),
+                Some((testament, index + by))
+            );
+        };
+        shifted("Gen", 1, 1, 0);
+        shifted("3John", 1, 14, 0);
+        shifted("Jude", 1, 1, 1);
+        shifted("Rev", 12, 17, 1);
+        shifted("Rev", 13, 1, 2);
+        assert!(nrsv.position("3John", 1, 15).is_some());
+        assert!(nrsv.position("Rev", 12, 18).is_some());
+    }
+
+    #[test]
+    fn refuses_unknown_layouts() {
+        let vulgate = Fixture::new("Vulgate", "ModDrv=zText\nVersification=Vulg\n", &[]);
+        assert!(SwordModule::open(&vulgate.conf).is_err());
+        let kjva = Fixture::new("Kjva", "ModDrv=zText\nVersification=KJVA\n", &[]);
+        assert_eq!(kjva.module().versification().name, "KJVA");
+    }
+
+    #[test]
+    fn reads_ztext_modules_a_block_at_a_time() {
+        let fixture = Fixture::with_blocks(
+            "Blocks",
+            "ModDrv=zText\nEncoding=UTF-8\n",
+            &[
+                ("Gen", 1, 1, b"In the beginning"),
+                ("Matt", 1, 1, b"The book of the generation"),
+                ("Matt", 1, 2, b"Abraham begat Isaac"),
+                ("Matt", 1, 3, b"And Judas begat Phares"),
+            ],
+            2,
+        );
+        let module = fixture.module();
+        let mut verses = module.verses().unwrap();
+        let mut read = |book, chapter, verse| {
+            let entry = verses.entry(book, chapter, verse).unwrap();
+            let block = verses.block.as_ref().map(|(t, b, _)| (*t, *b));
+            (entry, block)
+        };
+
+        let text = |s: &str| Some(s.to_string());
+        assert_eq!(
+            read("Matt", 1, 1),
+            (text("The book of the generation"), Some((1, 0)))
+        );
+        assert_eq!(
+            read("Matt", 1, 2),
+            (text("Abraham begat Isaac"), Some((1, 0)))
+        );
+        assert_eq!(
+            read("Matt", 1, 3),
+            (text("And Judas begat Phares"), Some((1, 1)))
+        );
+        assert_eq!(read("Gen", 1, 1), (text("In the beginning"), Some((0, 0))));
+        // Past the end of the index, and a verse without text in the middle of it.
+        assert_eq!(read("Matt", 1, 4).0, None);
+        assert_eq!(read("Gen", 1, 2).0, None);
+    }
+
+    #[test]
+    fn reads_raw_text_in_latin1() {
+        let verses: &[(&str, usize, usize, &[u8])] = &[
+            (
+                "John",
+                1,
+                1,
+                b"Im Anfang war das Wort, und das Wort war bei Gott.",
+            ),
+            (
+                "John",
+                1,
+                2,
+                b"Dasselbe war im Anfang bei Gott, so gr\xfc\xdf.",
+            ),
+            (
+                "John",
+                1,
+                3,
+                "Alle Dinge sind durch dasselbe gemacht, gr\u{fc}\u{df}.".as_bytes(),
+            ),
+        ];
+        let fixture = Fixture::new("Latin", "ModDrv=rawText\n", verses);
+        let module = fixture.module();
+        let mut reader = module.verses().unwrap();
+        let entry = reader.entry("John", 1, 2).unwrap().unwrap();
+        assert_eq!(entry, "Dasselbe war im Anfang bei Gott, so gr\u{fc}\u{df}.");
+        // Modules that say they are Latin-1 can still have UTF-8 in them.
+        let entry = reader.entry("John", 1, 3).unwrap().unwrap();
+        assert_eq!(
+            entry,
+            "Alle Dinge sind durch dasselbe gemacht, gr\u{fc}\u{df}."
+        );
+
+        let fixture = Fixture::new("Utf", "ModDrv=rawText\nEncoding=UTF-8\n", verses);
+        let module = fixture.module();
+        let entry = module
+            .verses()
+            .unwrap()
+            .entry("John", 1, 2)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            entry,
+            "Dasselbe war im Anfang bei Gott, so gr\u{fffd}\u{fffd}."
+        );
+    }
+
+    #[test]
+    fn converts_gbf_and_thml_to_osis() {
+        assert_eq!(
+            to_osis(
+                Markup::Gbf,
+                "<FI>And<Fi> he said<RF>a note<Rf>, <FR>Come & see<Fr>."
+            ),
+            "<transChange type=\"added\">And</transChange> he said, \
+             <q who=\"Jesus\">Come &amp; see</q>."
+        );
+        assert_eq!(
+            to_osis(
+                Markup::Thml,
+                "He <i>said</i><note>a note</note>, <font>come</font>."
+            ),
+            "He said, come."
+        );
+
+        let verses: &[(&str, usize, usize, &[u8])] = &[
+            (
+                "John",
+                1,
+                1,
+                b"In the beginning <FI>was<Fi> the Word.<RF>Or, reason<Rf>",
+            ),
+            ("John", 1, 2, b"The same <FR>was<Fr> in the beginning."),
+        ];
+        let fixture = Fixture::new("Gbf", "ModDrv=zText\nSourceType=GBF\n", verses);
+        let bible = Bible::from_file(&fixture.conf).unwrap();
+        let chapter = bible.get_chapter("John", 1).unwrap();
+        let text: Vec<_> = chapter
+            .get_verses()
+            .map(|v| v.collect_string(bible.get_raw_data()))
+            .collect();
+        assert_eq!(
+            text,
+            [
+                "In the beginning was the Word.",
+                "The same was in the beginning."
+            ]
+        );
+    }
+
+    #[test]
+    fn follows_links_between_lexicon_entries() {
+        let fixture = Fixture::lexicon(
+            "Links",
+            &[
+                ("03056", "logos, a word"),
+                ("03057", "@LINK G3056"),
+                ("03058", "@LINK 03059"),
+                ("03059", "@LINK 03058"),
+                ("03060", "@LINK G3060"),
+            ],
+        );
+        let module = fixture.module();
+        let entry = |key| module.lexicon_entry(key).unwrap();
+        assert_eq!(entry("g3056").as_deref(), Some("logos, a word"));
+        assert_eq!(entry("G3057").as_deref(), Some("logos, a word"));
+        assert_eq!(entry("G3058"), None);
+        assert_eq!(entry("G3060"), None);
+        assert_eq!(entry("G3061"), None);
+    }
+}
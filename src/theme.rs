@@ -0,0 +1,349 @@
+use crate::prelude::*;
+use serde::{Deserialize, Deserializer, de};
+use std::ops::Range;
+use toml::Spanned;
+
+/// Theme that is used when the config file does not pick one.
+pub const DEFAULT_THEME: &str = "dark";
+pub const BUILTIN_THEMES: [&str; 3] = ["dark", "light", "high-contrast"];
+
+/// The theme of the config file.
+pub fn theme() -> &'static Theme {
+    &settings().theme
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Declares the roles of a theme once, for both `Theme` and the optional `ThemeSpec` of the config
+/// file.
+macro_rules! roles {
+    ($($(#[doc = $doc:literal])* $role:ident,)*) => {
+        /// Styles by what they are used for, components should not pick their own.
+        #[derive(Debug, Clone)]
+        pub struct Theme {
+            $($(#[doc = $doc])* pub $role: Style,)*
+        }
+
+        /// A theme in the config file, the roles that are left out come from `extends`.
+        #[derive(Default, Deserialize)]
+        #[serde(default, deny_unknown_fields)]
+        pub struct ThemeSpec {
+            extends: Option<Spanned<String>>,
+            $($role: Option<StyleSpec>,)*
+        }
+
+        impl ThemeSpec {
+            fn apply(&self, theme: &mut Theme) {
+                $(if let Some(StyleSpec(style)) = self.$role {
+                    theme.$role = style;
+                })*
+            }
+        }
+    };
+}
+
+roles! {
+    /// Panel titles and the logo.
+    title,
+    border,
+    border_focused,
+    /// The selected item of a list.
+    selected,
+    /// Menus and messages.
+    text,
+    /// Less important text, e.g. details of a list item.
+    dim,
+    error,
+    /// Key hints in the footer.
+    hint,
+    /// Background of the footer and the command prompt.
+    bar,
+    verse_number,
+    chapter_heading,
+    /// Words added by the translators.
+    added,
+    /// Quoted speech, other than the words of Christ in red letter mode.
+    quote,
+    words_of_christ,
+    /// Matched words in search results.
+    search_hit,
+    /// The passage that was jumped to.
+    highlight,
+    /// Verses in the visual selection.
+    selection,
+    /// The verse cursor and text input cursors.
+    cursor,
+    /// The word cursor, on top of the verse cursor.
+    word_cursor,
+    /// Bars of the histogram of the concordance.
+    histogram,
+    /// Marks the verses that have a note.
+    note_marker,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::builtin(DEFAULT_THEME).unwrap_or_else(Self::dark)
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Theme {
+        Theme {
+            title: Style::new().yellow().bold(),
+            border: Style::new(),
+            border_focused: Style::new().blue(),
+            selected: Style::new().cyan().bold(),
+            text: Style::new().white(),
+            dim: Style::new().dark_gray(),
+            error: Style::new().red().bold(),
+            hint: Style::new().red().bold(),
+            bar: Style::new().bg(Color::Black),
+            verse_number: Style::new().dark_gray(),
+            chapter_heading: Style::new().blue().italic(),
+            added: Style::new().italic(),
+            quote: Style::new(),
+            words_of_christ: Style::new().red(),
+            search_hit: Style::new().yellow().bold(),
+            highlight: Style::new().black().on_yellow(),
+            selection: Style::new().on_dark_gray(),
+            cursor: Style::new().reversed(),
+            word_cursor: Style::new().black().on_cyan().not_reversed(),
+            histogram: Style::new().blue(),
+            note_marker: Style::new().yellow().bold(),
+        }
+    }
+
+    /// For terminals with a light background, where yellow and dark gray are hard to read.
+    pub fn light() -> Theme {
+        Theme {
+            title: Style::new().blue().bold(),
+            border: Style::new().gray(),
+            border_focused: Style::new().magenta(),
+            selected: Style::new().magenta().bold(),
+            text: Style::new().black(),
+            dim: Style::new().dark_gray(),
+            error: Style::new().red().bold(),
+            hint: Style::new().blue().bold(),
+            bar: Style::new().black().on_gray(),
+            verse_number: Style::new().dark_gray(),
+            chapter_heading: Style::new().blue().italic(),
+            added: Style::new().italic(),
+            quote: Style::new(),
+            words_of_christ: Style::new().red(),
+            search_hit: Style::new().magenta().bold(),
+            highlight: Style::new().black().on_light_yellow(),
+            selection: Style::new().on_gray(),
+            cursor: Style::new().reversed(),
+            word_cursor: Style::new().white().on_blue().not_reversed(),
+            histogram: Style::new().blue(),
+            note_marker: Style::new().magenta().bold(),
+        }
+    }
+
+    /// Only the brightest colours, and modifiers wherever a colour carries meaning.
+    pub fn high_contrast() -> Theme {
+        Theme {
+            title: Style::new().white().bold().underlined(),
+            border: Style::new().white(),
+            border_focused: Style::new().light_yellow().bold(),
+            selected: Style::new().black().on_white().bold(),
+            text: Style::new().white(),
+            dim: Style::new().white(),
+            error: Style::new().light_red().bold().underlined(),
+            hint: Style::new().light_yellow().bold(),
+            bar: Style::new().white().on_black(),
+            verse_number: Style::new().light_yellow().bold(),
+            chapter_heading: Style::new().white().bold().underlined(),
+            added: Style::new().italic().underlined(),
+            quote: Style::new(),
+            words_of_christ: Style::new().light_red().bold(),
+            search_hit: Style::new().black().on_light_yellow().bold(),
+            highlight: Style::new().black().on_light_cyan(),
+            selection: Style::new().black().on_white(),
+            cursor: Style::new().reversed().bold(),
+            word_cursor: Style::new().black().on_light_yellow().bold().not_reversed(),
+            histogram: Style::new().light_yellow(),
+            note_marker: Style::new().light_green().bold(),
+        }
+    }
+
+    pub fn builtin(name: &str) -> Option<Theme> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// Resolves a user theme from the config file, or a built-in one.  Errors come with the span
+    /// of the config file they are from, if there is one.
+    pub fn resolve(
+        name: &str,
+        themes: &HashMap<String, ThemeSpec>,
+    ) -> std::result::Result<Theme, (String, Option<Range<usize>>)> {
+        Self::resolve_extending(name, themes, &mut Vec::new())
+    }
+
+    fn resolve_extending<'a>(
+        name: &'a str,
+        themes: &'a HashMap<String, ThemeSpec>,
+        seen: &mut Vec<&'a str>,
+    ) -> std::result::Result<Theme, (String, Option<Range<usize>>)> {
+        let Some(spec) = themes.get(name) else {
+            return Self::builtin(name).ok_or_else(|| {
+                let builtin = BUILTIN_THEMES.join(", ");
+                let message = format!("unknown theme {name:?}, the built-in themes are {builtin}");
+                (message, None)
+            });
+        };
+        seen.push(name);
+
+        let mut theme = match &spec.extends {
+            // Changing a built-in theme under its own name.
+            Some(extends) if extends.get_ref() == name && Self::builtin(name).is_some() => {
+                Self::builtin(name).unwrap_or_default()
+            }
+            Some(extends) if seen.contains(&extends.get_ref().as_str()) => {
+                let message = format!(
+                    "themes extend each other: {} -> {}",
+                    seen.join(" -> "),
+                    extends.get_ref()
+                );
+                return Err((message, Some(extends.span())));
+            }
+            Some(extends) => Self::resolve_extending(extends.get_ref(), themes, seen)
+                .map_err(|(message, span)| (message, span.or(Some(extends.span()))))?,
+            // Without `extends` it is the built-in theme of the same name that changes.
+            None => Self::builtin(name).unwrap_or_default(),
+        };
+        spec.apply(&mut theme);
+        Ok(theme)
+    }
+
+    /// Style of the border of a panel.
+    pub fn border(&self, focused: bool) -> Style {
+        match focused {
+            true => self.border_focused,
+            false => self.border,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A style written as colours and modifiers, e.g. "yellow bold" or "black on #e0af68".
+#[derive(Clone, Copy)]
+pub struct StyleSpec(pub Style);
+
+impl std::str::FromStr for StyleSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut style = Style::new();
+        let mut words = s.split_whitespace();
+        while let Some(word) = words.next() {
+            style = match word.to_lowercase().as_str() {
+                "bold" => style.bold(),
+                "dim" => style.dim(),
+                "italic" => style.italic(),
+                "underlined" | "underline" => style.underlined(),
+                "reversed" | "reverse" => style.reversed(),
+                "crossed_out" | "strikethrough" => style.crossed_out(),
+                "on" => {
+                    let color = words.next().ok_or("expected a colour after \"on\"")?;
+                    style.bg(parse_color(color)?)
+                }
+                _ => style.fg(parse_color(word)?),
+            };
+        }
+        Ok(StyleSpec(style))
+    }
+}
+
+impl<'de> Deserialize<'de> for StyleSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+fn parse_color(color: &str) -> std::result::Result<Color, String> {
+    color
+        .parse()
+        .map_err(|_| format!("unknown colour or modifier {color:?}"))
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(s: &str) -> Style {
+        s.parse::<StyleSpec>()
+            .map(|StyleSpec(style)| style)
+            .unwrap()
+    }
+
+    fn themes(toml: &str) -> HashMap<String, ThemeSpec> {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn parses_styles() {
+        assert_eq!(style("yellow bold"), Style::new().yellow().bold());
+        assert_eq!(
+            style("black on #e0af68"),
+            Style::new().black().bg(Color::Rgb(0xe0, 0xaf, 0x68))
+        );
+        assert_eq!(
+            style("208 Italic"),
+            Style::new().fg(Color::Indexed(208)).italic()
+        );
+        assert_eq!(style(""), Style::new());
+        assert!("black on".parse::<StyleSpec>().is_err());
+        assert!("blurple".parse::<StyleSpec>().is_err());
+    }
+
+    #[test]
+    fn resolves_themes_that_extend_others() {
+        let themes = themes(
+            r##"
+[sepia]
+extends = "light"
+text = "#5b4636"
+
+[sepia-bold]
+extends = "sepia"
+title = "bold"
+
+[dark]
+error = "magenta"
+"##,
+        );
+        let theme = Theme::resolve("sepia-bold", &themes).unwrap();
+        assert_eq!(theme.title, Style::new().bold());
+        assert_eq!(theme.text, Style::new().fg(Color::Rgb(0x5b, 0x46, 0x36)));
+        assert_eq!(theme.dim, Theme::light().dim);
+
+        // Changing a built-in theme keeps the rest of it.
+        let theme = Theme::resolve("dark", &themes).unwrap();
+        assert_eq!(theme.error, Style::new().magenta());
+        assert_eq!(theme.title, Theme::dark().title);
+    }
+
+    #[test]
+    fn rejects_unknown_and_circular_themes() {
+        let (message, span) = Theme::resolve("nope", &HashMap::new()).unwrap_err();
+        assert!(message.starts_with("unknown theme \"nope\""), "{message}");
+        assert!(span.is_none());
+
+        let toml = "[a]\nextends = \"b\"\n[b]\nextends = \"a\"\n";
+        let (message, span) = Theme::resolve("a", &themes(toml)).unwrap_err();
+        assert_eq!(message, "themes extend each other: a -> b -> a");
+        assert_eq!(&toml[span.unwrap()], "\"a\"");
+    }
+}
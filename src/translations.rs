@@ -0,0 +1,293 @@
+use crate::{
+    filesystem::{is_bible_file, open_text},
+    formats::{Format, attr, resolve_entity},
+    prelude::*,
+    sword::{self, ModuleKind},
+};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use std::io::BufRead;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A translation that can be loaded, described by the `<work>` in its OSIS header.
+#[derive(Debug, Clone)]
+pub struct TranslationInfo {
+    /// Short name used to select the translation, e.g. "KJV".
+    pub name: String,
+    pub title: String,
+    pub language: String,
+    pub rights: String,
+    /// Name of the versification the file says it uses, e.g. "KJV".
+    pub versification: Option<String>,
+    pub path: PathBuf,
+}
+
+impl TranslationInfo {
+    /// Reads the metadata of a bible file or directory in any of the `Format`s.
+    pub fn from_file(path: &Path) -> Result<TranslationInfo> {
+        let (format, _) = Format::sniff_file(path)?;
+        format.parser().info(&mut open_text(path)?, path)
+    }
+
+    /// Reads the first `<work>` in the header.  Parsing stops at the end of the header, so the
+    /// rest of the file is never read.
+    pub fn from_osis_header(source: impl BufRead, path: &Path) -> Result<TranslationInfo> {
+        let mut reader = Reader::from_reader(source);
+        let mut buf = Vec::new();
+
+        let mut work_id = None;
+        let mut lang = None;
+        let mut in_work = false;
+        let mut seen_work = false;
+        // (element, type) of the element whose text is being read.
+        let mut field: Option<(Vec<u8>, String)> = None;
+        let mut text = String::new();
+        let mut fields: Vec<(Vec<u8>, String, String)> = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(ref e)) if e.name().as_ref() == b"osisText" => {
+                    work_id = attr(e, b"osisIDWork");
+                    lang = attr(e, b"xml:lang");
+                }
+                Ok(Event::Start(ref e)) if e.name().as_ref() == b"work" && !seen_work => {
+                    in_work = true;
+                    seen_work = true;
+                    work_id = attr(e, b"osisWork").or(work_id);
+                }
+                Ok(Event::End(ref e)) if e.name().as_ref() == b"work" => in_work = false,
+                Ok(Event::Start(ref e)) if in_work => {
+                    let name = e.name().as_ref().to_vec();
+                    field = Some((name, attr(e, b"type").unwrap_or_default()));
+                    text.clear();
+                }
+                Ok(Event::Text(ref e)) if field.is_some() => {
+                    text.push_str(&String::from_utf8_lossy(e));
+                }
+                Ok(Event::GeneralRef(ref e)) if field.is_some() => {
+                    text.push_str(&resolve_entity(e));
+                }
+                Ok(Event::End(_)) if field.is_some() => {
+                    let (name, kind) = field.take().expect("Checked by guard");
+                    let value = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    fields.push((name, kind, value));
+                }
+                Ok(Event::End(ref e)) if e.name().as_ref() == b"header" => break,
+                // Not every file has a header, don't read the whole text looking for one.
+                Ok(Event::Start(ref e)) if e.name().as_ref() == b"div" => break,
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(Error::BibleIndex(e.to_string())),
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        // Prefer the given types, otherwise the first of the element.
+        let find = |name: &[u8], preferred: &[&str]| {
+            let mut candidates = fields.iter().filter(|(n, _, _)| n == name);
+            preferred
+                .iter()
+                .find_map(|p| candidates.clone().find(|(_, t, _)| t == p))
+                .or_else(|| candidates.next())
+                .map(|(_, _, value)| value.clone())
+        };
+
+        let name = work_id
+            .as_deref()
+            .and_then(|id| id.rsplit('.').next())
+            .filter(|id| !id.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| file_stem(path))
+            .to_uppercase();
+
+        Ok(TranslationInfo {
+            title: find(b"title", &[]).unwrap_or_else(|| name.clone()),
+            language: find(b"language", &["x-vernacular", "x-in-english"])
+                .or(lang)
+                .unwrap_or_default(),
+            rights: find(b"rights", &["x-copyright"]).unwrap_or_default(),
+            // "Bible" on its own does not say which system.
+            versification: find(b"refSystem", &[]).filter(|system| system != "Bible"),
+            name,
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+// "eng-kjv.osis.xml.xz" -> "eng-kjv"
+pub fn file_stem(path: &Path) -> String {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .and_then(|n| n.split('.').next())
+        .unwrap_or_default()
+        .to_string()
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The translations that are available on disk.
+#[derive(Debug, Default)]
+pub struct TranslationRegistry {
+    translations: Vec<TranslationInfo>,
+}
+
+impl TranslationRegistry {
+    /// Scans the installed translations, then the bible modules of SWORD and last the ones
+    /// bundled with the app.
+    pub fn discover() -> TranslationRegistry {
+        let installed: Vec<PathBuf> = translations_dir().into_iter().collect();
+        let mut registry = Self::scan(&installed);
+
+        let modules = sword::discover().into_iter();
+        for module in modules.filter(|m| m.kind == ModuleKind::Bible) {
+            registry.add(TranslationInfo {
+                name: module.name.clone(),
+                title: module.conf.description().to_string(),
+                language: module.conf.language().to_string(),
+                rights: module.conf.rights().to_string(),
+                versification: Some(module.conf.versification().to_string()),
+                path: module.path,
+            });
+        }
+
+        for info in Self::scan(&[PathBuf::from(BUNDLED_TRANSLATIONS_DIR)]).translations {
+            registry.add(info);
+        }
+        registry
+    }
+
+    /// Reads the headers of the Bible files in `dirs`.  When names clash, the first directory
+    /// wins.
+    pub fn scan(dirs: &[PathBuf]) -> TranslationRegistry {
+        let start = Instant::now();
+        let mut registry = TranslationRegistry::default();
+
+        for dir in dirs {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                debug!("No translations in {dir:?}");
+                continue;
+            };
+
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| is_bible_file(p))
+                .collect();
+            paths.sort();
+
+            for path in paths {
+                match TranslationInfo::from_file(&path) {
+                    Ok(info) => registry.add(info),
+                    Err(e) => warn!("Failed to read translation {path:?}: {e}"),
+                }
+            }
+        }
+
+        info!(
+            "Found {} translations in {:?}",
+            registry.translations.len(),
+            start.elapsed()
+        );
+        registry
+    }
+
+    /// Adds the translation, unless one of the same name was found before.
+    fn add(&mut self, info: TranslationInfo) {
+        if self.find(&info.name).is_some() {
+            debug!(
+                "Skipping {:?}, {} is already installed",
+                info.path, info.name
+            );
+            return;
+        }
+        info!("Found {} ({}) at {:?}", info.name, info.title, info.path);
+        self.translations.push(info);
+    }
+
+    fn find(&self, name: &str) -> Option<&TranslationInfo> {
+        self.translations
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Looks up a translation by name, ignoring case.
+    pub fn get(&self, name: &str) -> Result<&TranslationInfo> {
+        self.find(name)
+            .ok_or(Error::UnsupprtedTranslation(name.to_string()))
+    }
+
+    /// The configured default, or the first translation if it is not installed.
+    pub fn get_default(&self) -> Result<&TranslationInfo> {
+        self.get(&settings().default_translation)
+            .or_else(|e| self.translations.first().ok_or(e))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TranslationInfo> {
+        self.translations.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.translations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.translations.is_empty()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = r#"<osis><osisText osisIDWork="Bible.en.KJV" xml:lang="en"><header>
+<work osisWork="Bible.en.WEB"><title>World English Bible</title>
+<language type="x-in-english">English</language><language type="x-vernacular">Anglais</language>
+<rights type="x-license">PD</rights><rights type="x-copyright">Public &amp; free</rights></work>
+<work osisWork="other"><title>Other</title></work></header>
+<div type="book" osisID="Gen"/></osisText></osis>"#;
+
+    #[test]
+    fn reads_the_first_work_of_the_header() {
+        let path = Path::new("eng-web.osis.xml");
+        let info = TranslationInfo::from_osis_header(HEADER.as_bytes(), path).unwrap();
+        assert_eq!(info.name, "WEB");
+        assert_eq!(info.title, "World English Bible");
+        assert_eq!(info.language, "Anglais");
+        assert_eq!(info.rights, "Public & free");
+    }
+
+    #[test]
+    fn falls_back_to_the_file_name() {
+        let source = r#"<osis><osisText><div type="book" osisID="Gen"/></osisText></osis>"#;
+        let path = Path::new("/bibles/eng-kjv.osis.xml.xz");
+        let info = TranslationInfo::from_osis_header(source.as_bytes(), path).unwrap();
+        assert_eq!(
+            (info.name.as_str(), info.title.as_str()),
+            ("ENG-KJV", "ENG-KJV")
+        );
+    }
+
+    #[test]
+    fn earlier_directories_win() {
+        let root = std::env::temp_dir().join(format!("logos-{}-registry", std::process::id()));
+        let dirs = [root.join("installed"), root.join("bundled")];
+        for (dir, title) in dirs.iter().zip(["Installed", "Bundled"]) {
+            std::fs::create_dir_all(dir).unwrap();
+            let header = format!(
+                r#"<osis><osisText osisIDWork="KJV"><header><work><title>{title}</title></work></header></osisText></osis>"#
+            );
+            std::fs::write(dir.join("kjv.xml"), header).unwrap();
+        }
+        std::fs::write(dirs[1].join("web.xml"), "<osis><osisText/></osis>").unwrap();
+
+        let registry = TranslationRegistry::scan(&dirs);
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.get("kjv").unwrap().title, "Installed");
+        assert_eq!(registry.get("Web").unwrap().name, "WEB");
+        assert!(registry.get("ASV").is_err());
+    }
+}
@@ -0,0 +1,621 @@
+use crate::bible::Testament;
+use crate::canon;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A versification system, i.e. how a tradition numbers its chapters and verses.  Systems are
+/// described by how they differ from the KJV, so that references can be converted between any
+/// two of them by going through the KJV.
+///
+/// Only the differences that move verses between chapters and the numbering of the Psalms are
+/// known, verses that are split or merged in one place are not.
+#[derive(Debug)]
+pub struct Versification {
+    /// Name as used by SWORD, e.g. "KJV".
+    pub name: &'static str,
+    /// Other names of the system, e.g. in the `refSystem` of OSIS files.
+    aliases: &'static [&'static str],
+    /// OSIS IDs with the number of verses of each chapter, empty when they are not known.  Read
+    /// through `books()`, which has the `changed_books` in their place.
+    books: &'static [(&'static str, &'static [u16])],
+    /// Books whose chapters have other numbers of verses than in `books`.
+    changed_books: &'static [(&'static str, &'static [u16])],
+    /// Books of the system whose verses are not known, so they are not in `books`.
+    missing_books: &'static [&'static str],
+    psalms: Psalms,
+    /// Verses that are in another chapter or at another number than in the KJV.
+    shifts: &'static [Shift],
+}
+
+static SYSTEMS: &[Versification] = &[
+    Versification {
+        name: "KJV",
+        aliases: &["Protestant"],
+        books: KJV,
+        changed_books: &[],
+        missing_books: &[],
+        psalms: Psalms::Kjv,
+        shifts: &[],
+    },
+    Versification {
+        name: "KJVA",
+        aliases: &[],
+        // The apocrypha come after Malachi, so the layout of the other books is the KJV's.
+        books: KJV,
+        changed_books: &[],
+        missing_books: &[
+            "1Esd", "2Esd", "Tob", "Jdt", "EsthGr", "Wis", "Sir", "Bar", "PrAzar", "Sus", "Bel",
+            "PrMan", "1Macc", "2Macc",
+        ],
+        psalms: Psalms::Kjv,
+        shifts: &[],
+    },
+    Versification {
+        name: "NRSV",
+        aliases: &[],
+        books: KJV,
+        changed_books: NRSV,
+        missing_books: &[],
+        psalms: Psalms::Kjv,
+        shifts: &[],
+    },
+    Versification {
+        name: "Leningrad",
+        aliases: &["Hebrew", "MT", "BHS", "WLC"],
+        books: &[],
+        changed_books: &[],
+        missing_books: &[],
+        psalms: Psalms::Hebrew,
+        shifts: HEBREW,
+    },
+    Versification {
+        name: "German",
+        aliases: &["Luther"],
+        books: &[],
+        changed_books: &[],
+        missing_books: &[],
+        psalms: Psalms::Hebrew,
+        shifts: &[],
+    },
+    Versification {
+        name: "Vulg",
+        aliases: &["Vulgate"],
+        books: &[],
+        changed_books: &[],
+        missing_books: &[],
+        psalms: Psalms::Greek,
+        shifts: &[],
+    },
+    Versification {
+        name: "Synodal",
+        aliases: &["SynodalProt", "Russian"],
+        books: &[],
+        changed_books: &[],
+        missing_books: &[],
+        psalms: Psalms::Greek,
+        shifts: SYNODAL,
+    },
+    Versification {
+        name: "LXX",
+        aliases: &["Septuagint"],
+        books: &[],
+        changed_books: &[],
+        missing_books: &[],
+        psalms: Psalms::Greek,
+        shifts: &[],
+    },
+];
+
+impl Versification {
+    /// The system of most English bibles, used to key anything that is attached to verses.
+    pub fn kjv() -> &'static Versification {
+        &SYSTEMS[0]
+    }
+
+    /// Looks up a system by name or alias, ignoring case.  OSIS names like "Bible.KJV" work too.
+    pub fn from_name(name: &str) -> Option<&'static Versification> {
+        let name = name.trim();
+        let name = name.strip_prefix("Bible.").unwrap_or(name);
+        SYSTEMS.iter().find(|v| {
+            std::iter::once(&v.name)
+                .chain(v.aliases)
+                .any(|n| n.eq_ignore_ascii_case(name))
+        })
+    }
+
+    /// OSIS IDs with the number of verses of each chapter, in the order of SWORD modules.  Empty
+    /// when they are not known.
+    pub fn books(&self) -> impl Iterator<Item = &'static (&'static str, &'static [u16])> + use<> {
+        let changed = self.changed_books;
+        self.books
+            .iter()
+            .map(move |book| changed.iter().find(|(id, _)| *id == book.0).unwrap_or(book))
+    }
+
+    /// Books of the system that are not in `books`, since their verses are not known.
+    pub fn missing_books(&self) -> &'static [&'static str] {
+        self.missing_books
+    }
+
+    /// The number of verses of each chapter of the book, `None` if the system does not have it.
+    pub fn chapters(&self, book: &str) -> Option<&'static [u16]> {
+        self.books()
+            .find(|(id, _)| *id == book)
+            .map(|(_, chapters)| *chapters)
+    }
+
+    /// The books of the Old Testament (with any apocrypha) or the New Testament, which SWORD
+    /// keeps in separate files.
+    pub fn testament(
+        &self,
+        new: bool,
+    ) -> impl Iterator<Item = &'static (&'static str, &'static [u16])> + use<> {
+        self.books().filter(move |(id, _)| {
+            let testament = canon::book_info(id).map(|b| b.testament);
+            (testament == Some(Testament::New)) == new
+        })
+    }
+
+    /// The (chapter, verse) of this system that a verse of the KJV is at.
+    pub fn map_from_kjv(&self, book: &str, chapter: usize, verse: usize) -> (usize, usize) {
+        if book == "Ps" {
+            return self.psalms.map_from_kjv(chapter, verse);
+        }
+        self.shifts
+            .iter()
+            .filter(|s| s.book == book)
+            .find_map(|s| s.map_from_kjv(chapter, verse))
+            .unwrap_or((chapter, verse))
+    }
+
+    /// The (chapter, verse) of the KJV that a verse of this system is at.
+    pub fn map_to_kjv(&self, book: &str, chapter: usize, verse: usize) -> (usize, usize) {
+        if book == "Ps" {
+            return self.psalms.map_to_kjv(chapter, verse);
+        }
+        self.shifts
+            .iter()
+            .filter(|s| s.book == book)
+            .find_map(|s| s.map_to_kjv(chapter, verse))
+            .unwrap_or((chapter, verse))
+    }
+
+    /// Converts a verse of this system to the (chapter, verse) of `other`.
+    pub fn convert(
+        &self,
+        other: &Versification,
+        book: &str,
+        chapter: usize,
+        verse: usize,
+    ) -> (usize, usize) {
+        if std::ptr::eq(self, other) {
+            return (chapter, verse);
+        }
+        let (chapter, verse) = self.map_to_kjv(book, chapter, verse);
+        other.map_from_kjv(book, chapter, verse)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// How a system numbers the Psalms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Psalms {
+    /// The titles are part of the first verse.
+    Kjv,
+    /// The titles are verses of their own, see `PSALM_TITLES`.
+    Hebrew,
+    /// Like `Hebrew`, with the psalms numbered as in the Septuagint, e.g. Psalm 23 is 22.
+    Greek,
+}
+
+impl Psalms {
+    fn map_from_kjv(self, chapter: usize, verse: usize) -> (usize, usize) {
+        if self == Psalms::Kjv {
+            return (chapter, verse);
+        }
+        let verse = verse + title_verses(chapter);
+        match self {
+            Psalms::Greek => hebrew_to_greek(chapter, verse),
+            _ => (chapter, verse),
+        }
+    }
+
+    fn map_to_kjv(self, chapter: usize, verse: usize) -> (usize, usize) {
+        let (chapter, verse) = match self {
+            Psalms::Kjv => return (chapter, verse),
+            Psalms::Hebrew => (chapter, verse),
+            Psalms::Greek => greek_to_hebrew(chapter, verse),
+        };
+        // Titles are verse 0, like the headings of SWORD.
+        (chapter, verse.saturating_sub(title_verses(chapter)))
+    }
+}
+
+/// Psalms whose titles are verses in the Hebrew numbering, with how many verses they take.
+#[rustfmt::skip]
+const PSALM_TITLES: &[(usize, usize)] = &[
+    (3, 1), (4, 1), (5, 1), (6, 1), (7, 1), (8, 1), (9, 1), (12, 1), (13, 1), (18, 1), (19, 1),
+    (20, 1), (21, 1), (22, 1), (30, 1), (31, 1), (34, 1), (36, 1), (38, 1), (39, 1), (40, 1),
+    (41, 1), (42, 1), (44, 1), (45, 1), (46, 1), (47, 1), (48, 1), (49, 1), (51, 2), (52, 2),
+    (53, 1), (54, 2), (55, 1), (56, 1), (57, 1), (58, 1), (59, 1), (60, 2), (61, 1), (62, 1),
+    (63, 1), (64, 1), (65, 1), (67, 1), (68, 1), (69, 1), (70, 1), (75, 1), (76, 1), (77, 1),
+    (80, 1), (81, 1), (83, 1), (84, 1), (85, 1), (88, 1), (89, 1), (92, 1), (102, 1), (108, 1),
+    (140, 1), (142, 1),
+];
+
+fn title_verses(psalm: usize) -> usize {
+    PSALM_TITLES
+        .iter()
+        .find(|(p, _)| *p == psalm)
+        .map_or(0, |(_, verses)| *verses)
+}
+
+/// The Septuagint joins 9 and 10, and 114 and 115, and splits 116 and 147.
+fn hebrew_to_greek(chapter: usize, verse: usize) -> (usize, usize) {
+    match chapter {
+        10 => (9, verse + 21),
+        11..=113 => (chapter - 1, verse),
+        114 => (113, verse),
+        115 => (113, verse + 8),
+        116 if verse <= 9 => (114, verse),
+        116 => (115, verse - 9),
+        117..=146 => (chapter - 1, verse),
+        147 if verse <= 11 => (146, verse),
+        147 => (147, verse - 11),
+        _ => (chapter, verse),
+    }
+}
+
+fn greek_to_hebrew(chapter: usize, verse: usize) -> (usize, usize) {
+    match chapter {
+        9 if verse > 21 => (10, verse - 21),
+        10..=112 => (chapter + 1, verse),
+        113 if verse <= 8 => (114, verse),
+        113 => (115, verse - 8),
+        114 => (116, verse),
+        115 => (116, verse + 9),
+        116..=145 => (chapter + 1, verse),
+        146 => (147, verse),
+        147 => (147, verse + 11),
+        _ => (chapter, verse),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A run of verses of the KJV that starts at another chapter and verse in a system.
+#[derive(Debug)]
+struct Shift {
+    book: &'static str,
+    chapter: usize,
+    first: usize,
+    last: usize,
+    to_chapter: usize,
+    to_first: usize,
+}
+
+/// `book` (chapter, first, last) of the KJV -> (chapter, first) of the system.
+const fn shift(book: &'static str, kjv: (usize, usize, usize), to: (usize, usize)) -> Shift {
+    Shift {
+        book,
+        chapter: kjv.0,
+        first: kjv.1,
+        last: kjv.2,
+        to_chapter: to.0,
+        to_first: to.1,
+    }
+}
+
+impl Shift {
+    fn map_from_kjv(&self, chapter: usize, verse: usize) -> Option<(usize, usize)> {
+        (chapter == self.chapter && (self.first..=self.last).contains(&verse))
+            .then(|| (self.to_chapter, verse - self.first + self.to_first))
+    }
+
+    fn map_to_kjv(&self, chapter: usize, verse: usize) -> Option<(usize, usize)> {
+        let to_last = self.to_first + self.last - self.first;
+        (chapter == self.to_chapter && (self.to_first..=to_last).contains(&verse))
+            .then(|| (self.chapter, verse - self.to_first + self.first))
+    }
+}
+
+/// The Hebrew bible, e.g. Malachi 4 is 3:19-24.
+#[rustfmt::skip]
+static HEBREW: &[Shift] = &[
+    shift("Gen", (31, 55, 55), (32, 1)), shift("Gen", (32, 1, 32), (32, 2)),
+    shift("Exod", (8, 1, 4), (7, 26)), shift("Exod", (8, 5, 32), (8, 1)),
+    shift("Exod", (22, 1, 1), (21, 37)), shift("Exod", (22, 2, 31), (22, 1)),
+    shift("Lev", (6, 1, 7), (5, 20)), shift("Lev", (6, 8, 30), (6, 1)),
+    shift("Num", (16, 36, 50), (17, 1)), shift("Num", (17, 1, 13), (17, 16)),
+    shift("Num", (29, 40, 40), (30, 1)), shift("Num", (30, 1, 16), (30, 2)),
+    shift("Deut", (12, 32, 32), (13, 1)), shift("Deut", (13, 1, 18), (13, 2)),
+    shift("Deut", (22, 30, 30), (23, 1)), shift("Deut", (23, 1, 25), (23, 2)),
+    shift("Deut", (29, 1, 1), (28, 69)), shift("Deut", (29, 2, 29), (29, 1)),
+    shift("1Sam", (21, 1, 15), (21, 2)),
+    shift("1Sam", (23, 29, 29), (24, 1)), shift("1Sam", (24, 1, 22), (24, 2)),
+    shift("2Sam", (18, 33, 33), (19, 1)), shift("2Sam", (19, 1, 43), (19, 2)),
+    shift("1Kgs", (4, 21, 34), (5, 1)), shift("1Kgs", (5, 1, 18), (5, 15)),
+    shift("2Kgs", (11, 21, 21), (12, 1)), shift("2Kgs", (12, 1, 21), (12, 2)),
+    shift("1Chr", (6, 1, 15), (5, 27)), shift("1Chr", (6, 16, 81), (6, 1)),
+    shift("2Chr", (2, 1, 1), (1, 18)), shift("2Chr", (2, 2, 18), (2, 1)),
+    shift("2Chr", (14, 1, 1), (13, 23)), shift("2Chr", (14, 2, 15), (14, 1)),
+    shift("Neh", (4, 1, 6), (3, 33)), shift("Neh", (4, 7, 23), (4, 1)),
+    shift("Neh", (9, 38, 38), (10, 1)), shift("Neh", (10, 1, 39), (10, 2)),
+    shift("Job", (41, 1, 8), (40, 25)), shift("Job", (41, 9, 34), (41, 1)),
+    shift("Eccl", (5, 1, 1), (4, 17)), shift("Eccl", (5, 2, 20), (5, 1)),
+    shift("Song", (6, 13, 13), (7, 1)), shift("Song", (7, 1, 13), (7, 2)),
+    shift("Isa", (9, 1, 1), (8, 23)), shift("Isa", (9, 2, 21), (9, 1)),
+    shift("Isa", (64, 2, 12), (64, 1)),
+    shift("Jer", (9, 1, 1), (8, 23)), shift("Jer", (9, 2, 26), (9, 1)),
+    shift("Ezek", (20, 45, 49), (21, 1)), shift("Ezek", (21, 1, 32), (21, 6)),
+    shift("Dan", (4, 1, 3), (3, 31)), shift("Dan", (4, 4, 37), (4, 1)),
+    shift("Dan", (5, 31, 31), (6, 1)), shift("Dan", (6, 1, 28), (6, 2)),
+    shift("Hos", (1, 10, 11), (2, 1)), shift("Hos", (2, 1, 23), (2, 3)),
+    shift("Hos", (11, 12, 12), (12, 1)), shift("Hos", (12, 1, 14), (12, 2)),
+    shift("Hos", (13, 16, 16), (14, 1)), shift("Hos", (14, 1, 9), (14, 2)),
+    shift("Joel", (2, 28, 32), (3, 1)), shift("Joel", (3, 1, 21), (4, 1)),
+    shift("Jonah", (1, 17, 17), (2, 1)), shift("Jonah", (2, 1, 10), (2, 2)),
+    shift("Mic", (5, 1, 1), (4, 14)), shift("Mic", (5, 2, 15), (5, 1)),
+    shift("Nah", (1, 15, 15), (2, 1)), shift("Nah", (2, 1, 13), (2, 2)),
+    shift("Zech", (1, 18, 21), (2, 1)), shift("Zech", (2, 1, 13), (2, 5)),
+    shift("Mal", (4, 1, 6), (3, 19)),
+];
+
+/// The Russian Synodal, which follows the Septuagint in the Psalms.
+static SYNODAL: &[Shift] = &[shift("Rom", (16, 25, 27), (14, 24))];
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The KJV, also used by most SWORD modules.
+#[rustfmt::skip]
+static KJV: &[(&str, &[u16])] = &[
+    ("Gen", &[
+        31, 25, 24, 26, 32, 22, 24, 22, 29, 32, 32, 20, 18, 24, 21, 16, 27, 33, 38, 18, 34, 24, 20,
+        67, 34, 35, 46, 22, 35, 43, 55, 32, 20, 31, 29, 43, 36, 30, 23, 23, 57, 38, 34, 34, 28, 34,
+        31, 22, 33, 26
+    ]),
+    ("Exod", &[
+        22, 25, 22, 31, 23, 30, 25, 32, 35, 29, 10, 51, 22, 31, 27, 36, 16, 27, 25, 26, 36, 31, 33,
+        18, 40, 37, 21, 43, 46, 38, 18, 35, 23, 35, 35, 38, 29, 31, 43, 38
+    ]),
+    ("Lev", &[
+        17, 16, 17, 35, 19, 30, 38, 36, 24, 20, 47, 8, 59, 57, 33, 34, 16, 30, 37, 27, 24, 33, 44,
+        23, 55, 46, 34
+    ]),
+    ("Num", &[
+        54, 34, 51, 49, 31, 27, 89, 26, 23, 36, 35, 16, 33, 45, 41, 50, 13, 32, 22, 29, 35, 41, 30,
+        25, 18, 65, 23, 31, 40, 16, 54, 42, 56, 29, 34, 13
+    ]),
+    ("Deut", &[
+        46, 37, 29, 49, 33, 25, 26, 20, 29, 22, 32, 32, 18, 29, 23, 22, 20, 22, 21, 20, 23, 30, 25,
+        22, 19, 19, 26, 68, 29, 20, 30, 52, 29, 12
+    ]),
+    ("Josh", &[
+        18, 24, 17, 24, 15, 27, 26, 35, 27, 43, 23, 24, 33, 15, 63, 10, 18, 28, 51, 9, 45, 34, 16,
+        33
+    ]),
+    ("Judg", &[36, 23, 31, 24, 31, 40, 25, 35, 57, 18, 40, 15, 25, 20, 20, 31, 13, 31, 30, 48, 25]),
+    ("Ruth", &[22, 23, 18, 22]),
+    ("1Sam", &[
+        28, 36, 21, 22, 12, 21, 17, 22, 27, 27, 15, 25, 23, 52, 35, 23, 58, 30, 24, 42, 15, 23, 29,
+        22, 44, 25, 12, 25, 11, 31, 13
+    ]),
+    ("2Sam", &[
+        27, 32, 39, 12, 25, 23, 29, 18, 13, 19, 27, 31, 39, 33, 37, 23, 29, 33, 43, 26, 22, 51, 39,
+        25
+    ]),
+    ("1Kgs", &[
+        53, 46, 28, 34, 18, 38, 51, 66, 28, 29, 43, 33, 34, 31, 34, 34, 24, 46, 21, 43, 29, 53
+    ]),
+    ("2Kgs", &[
+        18, 25, 27, 44, 27, 33, 20, 29, 37, 36, 21, 21, 25, 29, 38, 20, 41, 37, 37, 21, 26, 20, 37,
+        20, 30
+    ]),
+    ("1Chr", &[
+        54, 55, 24, 43, 26, 81, 40, 40, 44, 14, 47, 40, 14, 17, 29, 43, 27, 17, 19, 8, 30, 19, 32,
+        31, 31, 32, 34, 21, 30
+    ]),
+    ("2Chr", &[
+        17, 18, 17, 22, 14, 42, 22, 18, 31, 19, 23, 16, 22, 15, 19, 14, 19, 34, 11, 37, 20, 12, 21,
+        27, 28, 23, 9, 27, 36, 27, 21, 33, 25, 33, 27, 23
+    ]),
+    ("Ezra", &[11, 70, 13, 24, 17, 22, 28, 36, 15, 44]),
+    ("Neh", &[11, 20, 32, 23, 19, 19, 73, 18, 38, 39, 36, 47, 31]),
+    ("Esth", &[22, 23, 15, 17, 14, 14, 10, 17, 32, 3]),
+    ("Job", &[
+        22, 13, 26, 21, 27, 30, 21, 22, 35, 22, 20, 25, 28, 22, 35, 22, 16, 21, 29, 29, 34, 30, 17,
+        25, 6, 14, 23, 28, 25, 31, 40, 22, 33, 37, 16, 33, 24, 41, 30, 24, 34, 17
+    ]),
+    ("Ps", &[
+        6, 12, 8, 8, 12, 10, 17, 9, 20, 18, 7, 8, 6, 7, 5, 11, 15, 50, 14, 9, 13, 31, 6, 10, 22, 12,
+        14, 9, 11, 12, 24, 11, 22, 22, 28, 12, 40, 22, 13, 17, 13, 11, 5, 26, 17, 11, 9, 14, 20, 23,
+        19, 9, 6, 7, 23, 13, 11, 11, 17, 12, 8, 12, 11, 10, 13, 20, 7, 35, 36, 5, 24, 20, 28, 23,
+        10, 12, 20, 72, 13, 19, 16, 8, 18, 12, 13, 17, 7, 18, 52, 17, 16, 15, 5, 23, 11, 13, 12, 9,
+        9, 5, 8, 28, 22, 35, 45, 48, 43, 13, 31, 7, 10, 10, 9, 8, 18, 19, 2, 29, 176, 7, 8, 9, 4, 8,
+        5, 6, 5, 6, 8, 8, 3, 18, 3, 3, 21, 26, 9, 8, 24, 13, 10, 7, 12, 15, 21, 10, 20, 14, 9, 6
+    ]),
+    ("Prov", &[
+        33, 22, 35, 27, 23, 35, 27, 36, 18, 32, 31, 28, 25, 35, 33, 33, 28, 24, 29, 30, 31, 29, 35,
+        34, 28, 28, 27, 28, 27, 33, 31
+    ]),
+    ("Eccl", &[18, 26, 22, 16, 20, 12, 29, 17, 18, 20, 10, 14]),
+    ("Song", &[17, 17, 11, 16, 16, 13, 13, 14]),
+    ("Isa", &[
+        31, 22, 26, 6, 30, 13, 25, 22, 21, 34, 16, 6, 22, 32, 9, 14, 14, 7, 25, 6, 17, 25, 18, 23,
+        12, 21, 13, 29, 24, 33, 9, 20, 24, 17, 10, 22, 38, 22, 8, 31, 29, 25, 28, 28, 25, 13, 15,
+        22, 26, 11, 23, 15, 12, 17, 13, 12, 21, 14, 21, 22, 11, 12, 19, 12, 25, 24
+    ]),
+    ("Jer", &[
+        19, 37, 25, 31, 31, 30, 34, 22, 26, 25, 23, 17, 27, 22, 21, 21, 27, 23, 15, 18, 14, 30, 40,
+        10, 38, 24, 22, 17, 32, 24, 40, 44, 26, 22, 19, 32, 21, 28, 18, 16, 18, 22, 13, 30, 5, 28,
+        7, 47, 39, 46, 64, 34
+    ]),
+    ("Lam", &[22, 22, 66, 22, 22]),
+    ("Ezek", &[
+        28, 10, 27, 17, 17, 14, 27, 18, 11, 22, 25, 28, 23, 23, 8, 63, 24, 32, 14, 49, 32, 31, 49,
+        27, 17, 21, 36, 26, 21, 26, 18, 32, 33, 31, 15, 38, 28, 23, 29, 49, 26, 20, 27, 31, 25, 24,
+        23, 35
+    ]),
+    ("Dan", &[21, 49, 30, 37, 31, 28, 28, 27, 27, 21, 45, 13]),
+    ("Hos", &[11, 23, 5, 19, 15, 11, 16, 14, 17, 15, 12, 14, 16, 9]),
+    ("Joel", &[20, 32, 21]),
+    ("Amos", &[15, 16, 15, 13, 27, 14, 17, 14, 15]),
+    ("Obad", &[21]),
+    ("Jonah", &[17, 10, 10, 11]),
+    ("Mic", &[16, 13, 12, 13, 15, 16, 20]),
+    ("Nah", &[15, 13, 19]),
+    ("Hab", &[17, 20, 19]),
+    ("Zeph", &[18, 15, 20]),
+    ("Hag", &[15, 23]),
+    ("Zech", &[21, 13, 10, 14, 11, 15, 14, 23, 17, 12, 17, 14, 9, 21]),
+    ("Mal", &[14, 17, 18, 6]),
+    ("Matt", &[
+        25, 23, 17, 25, 48, 34, 29, 34, 38, 42, 30, 50, 58, 36, 39, 28, 27, 35, 30, 34, 46, 46, 39,
+        51, 46, 75, 66, 20
+    ]),
+    ("Mark", &[45, 28, 35, 41, 43, 56, 37, 38, 50, 52, 33, 44, 37, 72, 47, 20]),
+    ("Luke", &[
+        80, 52, 38, 44, 39, 49, 50, 56, 62, 42, 54, 59, 35, 35, 32, 31, 37, 43, 48, 47, 38, 71, 56,
+        53
+    ]),
+    ("John", &[51, 25, 36, 54, 47, 71, 53, 59, 41, 42, 57, 50, 38, 31, 27, 33, 26, 40, 42, 31, 25]),
+    ("Acts", &[
+        26, 47, 26, 37, 42, 15, 60, 40, 43, 48, 30, 25, 52, 28, 41, 40, 34, 28, 41, 38, 40, 30, 35,
+        27, 27, 32, 44, 31
+    ]),
+    ("Rom", &[32, 29, 31, 25, 21, 23, 25, 39, 33, 21, 36, 21, 14, 23, 33, 27]),
+    ("1Cor", &[31, 16, 23, 21, 13, 20, 40, 13, 27, 33, 34, 31, 13, 40, 58, 24]),
+    ("2Cor", &[24, 17, 18, 18, 21, 18, 16, 24, 15, 18, 33, 21, 14]),
+    ("Gal", &[24, 21, 29, 31, 26, 18]),
+    ("Eph", &[23, 22, 21, 32, 33, 24]),
+    ("Phil", &[30, 30, 21, 23]),
+    ("Col", &[29, 23, 25, 18]),
+    ("1Thess", &[10, 20, 13, 18, 28]),
+    ("2Thess", &[12, 17, 18]),
+    ("1Tim", &[20, 15, 16, 16, 25, 21]),
+    ("2Tim", &[18, 26, 17, 22]),
+    ("Titus", &[16, 15, 15]),
+    ("Phlm", &[25]),
+    ("Heb", &[14, 18, 19, 16, 14, 20, 28, 13, 28, 39, 40, 29, 25]),
+    ("Jas", &[27, 26, 18, 17, 20]),
+    ("1Pet", &[25, 25, 22, 19, 14]),
+    ("2Pet", &[21, 22, 18]),
+    ("1John", &[10, 29, 24, 21, 21]),
+    ("2John", &[13]),
+    ("3John", &[14]),
+    ("Jude", &[25]),
+    ("Rev", &[
+        20, 29, 22, 11, 14, 17, 17, 13, 21, 11, 19, 17, 18, 20, 8, 21, 18, 24, 21, 15, 27, 21
+    ]),
+];
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The NRSV splits 3 John 1:14 and moves the start of Revelation 13 to 12:18.
+#[rustfmt::skip]
+static NRSV: &[(&str, &[u16])] = &[
+    ("3John", &[15]),
+    ("Rev", &[
+        20, 29, 22, 11, 14, 17, 17, 13, 21, 11, 19, 18, 18, 20, 8, 21, 18, 24, 21, 15, 27, 21
+    ]),
+];
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn system(name: &str) -> &'static Versification {
+        Versification::from_name(name).unwrap()
+    }
+
+    /// Every verse of the book in the KJV.
+    fn kjv_verses(book: &str) -> impl Iterator<Item = (usize, usize)> {
+        let chapters = Versification::kjv().chapters(book).unwrap();
+        chapters
+            .iter()
+            .enumerate()
+            .flat_map(|(i, verses)| (1..=*verses as usize).map(move |v| (i + 1, v)))
+    }
+
+    #[test]
+    fn counts_the_verses_of_the_kjv() {
+        let kjv = Versification::from_name("kjv").unwrap();
+        assert_eq!(kjv.books.len(), 66);
+        assert_eq!(kjv.chapters("Gen").map(<[u16]>::len), Some(50));
+        assert_eq!(kjv.chapters("3John"), Some(&[14][..]));
+        assert_eq!(kjv.chapters("Tob"), None);
+        assert_eq!(kjv.testament(false).count(), 39);
+        assert_eq!(kjv.testament(true).next().map(|b| b.0), Some("Matt"));
+        assert!(Versification::from_name("Vulgate").is_some());
+        assert!(Versification::from_name("Nope").is_none());
+    }
+
+    #[test]
+    fn maps_known_verses() {
+        let (hebrew, greek, synodal) = (system("Leningrad"), system("Vulg"), system("Synodal"));
+        assert_eq!(hebrew.map_from_kjv("Mal", 4, 1), (3, 19));
+        assert_eq!(hebrew.map_to_kjv("Mal", 3, 19), (4, 1));
+        assert_eq!(hebrew.map_from_kjv("Mal", 4, 6), (3, 24));
+        assert_eq!(greek.map_from_kjv("Ps", 23, 1), (22, 1));
+        assert_eq!(greek.map_to_kjv("Ps", 22, 1), (23, 1));
+        assert_eq!(hebrew.map_from_kjv("Ps", 51, 1), (51, 3));
+        assert_eq!(hebrew.map_to_kjv("Ps", 51, 3), (51, 1));
+        // The title is verse 0 of the KJV.
+        assert_eq!(hebrew.map_to_kjv("Ps", 51, 1), (51, 0));
+        assert_eq!(greek.map_from_kjv("Ps", 147, 12), (147, 1));
+        assert_eq!(greek.map_to_kjv("Ps", 147, 1), (147, 12));
+        assert_eq!(greek.map_from_kjv("Ps", 147, 11), (146, 11));
+        assert_eq!(synodal.map_from_kjv("Rom", 16, 25), (14, 24));
+        assert_eq!(synodal.map_to_kjv("Rom", 14, 24), (16, 25));
+        assert_eq!(synodal.map_from_kjv("Rom", 16, 24), (16, 24));
+        assert_eq!(hebrew.convert(greek, "Ps", 51, 3), (50, 3));
+        assert_eq!(system("NRSV").convert(hebrew, "Mal", 4, 1), (3, 19));
+    }
+
+    #[test]
+    fn psalms_round_trip() {
+        for psalms in [Psalms::Hebrew, Psalms::Greek] {
+            for (chapter, verse) in kjv_verses("Ps") {
+                let (c, v) = psalms.map_from_kjv(chapter, verse);
+                assert_eq!(
+                    psalms.map_to_kjv(c, v),
+                    (chapter, verse),
+                    "{psalms:?} {c}:{v}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn greek_psalms_round_trip() {
+        for (chapter, verse) in kjv_verses("Ps") {
+            let (chapter, verse) = Psalms::Hebrew.map_from_kjv(chapter, verse);
+            let (c, v) = hebrew_to_greek(chapter, verse);
+            assert_eq!(greek_to_hebrew(c, v), (chapter, verse), "{chapter}:{verse}");
+        }
+    }
+
+    #[test]
+    fn hebrew_shifts_round_trip() {
+        let hebrew = system("Leningrad");
+        let mut books: Vec<&str> = HEBREW.iter().map(|s| s.book).collect();
+        books.dedup();
+        for book in books {
+            for (chapter, verse) in kjv_verses(book) {
+                // The end of 63:19 in the Hebrew bible, which has no verse of its own.
+                if (book, chapter, verse) == ("Isa", 64, 1) {
+                    continue;
+                }
+                let (c, v) = hebrew.map_from_kjv(book, chapter, verse);
+                assert_eq!(
+                    hebrew.map_to_kjv(book, c, v),
+                    (chapter, verse),
+                    "{book} {chapter}:{verse} is {c}:{v}"
+                );
+            }
+        }
+    }
+}